@@ -13,6 +13,11 @@ pub enum Action {
         start: Option<Store>,
         end: Option<Store>,
     },
+    // a single action so that a crash can't leave the entry deleted but not reinserted
+    Rename {
+        from: String,
+        to: String,
+    },
     Noop,
 }
 
@@ -44,6 +49,7 @@ impl Action {
                 start: end,
                 end: start,
             },
+            Self::Rename { from, to } => Self::Rename { from: to, to: from },
         }
     }
 }
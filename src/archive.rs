@@ -0,0 +1,313 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use aes_gcm::aead::OsRng;
+use argon2::password_hash::SaltString;
+use clap::ValueEnum;
+use enum_iterator::Sequence;
+use serde::Deserialize;
+
+use crate::{
+    errors::ArchiveError,
+    secure::{Decrypted, Encrypted, KdfParams, SecureData},
+    utils,
+    vault::{encrypted::PasswordEncrypted, UpdatedVault, Vault},
+    Password,
+};
+
+// marks the file as a pants archive, followed by the format version
+pub const MAGIC: &[u8; 8] = b"PANTSARC";
+// 2 added the key derivation costs
+pub const VERSION: u16 = 2;
+pub const EXTENSION: &str = "pantsarc";
+
+// every vault in the archive by name, encrypted together under the archive's own password
+pub type ArchiveEncrypted = PasswordEncrypted<BTreeMap<String, Vault>>;
+
+// archives from before the key derivation costs were stored, which always used the defaults
+#[derive(Deserialize)]
+struct ArchiveV1 {
+    data: Encrypted<BTreeMap<String, Vault>>,
+    salt: String,
+}
+
+// what to do when restoring a vault that already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Sequence, ValueEnum)]
+pub enum Collision {
+    // merge, keeping the existing entries
+    #[default]
+    Skip,
+    // merge, replacing the existing entries
+    Overwrite,
+    // restore next to the existing vault under a new name
+    Rename,
+}
+
+impl Display for Collision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "Skip existing entries"),
+            Self::Overwrite => write!(f, "Overwrite existing entries"),
+            Self::Rename => write!(f, "Restore as new vaults"),
+        }
+    }
+}
+
+// a fresh salt for every archive so it shares nothing with the vaults it came from, the
+// vaults are only ever in memory before being encrypted
+pub fn write(
+    path: &Path,
+    vaults: &BTreeMap<String, Vault>,
+    password: Password,
+    params: KdfParams,
+) -> anyhow::Result<()> {
+    let salt = SaltString::generate(&mut OsRng).to_string();
+    let key = ArchiveEncrypted::get_key(&salt, &params, password);
+    let archive = ArchiveEncrypted {
+        data: Encrypted::encrypt(vaults, key)?,
+        salt,
+        params,
+    };
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend(VERSION.to_le_bytes());
+    bytes.extend(bincode::serialize(&archive)?);
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+pub fn read(path: &Path) -> anyhow::Result<ArchiveEncrypted> {
+    let bytes = fs::read(path)?;
+    let body = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .ok_or(ArchiveError::NotArchive)?;
+    if body.len() < 2 {
+        return Err(ArchiveError::NotArchive.into());
+    }
+    let (version, body) = body.split_at(2);
+    let version = u16::from_le_bytes([version[0], version[1]]);
+    match version {
+        1 => bincode::deserialize::<ArchiveV1>(body)
+            .map(|archive| ArchiveEncrypted {
+                data: archive.data,
+                salt: archive.salt,
+                params: KdfParams::default(),
+            })
+            .map_err(|_| ArchiveError::NotArchive.into()),
+        VERSION => bincode::deserialize(body).map_err(|_| ArchiveError::NotArchive.into()),
+        _ => Err(ArchiveError::UnsupportedVersion(version).into()),
+    }
+}
+
+// the vaults keep the layout they had when the archive was written, newest first like a vault
+pub fn contents(decrypted: &Decrypted<BTreeMap<String, Vault>>) -> Option<BTreeMap<String, Vault>> {
+    decrypted
+        .try_deserialize_as::<BTreeMap<String, Vault>>()
+        .or_else(|| {
+            decrypted
+                .try_deserialize_as::<BTreeMap<String, UpdatedVault>>()
+                .map(|vaults| {
+                    vaults
+                        .into_iter()
+                        .map(|(name, vault)| (name, vault.into()))
+                        .collect()
+                })
+        })
+}
+
+// reads the archive back and decodes every vault in it, so it's known to restore before relying
+// on it
+pub fn verify(path: &Path, password: Password, vaults: &[String]) -> Result<(), ArchiveError> {
+    let archive = read(path).map_err(|_| ArchiveError::Unverified)?;
+    let decrypted = archive
+        .decrypt(archive.key(password))
+        .map_err(|_| ArchiveError::Unverified)?;
+    let contents = contents(&decrypted).ok_or(ArchiveError::Unverified)?;
+    if contents.keys().eq(vaults.iter()) {
+        Ok(())
+    } else {
+        Err(ArchiveError::Unverified)
+    }
+}
+
+// automatic backups are named by vault and time so they sort oldest first
+pub fn timestamped_path(dir: &Path, vault: &str) -> PathBuf {
+    dir.join(format!(
+        "{}-{}.{}",
+        vault,
+        utils::format_date(utils::now()),
+        EXTENSION
+    ))
+}
+
+// the automatic backups of the vault, oldest first
+pub fn timestamped_all(dir: &Path, vault: &str) -> Vec<(PathBuf, String)> {
+    let prefix = format!("{}-", vault);
+    let suffix = format!(".{}", EXTENSION);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut files = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let date = name.strip_prefix(&prefix)?.strip_suffix(&suffix)?;
+            utils::read_date(date).ok()?;
+            Some((entry.path(), date.to_string()))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    files
+}
+
+pub fn backup_due(dir: &Path, vault: &str, interval_hours: u64) -> bool {
+    match timestamped_all(dir, vault).last() {
+        None => true,
+        Some((_, date)) => utils::read_date(date)
+            .map(|date| {
+                utils::now().signed_duration_since(date).num_hours() >= interval_hours as i64
+            })
+            .unwrap_or(true),
+    }
+}
+
+// drop the oldest automatic backups past the number to keep
+pub fn rotate(dir: &Path, vault: &str, keep: usize) -> anyhow::Result<()> {
+    let files = timestamped_all(dir, vault);
+    let extra = files.len().saturating_sub(keep);
+    for (path, _) in files.into_iter().take(extra) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::ExposeSecret;
+
+    use super::*;
+    use crate::store::Store;
+
+    // as cheap as argon2 allows, the costs aren't what's being tested
+    const PARAMS: KdfParams = KdfParams {
+        memory: 8,
+        iterations: 1,
+        parallelism: 1,
+    };
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "pants-{}-{}.{}",
+            name,
+            uuid::Uuid::new_v4(),
+            EXTENSION
+        ))
+    }
+
+    fn vaults() -> BTreeMap<String, Vault> {
+        let vault: Vault = serde_json::from_value(serde_json::json!({
+            "data": { "github": { "Password": "hunter2" } },
+            "updated": {},
+            "used": {},
+        }))
+        .unwrap();
+        BTreeMap::from([
+            ("main".to_string(), vault),
+            ("empty".to_string(), Vault::new()),
+        ])
+    }
+
+    fn restore(path: &Path, password: &str) -> Option<BTreeMap<String, Vault>> {
+        let archive = read(path).ok()?;
+        let decrypted = archive
+            .decrypt(archive.key(password.to_string().into()))
+            .ok()?;
+        contents(&decrypted)
+    }
+
+    fn github(vaults: BTreeMap<String, Vault>) -> String {
+        let mut entries = vaults
+            .into_iter()
+            .find(|(name, _)| name == "main")
+            .unwrap()
+            .1
+            .entries();
+        match entries.pop() {
+            Some((key, Store::Password(password))) if key == "github" => {
+                password.expose_secret().to_string()
+            }
+            other => panic!("unexpected entry {:?}", other),
+        }
+    }
+
+    #[test]
+    fn write_then_read() {
+        let path = temp_path("roundtrip");
+        write(&path, &vaults(), "archive".to_string().into(), PARAMS).unwrap();
+        assert_eq!(read(&path).unwrap().params, PARAMS);
+        let restored = restore(&path, "archive").unwrap();
+        assert_eq!(restored.keys().collect::<Vec<_>>(), ["empty", "main"]);
+        assert_eq!(github(restored), "hunter2");
+        assert!(restore(&path, "wrong").is_none());
+
+        let names = ["empty".to_string(), "main".to_string()];
+        assert!(verify(&path, "archive".to_string().into(), &names).is_ok());
+        assert!(verify(&path, "archive".to_string().into(), &names[..1]).is_err());
+        assert!(verify(&path, "wrong".to_string().into(), &names).is_err());
+        fs::remove_file(path).unwrap();
+    }
+
+    // the first version had no key derivation costs stored and always used the defaults
+    #[test]
+    fn reads_version_one() {
+        let path = temp_path("v1");
+        let salt = SaltString::generate(&mut OsRng).to_string();
+        let key =
+            ArchiveEncrypted::get_key(&salt, &KdfParams::default(), "archive".to_string().into());
+        let data: Encrypted<BTreeMap<String, Vault>> = Encrypted::encrypt(&vaults(), key).unwrap();
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(bincode::serialize(&(data, salt)).unwrap());
+        fs::write(&path, bytes).unwrap();
+
+        assert_eq!(read(&path).unwrap().params, KdfParams::default());
+        assert_eq!(github(restore(&path, "archive").unwrap()), "hunter2");
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_corrupt_files() {
+        let path = temp_path("corrupt");
+        write(&path, &vaults(), "archive".to_string().into(), PARAMS).unwrap();
+        let good = fs::read(&path).unwrap();
+
+        // the first byte of the ciphertext, after the header and the lengths of the nonce and data
+        let mut flipped = good.clone();
+        flipped[MAGIC.len() + 2 + 8 + 12 + 8] ^= 0xff;
+        fs::write(&path, &flipped).unwrap();
+        assert!(read(&path).is_ok());
+        assert!(restore(&path, "archive").is_none());
+
+        let not_archive = b"not an archive at all".to_vec();
+        let truncated = good[..MAGIC.len() + 1].to_vec();
+        let mut future = good.clone();
+        future[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        let cut_short = good[..good.len() / 2].to_vec();
+        for bytes in [not_archive, truncated, cut_short] {
+            fs::write(&path, bytes).unwrap();
+            assert!(matches!(
+                read(&path).unwrap_err().downcast_ref::<ArchiveError>(),
+                Some(ArchiveError::NotArchive)
+            ));
+        }
+        fs::write(&path, future).unwrap();
+        assert!(matches!(
+            read(&path).unwrap_err().downcast_ref::<ArchiveError>(),
+            Some(ArchiveError::UnsupportedVersion(_))
+        ));
+        fs::remove_file(path).unwrap();
+    }
+}
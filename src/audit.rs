@@ -0,0 +1,133 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{manager_message::ManagerMessage, message::Message, utils};
+
+// one line of the log, only names are kept and never any of the values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEvent {
+    // in the sortable format from `utils::format_date`
+    pub time: String,
+    pub action: String,
+    pub vault: Option<String>,
+    pub key: Option<String>,
+    // why it failed, e.g. a wrong password, missing when it went through
+    pub error: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl std::fmt::Display for AuditEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = utils::read_date(&self.time)
+            .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| self.time.clone());
+        write!(f, "{} {}", time, self.action)?;
+        if let Some(key) = &self.key {
+            write!(f, " {}", key)?;
+        }
+        if let Some(vault) = &self.vault {
+            write!(f, " in {}", vault)?;
+        }
+        match &self.error {
+            Some(error) => write!(f, ": failed, {}", error),
+            None => Ok(()),
+        }
+    }
+}
+
+// what gets logged about a message, anything that only looks at names or settings isn't logged
+pub fn describe(message: &ManagerMessage) -> Option<(String, Option<String>, Option<String>)> {
+    let (action, vault, key) = match message {
+        ManagerMessage::VaultMessage(vault, message) => {
+            let (action, key) = match message {
+                Message::Get(_, key) => ("read", Some(key.clone())),
+                Message::GetAll(_) => ("read all", None),
+                Message::Update(_, key, _) => ("update", Some(key.clone())),
+                Message::UpdateMany(_, entries) => {
+                    return Some((
+                        format!("update {} entries", entries.len()),
+                        Some(vault.clone()),
+                        None,
+                    ))
+                }
+                Message::Delete(_, key) => ("delete", Some(key.clone())),
+                Message::DeleteMany(_, keys) => {
+                    return Some((
+                        format!("delete {} entries", keys.len()),
+                        Some(vault.clone()),
+                        None,
+                    ))
+                }
+                Message::Rename(_, old, new) => {
+                    return Some((
+                        format!("rename {} to", old),
+                        Some(vault.clone()),
+                        Some(new.clone()),
+                    ))
+                }
+                Message::Backup(_) => ("backup", None),
+                Message::Rotate(..) => ("change password", None),
+                Message::Recover(..) => ("recover", None),
+                Message::CreateRecovery(_) => ("create recovery key", None),
+                Message::Restore(..) => ("restore", None),
+                Message::Schema | Message::BackupList => return None,
+            };
+            (action, Some(vault.clone()), key)
+        }
+        ManagerMessage::DeleteVault(vault, _) => ("delete vault", Some(vault.clone()), None),
+        ManagerMessage::ChangePassword { vault, .. } => {
+            ("change password", Some(vault.clone()), None)
+        }
+        ManagerMessage::Recover { vault, .. } => ("recover", Some(vault.clone()), None),
+        ManagerMessage::Export { vault, .. } => ("export", Some(vault.clone()), None),
+        ManagerMessage::Import { vault, .. } => ("import", Some(vault.clone()), None),
+        ManagerMessage::ImportVaults { .. } => ("import", None, None),
+        ManagerMessage::BackupExport { .. } => ("archive", None, None),
+        ManagerMessage::BackupImport { .. } => ("restore archive", None, None),
+        ManagerMessage::Regenerate { vault, .. } => ("regenerate", Some(vault.clone()), None),
+        ManagerMessage::CheckBreach { vault, key, .. } => {
+            ("check breach", Some(vault.clone()), Some(key.clone()))
+        }
+        ManagerMessage::CheckReuse { vault, .. } => ("check reuse", Some(vault.clone()), None),
+        ManagerMessage::CheckStrength { vault, .. } => {
+            ("check strength", Some(vault.clone()), None)
+        }
+        _ => return None,
+    };
+    Some((action.to_string(), vault, key))
+}
+
+// the log is only ever added to
+pub fn append(path: &Path, event: &AuditEvent) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(event)?)?;
+    Ok(())
+}
+
+// the last `count` events, oldest first, lines that can't be read are skipped
+pub fn recent(path: &Path, count: usize) -> anyhow::Result<Vec<AuditEvent>> {
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let file = std::fs::File::open(path)?;
+    let events = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect::<Vec<AuditEvent>>();
+    let start = events.len().saturating_sub(count);
+    Ok(events[start..].to_vec())
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use secrecy::ExposeSecret;
+use sha1::{Digest, Sha1};
+
+use crate::Password;
+
+// k-anonymity range lookup, only the first five characters of the hash are ever sent
+const RANGE_API: &str = "https://api.pwnedpasswords.com/range/";
+const PREFIX_LENGTH: usize = 5;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn hash(password: &Password) -> String {
+    Sha1::digest(password.expose_secret().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+// how many times the password with the given hash shows up in known breaches, the response lists
+// every suffix for the prefix so the match is done locally
+pub async fn check(hash: String) -> Result<u64, String> {
+    async_std::task::spawn_blocking(move || {
+        let (prefix, suffix) = hash.split_at(PREFIX_LENGTH);
+        let body = ureq::get(&format!("{}{}", RANGE_API, prefix))
+            .timeout(TIMEOUT)
+            // padding hides how many suffixes actually came back for the prefix
+            .set("Add-Padding", "true")
+            .call()
+            .map_err(|e| format!("Breach check failed: {}", e))?
+            .into_string()
+            .map_err(|e| format!("Breach check failed: {}", e))?;
+        Ok(body
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+            .and_then(|(_, count)| count.parse().ok())
+            .unwrap_or(0))
+    })
+    .await
+}
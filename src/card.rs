@@ -0,0 +1,148 @@
+use secrecy::{ExposeSecret, Secret};
+use serde::{ser::SerializeStruct, Deserialize, Serialize};
+
+use crate::errors::SchemaError;
+
+// the number is kept as just the digits and the expiry as MM/YY, however they were entered
+#[derive(Debug, Clone, Deserialize)]
+pub struct Card {
+    pub number: Secret<String>,
+    pub expiry: String,
+    pub cvv: Secret<String>,
+    pub cardholder: String,
+}
+
+impl Serialize for Card {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut state = serializer.serialize_struct("Card", 4)?;
+        state.serialize_field("number", self.number.expose_secret())?;
+        state.serialize_field("expiry", &self.expiry)?;
+        state.serialize_field("cvv", self.cvv.expose_secret())?;
+        state.serialize_field("cardholder", &self.cardholder)?;
+        state.end()
+    }
+}
+
+impl Card {
+    pub fn new(
+        number: &str,
+        expiry: &str,
+        cvv: &str,
+        cardholder: &str,
+    ) -> Result<Self, SchemaError> {
+        let number = digits(number);
+        if !(12..=19).contains(&number.len()) || !luhn(&number) {
+            return Err(SchemaError::BadCardNumber);
+        }
+        let cvv = cvv.trim();
+        if !cvv.is_empty() && (!(3..=4).contains(&cvv.len()) || cvv.parse::<u16>().is_err()) {
+            return Err(SchemaError::BadCardCvv);
+        }
+        Ok(Self {
+            number: number.into(),
+            expiry: format_expiry(expiry)?,
+            cvv: cvv.to_string().into(),
+            cardholder: cardholder.trim().to_string(),
+        })
+    }
+
+    pub fn last_four(&self) -> &str {
+        let number = self.number.expose_secret();
+        &number[number.len().saturating_sub(4)..]
+    }
+}
+
+// spaces and dashes are allowed between the digits
+fn digits(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect()
+}
+
+// from the right, every second digit is doubled and the sum has to be a multiple of ten
+pub fn luhn(number: &str) -> bool {
+    let mut sum = 0;
+    for (i, c) in number.chars().rev().enumerate() {
+        let Some(digit) = c.to_digit(10) else {
+            return false;
+        };
+        sum += match (i % 2 == 1, digit * 2) {
+            (true, doubled) if doubled > 9 => doubled - 9,
+            (true, doubled) => doubled,
+            (false, _) => digit,
+        };
+    }
+    !number.is_empty() && sum % 10 == 0
+}
+
+// takes MM/YY, MM/YYYY, MMYY, or with a dash, and gives MM/YY
+pub fn format_expiry(expiry: &str) -> Result<String, SchemaError> {
+    let expiry = expiry.trim();
+    let (month, year) = match expiry.split_once(['/', '-']) {
+        Some((month, year)) => (month.trim(), year.trim()),
+        // only split when it's all single byte characters so it can't land inside one
+        None if expiry.len() == 4 && expiry.is_ascii() => expiry.split_at(2),
+        None => return Err(SchemaError::BadCardExpiry),
+    };
+    let month: u32 = month.parse().map_err(|_| SchemaError::BadCardExpiry)?;
+    let year: u32 = match year.len() {
+        2 => year.parse().map_err(|_| SchemaError::BadCardExpiry)?,
+        4 => year
+            .parse::<u32>()
+            .map(|year| year % 100)
+            .map_err(|_| SchemaError::BadCardExpiry)?,
+        _ => return Err(SchemaError::BadCardExpiry),
+    };
+    if !(1..=12).contains(&month) {
+        return Err(SchemaError::BadCardExpiry);
+    }
+    Ok(format!("{:02}/{:02}", month, year))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn luhn_valid_numbers() {
+        assert!(luhn("4111111111111111"));
+        assert!(luhn("5555555555554444"));
+        assert!(luhn("378282246310005"));
+        assert!(Card::new("4111 1111 1111 1111", "12/30", "123", "A").is_ok());
+    }
+
+    #[test]
+    fn luhn_invalid_numbers() {
+        assert!(!luhn("4111111111111112"));
+        assert!(!luhn("41111111111a1111"));
+        assert!(!luhn(""));
+        assert!(matches!(
+            Card::new("4111-1111-1111-1112", "12/30", "123", "A"),
+            Err(SchemaError::BadCardNumber)
+        ));
+    }
+
+    #[test]
+    fn expiry_formats() {
+        for expiry in ["04/27", "04/2027", "0427", "04-27", "04-2027", " 4 / 27 "] {
+            assert_eq!(format_expiry(expiry).unwrap(), "04/27", "{}", expiry);
+        }
+    }
+
+    #[test]
+    fn bad_expiry() {
+        for expiry in [
+            "", "13/27", "00/27", "4/7", "04/027", "042", "ab/cd", "é27", "1é2",
+        ] {
+            assert!(
+                matches!(format_expiry(expiry), Err(SchemaError::BadCardExpiry)),
+                "{}",
+                expiry
+            );
+        }
+    }
+}
@@ -1,21 +1,30 @@
 use core::panic;
-use std::{process::exit, str::FromStr, thread, time::Duration};
+use std::{
+    collections::BTreeMap, fs, path::PathBuf, process::exit, str::FromStr, thread, time::Duration,
+};
 
 use arboard::Clipboard;
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
 use inquire::Confirm;
-use pants_gen::password::PasswordSpec;
 use secrecy::ExposeSecret;
 
 use crate::{
+    archive::{self, Collision},
+    card::Card,
     config::{client_config::ClientConfig, internal_config::BaseConfig},
-    errors::{ClientError, CommunicationError, SchemaError},
+    errors::{CommunicationError, ManagerError, SchemaError},
+    export::{self, ExportFormat},
+    generate::GenSpec,
+    import::{bitwarden, ImportFormat},
     info::Info,
     manager_message::ManagerMessage,
     message::Message,
     output::Output,
-    schema::Schema,
+    schema::{Schema, StoreKind},
     store::Store,
+    totp::{Totp, TotpAlgorithm, DEFAULT_DIGITS, DEFAULT_PERIOD},
+    utils,
     vault::manager::VaultManager,
     Password,
 };
@@ -34,28 +43,55 @@ pub struct CliArgs {
     /// how to handle values pulled from vault
     #[arg(long, value_enum, default_value_t = OutputStyle::Clipboard)]
     output: OutputStyle,
+    /// print output as json instead, values pulled from a vault are included
+    #[arg(long, global = true)]
+    json: bool,
 }
 
 #[derive(Subcommand)]
 pub enum CLICommands {
     /// create new vault
-    New { name: String },
+    New {
+        name: String,
+        /// also make a recovery key that can set a new password if this one is forgotten, it is
+        /// only shown once
+        #[arg(long)]
+        recovery: bool,
+    },
     /// create new entry
     Add {
         /// name of the vault
         vault: String,
         #[command(subcommand)]
         style: EntryStyle,
-        /// specify a password spec string to be used over the configured one
+        /// specify a password spec string to be used over the configured one, e.g. `pin:6`
         #[arg(long)]
         spec: Option<String>,
     },
+    /// create a totp entry from an `otpauth://totp/...` uri, like the ones in setup qr codes
+    AddTotp {
+        /// name of the vault
+        vault: String,
+        /// the `otpauth://totp/...` uri
+        uri: String,
+        /// name of the entry, defaults to the uri's label
+        #[arg(long)]
+        name: Option<String>,
+    },
     /// lookup the given entry
     Get {
         /// name of the vault
         vault: String,
         /// name of the entry
         key: String,
+        /// field of the entry to print when quiet, `code` gives the current totp code and `uri` its
+        /// `otpauth://` uri
+        #[arg(long, default_value = "password")]
+        field: String,
+        /// for scripts, reads the vault password from PANTS_PASSWORD or else the first line of
+        /// stdin and prints only the field
+        #[arg(long)]
+        quiet: bool,
     },
     /// update the entry
     Update {
@@ -65,7 +101,7 @@ pub enum CLICommands {
         key: String,
         // #[command(subcommand)]
         // password: Option<Generate>,
-        /// specify a password spec string to be used over the configured one
+        /// specify a password spec string to be used over the configured one, e.g. `pin:6`
         #[arg(long)]
         spec: Option<String>,
     },
@@ -76,6 +112,17 @@ pub enum CLICommands {
         /// name of the entry
         key: Option<String>,
     },
+    /// replace the passwords of the given entries with newly generated ones, e.g. after a breach
+    Regenerate {
+        /// name of the vault
+        vault: String,
+        /// names of the entries
+        #[arg(required = true)]
+        keys: Vec<String>,
+        /// specify a password spec string to be used over the configured one, e.g. `pin:6`
+        #[arg(long)]
+        spec: Option<String>,
+    },
     /// list the vaults/entries
     List {
         /// name of vault to list entries of
@@ -94,9 +141,111 @@ pub enum CLICommands {
     Rotate {
         /// name of the vault
         vault: String,
+    },
+    /// set a new master password for the vault using its recovery key
+    Recover {
+        /// name of the vault
+        vault: String,
     }, // Transaction,
-    /// generate password
-    Gen(pants_gen::cli::CliArgs),
+    /// export the vault's entries as CSV to stdout, the output is not encrypted
+    Export {
+        /// name of the vault
+        vault: String,
+    },
+    /// print each entry's password as a shell variable assignment, e.g. for
+    /// `source <(pants export-env vault)`
+    ExportEnv {
+        /// name of the vault
+        vault: String,
+        /// put in front of every variable name, separated by `_`
+        #[arg(long)]
+        prefix: Option<String>,
+        /// start each line with `export` so the variables reach child processes
+        #[arg(long)]
+        export: bool,
+    },
+    /// list the entries of the vault that share a password, the passwords themselves are not shown
+    Reused {
+        /// name of the vault
+        vault: String,
+    },
+    /// list the entries of the vault with weak or fair passwords, the passwords are not shown
+    Weak {
+        /// name of the vault
+        vault: String,
+    },
+    /// show the latest actions from the audit log, none of the values are ever logged
+    Audit {
+        /// how many events to show
+        #[arg(long, default_value_t = 20)]
+        count: usize,
+    },
+    /// write the given vaults, or all of them, to a single file encrypted with its own password,
+    /// each vault's password is asked for
+    Archive {
+        /// file to write the archive to
+        path: PathBuf,
+        /// names of the vaults to include
+        vaults: Vec<String>,
+    },
+    /// restore the vaults in an archive, vaults that don't exist yet are created
+    Unarchive {
+        /// archive file to restore from
+        path: PathBuf,
+        /// what to do with vaults that already exist
+        #[arg(long, value_enum, default_value_t = Collision::Skip)]
+        collision: Collision,
+    },
+    /// import entries from a CSV or JSON file into the vault
+    Import {
+        /// name of the vault
+        vault: String,
+        /// file to import from, the format is taken from the extension
+        path: PathBuf,
+        /// replace existing entries with the same name instead of skipping them
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// import a bitwarden unencrypted json export, grouping items by folder into vaults
+    Bitwarden {
+        /// file to import from
+        path: PathBuf,
+        /// put everything into this vault instead of grouping by folder
+        #[arg(long)]
+        vault: Option<String>,
+        /// replace existing entries with the same name instead of skipping them
+        #[arg(long)]
+        overwrite: bool,
+    },
+    /// set the password spec used for the vault over the configured one, clears it when not given
+    Spec {
+        /// name of the vault
+        vault: String,
+        /// password spec string
+        spec: Option<String>,
+    },
+    /// remake the vault's key with the configured key derivation costs the next time it's unlocked
+    Reharden {
+        /// name of the vault
+        vault: String,
+    },
+    /// generate passwords without touching any vault
+    Gen {
+        /// password spec string or the name of a preset, the configured one when not given
+        #[arg(long)]
+        spec: Option<String>,
+        /// how many passwords to generate
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// put the password on the clipboard instead of printing it, cleared after the configured time
+        #[arg(long)]
+        copy: bool,
+    },
+    /// print the completion script for the shell to stdout
+    Completions {
+        /// shell to generate completions for
+        shell: Shell,
+    },
 }
 
 #[derive(Subcommand)]
@@ -109,6 +258,18 @@ pub enum EntryStyle {
     UsernamePassword {
         name: String,
     },
+    Login {
+        name: String,
+    },
+    Totp {
+        name: String,
+    },
+    Note {
+        name: String,
+    },
+    Card {
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -128,8 +289,32 @@ pub struct CliApp {
 impl CliApp {
     pub fn run() {
         let args = CliArgs::parse();
+        // doesn't need the config or any vaults, so it shouldn't fail because of them
+        if let CLICommands::Completions { shell } = args.command {
+            clap_complete::generate(
+                shell,
+                &mut CliArgs::command(),
+                "pants",
+                &mut std::io::stdout(),
+            );
+            return;
+        }
         let config = <ClientConfig as BaseConfig>::load_err();
-        let interface = VaultManager::default();
+        // only needs the config for the defaults, not the vaults
+        if let CLICommands::Gen { spec, count, copy } = &args.command {
+            if let Err(e) = Self::generate(&config, spec.as_deref(), *count, *copy) {
+                eprintln!("Encountered error: {}", e);
+                exit(1)
+            }
+            return;
+        }
+        let interface = match VaultManager::with_dir(config.data_dir.clone()) {
+            Ok(interface) => interface,
+            Err(e) => {
+                eprintln!("Encountered error: {}", e);
+                exit(1)
+            }
+        };
         let app = CliApp {
             args,
             config,
@@ -139,35 +324,139 @@ impl CliApp {
     }
 
     pub fn execute(self) {
-        match self.args.command {
-            CLICommands::Gen(args) => {
-                if let Some(p) = args.execute() {
-                    println!("{p}");
-                } else {
-                    println!("Could not satisfy password spec constraints");
-                }
+        match Self::process(
+            &self.config,
+            &self.args.output,
+            self.args.json,
+            self.interface,
+            &self.args.command,
+        ) {
+            Ok(()) => (),
+            Err(e) if self.args.json => {
+                eprintln!("{}", Output::Error(e.to_string()).to_json());
+                exit(1)
             }
-            ref command => {
-                match Self::process(&self.config, &self.args.output, self.interface, command) {
-                    Ok(()) => (),
-                    Err(e) => {
-                        println!("Encountered error: {}", e);
-                        exit(1)
-                    }
-                }
+            Err(e) => {
+                eprintln!("Encountered error: {}", e);
+                exit(1)
+            }
+        }
+    }
+
+    fn generate(
+        config: &ClientConfig,
+        spec: Option<&str>,
+        count: usize,
+        copy: bool,
+    ) -> anyhow::Result<()> {
+        let spec = spec.unwrap_or(&config.password_spec);
+        // a preset's name stands in for its spec
+        let spec = GenSpec::from_str(config.presets.get(spec).map_or(spec, String::as_str))?;
+        if copy {
+            if count != 1 {
+                return Err(anyhow::anyhow!("Only a single password can be copied"));
             }
+            let password = spec.generate()?;
+            let mut clipboard = Clipboard::new()?;
+            // whatever isn't text can't be put back, so the password is just cleared then
+            let orig = clipboard.get_text().ok();
+            clipboard.set_text(password)?;
+            println!("password: <Copied to clipboard>");
+            thread::sleep(Duration::from_secs(config.clipboard_time));
+            match orig {
+                Some(orig) => clipboard.set_text(orig)?,
+                None => clipboard.clear()?,
+            }
+            println!("Resetting clipboard");
+            thread::sleep(Duration::from_secs(1));
+            return Ok(());
+        }
+        for _ in 0..count {
+            println!("{}", spec.generate()?);
         }
+        Ok(())
     }
     fn process(
         config: &ClientConfig,
         output_style: &OutputStyle,
+        json: bool,
         mut manager: VaultManager,
         command: &CLICommands,
     ) -> anyhow::Result<()> {
         let message = Self::construct_message(&mut manager, config, command)?;
         let output = manager.receive(message)?;
+        if json {
+            return Self::print_json(output, command);
+        }
+        if let CLICommands::Get {
+            key,
+            field,
+            quiet: true,
+            ..
+        } = command
+        {
+            return Self::print_field(output, key, field);
+        }
+        if let CLICommands::ExportEnv { prefix, export, .. } = command {
+            return Self::print_env(output, prefix.as_deref(), *export);
+        }
         Self::handle_output(config, output_style, output)
     }
+    // errors are left to the caller so they end up on stderr
+    fn print_json(output: Output, command: &CLICommands) -> anyhow::Result<()> {
+        let value = match (&output, command) {
+            (Output::Error(e), _) => return Err(anyhow::anyhow!(e.to_string())),
+            // a single entry is asked for so it is given by itself
+            (Output::Read(reads), CLICommands::Get { key, .. }) => reads
+                .data
+                .get(key)
+                .map(Output::entry_json)
+                .ok_or_else(|| anyhow::anyhow!("No entry {}", key))?,
+            _ => output.to_json(),
+        };
+        println!("{}", value);
+        Ok(())
+    }
+    // only the value goes to stdout so it can be piped, anything missing is an error
+    fn print_field(output: Output, key: &str, field: &str) -> anyhow::Result<()> {
+        let mut reads = match output {
+            Output::Read(reads) => reads,
+            Output::Error(e) => return Err(anyhow::anyhow!(e)),
+            _ => return Err(CommunicationError::UnexpectedOutput.into()),
+        };
+        let store = reads
+            .remove(key)
+            .ok_or_else(|| anyhow::anyhow!("No entry {}", key))?;
+        if let Some((_, value)) = store.custom_fields().iter().find(|(name, _)| name == field) {
+            println!("{}", value.expose_secret());
+            return Ok(());
+        }
+        let store = store.base();
+        if let (Store::Totp(totp), "code") = (&store, field) {
+            println!("{}", totp.code());
+            return Ok(());
+        }
+        if let (Store::Totp(totp), "uri") = (&store, field) {
+            println!("{}", totp.uri(key));
+            return Ok(());
+        }
+        let (choice, values) = store.split();
+        let value = values
+            .get(field)
+            .ok_or_else(|| anyhow::anyhow!("{} entries have no field {}", choice, field))?;
+        println!("{}", value.expose_secret());
+        Ok(())
+    }
+    // nothing else goes to stdout so the output can be sourced as is
+    fn print_env(output: Output, prefix: Option<&str>, export: bool) -> anyhow::Result<()> {
+        let reads = match output {
+            Output::Read(reads) => reads,
+            Output::Error(e) => return Err(anyhow::anyhow!(e)),
+            _ => return Err(CommunicationError::UnexpectedOutput.into()),
+        };
+        print!("{}", export::to_env(reads, prefix, export)?.expose_secret());
+        Ok(())
+    }
     fn handle_output(
         config: &ClientConfig,
         output_style: &OutputStyle,
@@ -183,7 +472,13 @@ impl CliApp {
                             let orig = clipboard.get_text().unwrap_or("".to_string());
                             for (key, value) in reads.data.clone().into_iter() {
                                 println!("{}", key);
-                                match value {
+                                for (name, field) in value.custom_fields() {
+                                    println!("  {}: {}", name, field.expose_secret());
+                                }
+                                if let Some(notes) = value.notes() {
+                                    println!("  notes: {}", notes.expose_secret());
+                                }
+                                match value.plain() {
                                     Store::Password(ref pass) => {
                                         clipboard.set_text(pass.expose_secret())?;
                                         println!("  password: <Copied to clipboard>");
@@ -195,6 +490,37 @@ impl CliApp {
                                         println!("  password: <Copied to clipboard>");
                                         thread::sleep(Duration::from_secs(config.clipboard_time));
                                     }
+                                    Store::Login(ref user, ref pass, ref url) => {
+                                        clipboard.set_text(pass.expose_secret())?;
+                                        println!("  username: {}", user.expose_secret());
+                                        println!("  url: {}", url.expose_secret());
+                                        println!("  password: <Copied to clipboard>");
+                                        thread::sleep(Duration::from_secs(config.clipboard_time));
+                                    }
+                                    Store::Totp(ref totp) => {
+                                        clipboard.set_text(totp.code())?;
+                                        println!(
+                                            "  code: <Copied to clipboard> (expires in {}s)",
+                                            totp.remaining()
+                                        );
+                                        thread::sleep(Duration::from_secs(config.clipboard_time));
+                                    }
+                                    Store::Note(ref note) => {
+                                        clipboard.set_text(note.expose_secret())?;
+                                        println!("  note: <Copied to clipboard>");
+                                        thread::sleep(Duration::from_secs(config.clipboard_time));
+                                    }
+                                    Store::Card(ref card) => {
+                                        clipboard.set_text(card.number.expose_secret())?;
+                                        if !card.cardholder.is_empty() {
+                                            println!("  cardholder: {}", card.cardholder);
+                                        }
+                                        println!("  number: <Copied to clipboard>");
+                                        println!("  expiry: {}", card.expiry);
+                                        thread::sleep(Duration::from_secs(config.clipboard_time));
+                                    }
+                                    // unwrapped above
+                                    Store::WithFields(..) | Store::WithNotes(..) => {}
                                 }
                             }
                             clipboard.set_text(orig)?;
@@ -247,12 +573,86 @@ impl CliApp {
                 println!("Backed up to: {}", backup);
                 Ok(())
             }
+            Output::Archive(path, vaults) => {
+                println!("Archived {} to: {}", vaults.join(", "), path.display());
+                Ok(())
+            }
             Output::BackupFiles(backups) => {
                 for file in backups {
                     println!("{}", file);
                 }
                 Ok(())
             }
+            Output::Export(_, data) => {
+                print!("{}", data.expose_secret());
+                Ok(())
+            }
+            Output::Import(imported, skipped) => {
+                println!("Imported {} entries", imported);
+                if !skipped.is_empty() {
+                    println!("Skipped {} existing entries:", skipped.len());
+                    for key in skipped {
+                        println!("- {}", key);
+                    }
+                }
+                Ok(())
+            }
+            Output::Reused(vault, groups) => {
+                if groups.is_empty() {
+                    println!("No reused passwords in {}", vault);
+                } else {
+                    println!("Entries in {} sharing a password:", vault);
+                    for group in groups {
+                        println!("- {}", group.join(", "));
+                    }
+                }
+                Ok(())
+            }
+            Output::Weak(vault, entries) => {
+                if entries.is_empty() {
+                    println!("No weak passwords in {}", vault);
+                } else {
+                    println!("Entries in {} with weak passwords:", vault);
+                    for (key, strength) in entries {
+                        println!("- {}: {}", key, strength);
+                    }
+                }
+                Ok(())
+            }
+            Output::Audit(events) => {
+                if events.is_empty() {
+                    println!("Nothing has been logged yet");
+                }
+                for event in events {
+                    println!("{}", event);
+                }
+                Ok(())
+            }
+            Output::RecoveryKey(key) => {
+                println!("Recovery key: {}", key.expose_secret());
+                println!("Write it down somewhere safe, it will not be shown again");
+                Ok(())
+            }
+            Output::Regenerated(regenerated, skipped) => {
+                println!("Regenerated {} passwords", regenerated);
+                if !skipped.is_empty() {
+                    println!("Skipped {} entries without a password:", skipped.len());
+                    for key in skipped {
+                        println!("- {}", key);
+                    }
+                }
+                Ok(())
+            }
+            Output::Version(version) => {
+                println!("{}", version);
+                Ok(())
+            }
+            // the cli runs the manager itself, so they can't disagree
+            Output::ProtocolMismatch(..) => Ok(()),
+            // breach checks are only made by the gui
+            Output::PasswordHash(..) => Ok(()),
+            Output::LockedOut(seconds) => Err(ManagerError::LockedOut(seconds).into()),
+            Output::Error(e) => Err(anyhow::anyhow!(e)),
         }
     }
     fn construct_message(
@@ -261,9 +661,25 @@ impl CliApp {
         command: &CLICommands,
     ) -> anyhow::Result<ManagerMessage> {
         match command {
-            CLICommands::New { name } => Ok(ManagerMessage::NewVault(name.into())),
-            CLICommands::Get { vault, key } => {
-                let password = Self::get_password("Vault password:")?;
+            CLICommands::New { name, recovery } => {
+                if !*recovery {
+                    return Ok(ManagerMessage::NewVault(name.into()));
+                }
+                let password = Self::get_password_confirm("Vault password:")?;
+                manager.receive(ManagerMessage::NewVault(name.into()))?;
+                Ok(ManagerMessage::VaultMessage(
+                    name.into(),
+                    Message::CreateRecovery(password),
+                ))
+            }
+            CLICommands::Get {
+                vault, key, quiet, ..
+            } => {
+                let password = if *quiet {
+                    Self::read_password()?
+                } else {
+                    Self::get_password("Vault password:")?
+                };
                 Ok(ManagerMessage::VaultMessage(
                     vault.to_string(),
                     Message::Get(password, key.to_string()),
@@ -274,11 +690,21 @@ impl CliApp {
                 match schema.get(key) {
                     None => Err(Box::new(CommunicationError::NoEntry).into()),
                     Some(style) => {
-                        let spec = PasswordSpec::from_str(
-                            &spec.clone().unwrap_or_else(|| config.password_spec.clone()),
-                        )?;
-                        let value = Self::prompt(style, spec)?;
+                        let info = Self::get_info(manager)?;
+                        let spec = GenSpec::from_str(&Self::spec_for(
+                            config,
+                            &info,
+                            vault,
+                            spec.as_ref(),
+                        ))?;
                         let password = Self::get_password("Vault password:")?;
+                        // only the value is replaced, the custom fields and notes stay with it
+                        let existing = Self::get_entry(manager, vault, password.clone(), key)?;
+                        let mut value = Self::prompt(*style, spec)?;
+                        if let Some(notes) = existing.notes() {
+                            value = value.with_notes(notes);
+                        }
+                        let value = value.with_fields(existing.custom_fields());
                         Ok(ManagerMessage::VaultMessage(
                             vault.into(),
                             Message::Update(password, key.to_string(), value),
@@ -308,6 +734,26 @@ impl CliApp {
                     }
                 }
             }
+            CLICommands::Regenerate { vault, keys, spec } => {
+                let info = Self::get_info(manager)?;
+                let spec = Self::spec_for(config, &info, vault, spec.as_ref());
+                let confirmed = Confirm::new(&format!(
+                    "Replace the passwords of {} entries? The current ones can't be recovered",
+                    keys.len()
+                ))
+                .with_default(false)
+                .prompt()?;
+                if !confirmed {
+                    return Ok(ManagerMessage::Empty);
+                }
+                let password = Self::get_password("Vault password:")?;
+                Ok(ManagerMessage::Regenerate {
+                    vault: vault.into(),
+                    password,
+                    keys: keys.clone(),
+                    spec,
+                })
+            }
             CLICommands::Add { vault, style, spec } => {
                 let info = Self::get_info(manager)?;
                 let schema = info.get(vault).cloned().unwrap_or(Schema::default());
@@ -316,15 +762,14 @@ impl CliApp {
                 if new_vault {
                     manager.receive(ManagerMessage::NewVault(vault.into()))?;
                 }
-                let spec =
-                    PasswordSpec::from_str(&spec.clone().unwrap_or(config.password_spec.clone()))?;
+                let spec = GenSpec::from_str(&Self::spec_for(config, &info, vault, spec.as_ref()))?;
                 match style {
                     EntryStyle::Password { name } => Self::handle_new(
                         confirm_password,
                         vault.into(),
                         schema,
                         name.to_string(),
-                        "password",
+                        StoreKind::Password,
                         spec,
                     ),
                     EntryStyle::UsernamePassword { name } => Self::handle_new(
@@ -332,11 +777,69 @@ impl CliApp {
                         vault.into(),
                         schema,
                         name.to_string(),
-                        "username-password",
+                        StoreKind::UsernamePassword,
+                        spec,
+                    ),
+                    EntryStyle::Login { name } => Self::handle_new(
+                        confirm_password,
+                        vault.into(),
+                        schema,
+                        name.to_string(),
+                        StoreKind::Login,
+                        spec,
+                    ),
+                    EntryStyle::Totp { name } => Self::handle_new(
+                        confirm_password,
+                        vault.into(),
+                        schema,
+                        name.to_string(),
+                        StoreKind::Totp,
+                        spec,
+                    ),
+                    EntryStyle::Note { name } => Self::handle_new(
+                        confirm_password,
+                        vault.into(),
+                        schema,
+                        name.to_string(),
+                        StoreKind::Note,
+                        spec,
+                    ),
+                    EntryStyle::Card { name } => Self::handle_new(
+                        confirm_password,
+                        vault.into(),
+                        schema,
+                        name.to_string(),
+                        StoreKind::Card,
                         spec,
                     ),
                 }
             }
+            CLICommands::AddTotp { vault, uri, name } => {
+                let totp = Totp::from_uri(uri)?;
+                let name = name
+                    .clone()
+                    .or_else(|| Totp::label_from_uri(uri))
+                    .ok_or(SchemaError::MissingTotpParameter("label"))?;
+                let key = utils::entry_key(&name)?;
+                let info = Self::get_info(manager)?;
+                let schema = info.get(vault).cloned().unwrap_or(Schema::default());
+                if schema.get(&key).is_some() {
+                    return Err(Box::new(CommunicationError::ExistingEntry).into());
+                }
+                let new_vault = !info.data.contains_key(vault);
+                if new_vault {
+                    manager.receive(ManagerMessage::NewVault(vault.into()))?;
+                }
+                let password = if new_vault || schema.is_empty() {
+                    Self::get_password_confirm("New vault password:")?
+                } else {
+                    Self::get_password("Vault password:")?
+                };
+                Ok(ManagerMessage::VaultMessage(
+                    vault.into(),
+                    Message::Update(password, key, Store::Totp(totp)),
+                ))
+            }
             CLICommands::Rotate { vault } => {
                 let password = Self::get_password("Vault password:")?;
                 let new_password = Self::get_password_confirm("New vault password:")?;
@@ -345,6 +848,126 @@ impl CliApp {
                     Message::Rotate(password, new_password),
                 ))
             }
+            CLICommands::Recover { vault } => {
+                let recovery = Self::get_password("Recovery key:")?;
+                let new_password = Self::get_password_confirm("New vault password:")?;
+                Ok(ManagerMessage::Recover {
+                    vault: vault.into(),
+                    recovery,
+                    new: new_password,
+                })
+            }
+            CLICommands::Export { vault } => {
+                let password = Self::get_password("Vault password:")?;
+                Ok(ManagerMessage::Export {
+                    vault: vault.into(),
+                    password,
+                    format: ExportFormat::Csv,
+                })
+            }
+            CLICommands::ExportEnv { vault, .. } => {
+                let password = Self::get_password("Vault password:")?;
+                Ok(ManagerMessage::VaultMessage(
+                    vault.into(),
+                    Message::GetAll(password),
+                ))
+            }
+            CLICommands::Reused { vault } => {
+                let password = Self::get_password("Vault password:")?;
+                Ok(ManagerMessage::CheckReuse {
+                    vault: vault.into(),
+                    password,
+                })
+            }
+            CLICommands::Weak { vault } => {
+                let password = Self::get_password("Vault password:")?;
+                Ok(ManagerMessage::CheckStrength {
+                    vault: vault.into(),
+                    password,
+                })
+            }
+            CLICommands::Audit { count } => Ok(ManagerMessage::AuditLog(*count)),
+            CLICommands::Archive { path, vaults } => {
+                let names = if vaults.is_empty() {
+                    Self::get_info(manager)?.data.into_keys().collect()
+                } else {
+                    vaults.clone()
+                };
+                // every vault can have a different password
+                let mut passwords = BTreeMap::new();
+                for name in names {
+                    let password = Self::get_password(&format!("Password for {}:", name))?;
+                    passwords.insert(name, password);
+                }
+                let archive_password = Self::get_password_confirm("Archive password:")?;
+                Ok(ManagerMessage::BackupExport {
+                    vaults: passwords,
+                    archive_password,
+                    path: path.clone(),
+                })
+            }
+            CLICommands::Unarchive { path, collision } => {
+                archive::read(path)?;
+                let archive_password = Self::get_password("Archive password:")?;
+                let password = Self::get_password_confirm("Password for the restored vaults:")?;
+                Ok(ManagerMessage::BackupImport {
+                    path: path.clone(),
+                    password,
+                    archive_password,
+                    collision: *collision,
+                })
+            }
+            CLICommands::Import {
+                vault,
+                path,
+                overwrite,
+            } => {
+                let format = ImportFormat::from_path(path).ok_or_else(|| {
+                    anyhow::anyhow!("Unrecognized import format, expected a .csv or .json file")
+                })?;
+                let entries = format.parse(&fs::read_to_string(path)?)?;
+                // importing into an empty vault sets its password
+                let info = Self::get_info(manager)?;
+                let password = if info.get(vault).map_or(true, |schema| schema.is_empty()) {
+                    Self::get_password_confirm("Vault password:")?
+                } else {
+                    Self::get_password("Vault password:")?
+                };
+                Ok(ManagerMessage::Import {
+                    vault: vault.into(),
+                    password,
+                    entries,
+                    overwrite: *overwrite,
+                })
+            }
+            CLICommands::Bitwarden {
+                path,
+                vault,
+                overwrite,
+            } => {
+                let import = bitwarden::parse(&fs::read_to_string(path)?, vault.as_deref())?;
+                println!("{}", import.summary());
+                let info = Self::get_info(manager)?;
+                let new_vault = import
+                    .vaults
+                    .keys()
+                    .any(|vault| info.get(vault).map_or(true, |schema| schema.is_empty()));
+                let password = if new_vault {
+                    Self::get_password_confirm("Vault password:")?
+                } else {
+                    Self::get_password("Vault password:")?
+                };
+                Ok(ManagerMessage::ImportVaults {
+                    password,
+                    vaults: import.vaults,
+                    overwrite: *overwrite,
+                })
+            }
+            CLICommands::Spec { vault, spec } => Ok(ManagerMessage::SetPasswordSpec {
+                vault: vault.into(),
+                spec: spec.clone(),
+            }),
+            CLICommands::Reharden { vault } => Ok(ManagerMessage::Reharden(vault.into())),
             CLICommands::Backup { vault, option } => match option {
                 None => {
                     let password = Self::get_password("Vault password:")?;
@@ -385,7 +1008,8 @@ impl CliApp {
                     Ok(ManagerMessage::Info)
                 }
             }
-            CLICommands::Gen(_) => panic!("Should have branched before this"),
+            CLICommands::Gen { .. } => panic!("Should have branched before this"),
+            CLICommands::Completions { .. } => panic!("Should have branched before this"),
         }
     }
 
@@ -394,9 +1018,10 @@ impl CliApp {
         vault: String,
         schema: Schema,
         key: String,
-        style: &str,
-        spec: PasswordSpec,
+        style: StoreKind,
+        spec: GenSpec,
     ) -> anyhow::Result<ManagerMessage> {
+        let key = utils::entry_key(&key)?;
         match schema.get(&key) {
             None => {
                 let value = Self::prompt(style, spec)?;
@@ -414,6 +1039,13 @@ impl CliApp {
         }
     }
 
+    // an explicitly given spec wins, then the vault's, then the configured one
+    fn spec_for(config: &ClientConfig, info: &Info, vault: &str, spec: Option<&String>) -> String {
+        spec.or_else(|| info.spec(vault))
+            .unwrap_or(&config.password_spec)
+            .to_string()
+    }
+
     fn get_schema(manager: &mut VaultManager, vault: String) -> anyhow::Result<Schema> {
         match manager.receive(ManagerMessage::VaultMessage(vault, Message::Schema))? {
             Output::Schema(schema) => Ok(schema),
@@ -421,6 +1053,24 @@ impl CliApp {
         }
     }
 
+    fn get_entry(
+        manager: &mut VaultManager,
+        vault: &str,
+        password: Password,
+        key: &str,
+    ) -> anyhow::Result<Store> {
+        match manager.receive(ManagerMessage::VaultMessage(
+            vault.into(),
+            Message::Get(password, key.to_string()),
+        ))? {
+            Output::Read(mut reads) => reads
+                .remove(key)
+                .ok_or_else(|| Box::new(CommunicationError::NoEntry).into()),
+            Output::Error(e) => Err(anyhow::anyhow!(e)),
+            _ => Err(Box::new(CommunicationError::UnexpectedOutput).into()),
+        }
+    }
+
     fn get_info(manager: &mut VaultManager) -> anyhow::Result<Info> {
         match manager.receive(ManagerMessage::Info)? {
             Output::Info(info) => Ok(info),
@@ -437,6 +1087,17 @@ impl CliApp {
         Ok(password.into())
     }
 
+    // without prompting, for when the cli is used from scripts
+    fn read_password() -> anyhow::Result<Password> {
+        if let Ok(password) = std::env::var("PANTS_PASSWORD") {
+            return Ok(password.into());
+        }
+        let mut password = String::new();
+        std::io::stdin().read_line(&mut password)?;
+        let password = password.trim_end_matches(['\r', '\n']).to_string();
+        Ok(password.into())
+    }
+
     fn get_password_confirm(prompt: &str) -> anyhow::Result<Password> {
         let password = inquire::Password::new(prompt)
             .with_display_toggle_enabled()
@@ -445,10 +1106,10 @@ impl CliApp {
         Ok(password.into())
     }
 
-    fn prompt(repr: &str, spec: PasswordSpec) -> anyhow::Result<Store> {
-        match repr {
-            "password" => Self::get_store_password(spec).map(Store::Password),
-            "username-password" => {
+    fn prompt(kind: StoreKind, spec: GenSpec) -> anyhow::Result<Store> {
+        match kind {
+            StoreKind::Password => Self::get_store_password(spec).map(Store::Password),
+            StoreKind::UsernamePassword => {
                 let username_input = inquire::Text::new("Username:")
                     .with_help_message("New username")
                     .prompt();
@@ -456,18 +1117,74 @@ impl CliApp {
                 let password = Self::get_store_password(spec)?;
                 Ok(Store::UsernamePassword(username.into(), password))
             }
-            _ => Err(Box::new(SchemaError::BadType).into()),
+            StoreKind::Login => {
+                let username = inquire::Text::new("Username:")
+                    .with_help_message("New username")
+                    .prompt()?;
+                let url = inquire::Text::new("URL:")
+                    .with_help_message("Where the login is used, can be left empty")
+                    .prompt()?;
+                let password = Self::get_store_password(spec)?;
+                Ok(Store::Login(username.into(), password, url.into()))
+            }
+            StoreKind::Totp => {
+                let secret = inquire::Password::new("Secret:")
+                    .without_confirmation()
+                    .with_display_toggle_enabled()
+                    .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                    .with_help_message("Base32 secret from the provider")
+                    .prompt()?;
+                let period = inquire::Text::new("Period:")
+                    .with_default(&DEFAULT_PERIOD.to_string())
+                    .prompt()?;
+                let digits = inquire::Text::new("Digits:")
+                    .with_default(&DEFAULT_DIGITS.to_string())
+                    .prompt()?;
+                let algorithm = inquire::Text::new("Algorithm:")
+                    .with_default(&TotpAlgorithm::default().to_string())
+                    .prompt()?;
+                let totp = Totp::from_fields(&secret, &period, &digits, &algorithm)?;
+                Ok(Store::Totp(totp))
+            }
+            StoreKind::Note => {
+                let note = inquire::Editor::new("Note:")
+                    .with_help_message("Opens your editor for the note's contents")
+                    .prompt()?;
+                Ok(Store::Note(note.into()))
+            }
+            StoreKind::Card => {
+                let cardholder = inquire::Text::new("Cardholder:")
+                    .with_help_message("Name on the card, can be left empty")
+                    .prompt()?;
+                let number = inquire::Password::new("Number:")
+                    .without_confirmation()
+                    .with_display_toggle_enabled()
+                    .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                    .prompt()?;
+                let expiry = inquire::Text::new("Expiry:")
+                    .with_help_message("MM/YY")
+                    .prompt()?;
+                let cvv = inquire::Password::new("CVV:")
+                    .without_confirmation()
+                    .with_display_toggle_enabled()
+                    .with_display_mode(inquire::PasswordDisplayMode::Masked)
+                    .with_help_message("Can be left empty")
+                    .prompt()?;
+                let card = Card::new(&number, &expiry, &cvv, &cardholder)?;
+                Ok(Store::Card(card))
+            }
+            StoreKind::Unknown => Err(Box::new(SchemaError::BadType).into()),
         }
     }
 
-    fn get_store_password(spec: PasswordSpec) -> anyhow::Result<Password> {
+    fn get_store_password(spec: GenSpec) -> anyhow::Result<Password> {
         let generate = Confirm::new("Generate password?")
             .with_default(true)
             .with_help_message("Create a random password or enter manually?")
             .prompt();
         match generate {
             Ok(true) => {
-                let password = spec.generate().ok_or(ClientError::BadPasswordSpec)?;
+                let password = spec.generate()?;
                 Ok(password.into())
             }
             Ok(false) => {
@@ -5,6 +5,7 @@ pub enum Command {
     Read { key: String },
     Update { key: String, value: Store },
     Delete { key: String },
+    Rename { from: String, to: String },
 }
 
 #[derive(Debug, Clone)]
@@ -1,10 +1,19 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::PathBuf,
+};
+
+use enum_iterator::Sequence;
 use figment::{
     value::{Dict, Map},
     Error, Metadata, Profile, Provider,
 };
 use iced::Theme;
 use pants_gen::password::PasswordSpec;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::schema::StoreKind;
 
 use super::internal_config::{BaseConfig, InternalConfig};
 
@@ -14,6 +23,110 @@ pub struct ClientConfig {
     // seconds
     pub clipboard_time: u64,
     pub theme: String,
+    // seconds without interaction before the session is locked, 0 disables it
+    pub auto_lock_seconds: u64,
+    // where vaults are kept when not the usual data directory
+    pub data_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub sort_order: SortOrder,
+    // days after an entry was last modified that it gets flagged for rotation, 0 disables it
+    pub rotation_days: u64,
+    // whether copying a whole entry includes its password
+    pub copy_entry_password: bool,
+    // secrets stay shown until hidden again instead of only while holding the button
+    pub sticky_reveal: bool,
+    // entries open with their secrets already shown, they can still be hidden again
+    pub reveal_secrets: bool,
+    // hours between automatic backups of a vault, 0 disables them
+    pub backup_interval_hours: u64,
+    // how many automatic backups of each vault are kept
+    pub backup_keep: usize,
+    // where automatic backups go when not next to the vaults
+    pub backup_dir: Option<PathBuf>,
+    // primary color for the custom theme as `#rrggbb`, ignored when it isn't valid
+    pub accent_color: Option<String>,
+    // logical size and position of the window when it was last changed
+    pub window_size: Option<(f32, f32)>,
+    pub window_position: Option<(i32, i32)>,
+    // vaults that were left expanded
+    #[serde(default)]
+    pub expanded: BTreeSet<String>,
+    // chord like `ctrl+shift+KeyP` that brings the window up from anywhere, off when missing
+    pub global_hotkey: Option<String>,
+    // keep running in the system tray when the window is closed
+    pub minimize_to_tray: bool,
+    // keep the window above the others, e.g. while copying into another app
+    pub always_on_top: bool,
+    // save vault passwords in the OS keychain after unlocking so they aren't asked for again
+    pub remember_passwords: bool,
+    // which selection copies go to, only different on Linux
+    #[serde(default)]
+    pub clipboard_target: ClipboardTarget,
+    // show a desktop notification once a copied secret is cleared off the clipboard
+    pub notify_on_clear: bool,
+    // what a new entry starts out as
+    #[serde(deserialize_with = "known_store_kind")]
+    pub default_store_type: StoreKind,
+    // named password specs to pick from when generating, also usable as the spec for `gen`
+    #[serde(default)]
+    pub presets: BTreeMap<String, String>,
+    // how many passwords a new entry gets to pick from each time one is generated
+    pub password_candidates: usize,
+}
+
+// more than this and the list gets in the way of the form
+pub const MAX_CANDIDATES: usize = 8;
+
+impl ClientConfig {
+    pub fn password_candidates(&self) -> usize {
+        self.password_candidates.clamp(1, MAX_CANDIDATES)
+    }
+}
+
+// a type this version doesn't know about falls back to the usual one
+fn known_store_kind<'de, D: Deserializer<'de>>(deserializer: D) -> Result<StoreKind, D::Error> {
+    match StoreKind::deserialize(deserializer)? {
+        StoreKind::Unknown => Ok(ClientConfig::default().default_store_type),
+        kind => Ok(kind),
+    }
+}
+
+// the primary selection is what gets pasted with a middle click
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Sequence)]
+pub enum ClipboardTarget {
+    #[default]
+    Clipboard,
+    Primary,
+}
+
+impl Display for ClipboardTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Clipboard => write!(f, "Clipboard"),
+            Self::Primary => write!(f, "Primary Selection"),
+        }
+    }
+}
+
+// how the entries of a vault are listed, favorites still come first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Sequence)]
+pub enum SortOrder {
+    #[default]
+    Alphabetical,
+    RecentlyUsed,
+    RecentlyModified,
+    Custom,
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Alphabetical => write!(f, "Alphabetical"),
+            Self::RecentlyUsed => write!(f, "Recently Used"),
+            Self::RecentlyModified => write!(f, "Recently Modified"),
+            Self::Custom => write!(f, "Custom"),
+        }
+    }
 }
 
 impl Default for ClientConfig {
@@ -22,6 +135,37 @@ impl Default for ClientConfig {
             password_spec: PasswordSpec::default().to_string(),
             clipboard_time: 10,
             theme: Theme::default().to_string(),
+            auto_lock_seconds: 300,
+            data_dir: None,
+            sort_order: SortOrder::default(),
+            rotation_days: 180,
+            copy_entry_password: true,
+            sticky_reveal: false,
+            reveal_secrets: false,
+            backup_interval_hours: 24,
+            backup_keep: 10,
+            backup_dir: None,
+            accent_color: None,
+            window_size: None,
+            window_position: None,
+            expanded: BTreeSet::new(),
+            global_hotkey: None,
+            minimize_to_tray: false,
+            always_on_top: false,
+            remember_passwords: false,
+            clipboard_target: ClipboardTarget::default(),
+            notify_on_clear: false,
+            default_store_type: StoreKind::UsernamePassword,
+            presets: BTreeMap::from_iter(
+                [
+                    ("PIN", "pin:6"),
+                    ("Strong 20", "classes:20:ulds"),
+                    ("Passphrase 5", "words:5:-"),
+                    ("Alphanumeric 16", "classes:16:uld"),
+                ]
+                .map(|(name, spec)| (name.to_string(), spec.to_string())),
+            ),
+            password_candidates: 1,
         }
     }
 }
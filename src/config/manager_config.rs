@@ -1,24 +1,114 @@
 use std::{collections::BTreeMap, path::PathBuf};
 
 use figment::{
+    providers::Format,
     value::{Dict, Map},
-    Error, Metadata, Profile, Provider,
+    Error, Figment, Metadata, Profile, Provider,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::config::internal_config::InternalConfig;
+use crate::{
+    config::internal_config::InternalConfig, errors::VaultNameError, secure::KdfParams, utils,
+};
 
 use super::internal_config::BaseConfig;
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ManagerConfig {
     pub map: BTreeMap<String, PathBuf>,
+    // the costs for keys made from now on, vaults keep using the ones they were made with
+    #[serde(default)]
+    pub kdf: KdfParams,
+    // keep a log of what was done to the vaults, none of the values are written to it
+    #[serde(default = "audit_default")]
+    pub audit_log: bool,
+    // where the log goes when not next to the vaults
+    #[serde(default)]
+    pub audit_path: Option<PathBuf>,
+    // wrong passwords in a row before a vault can't be tried for a while, 0 never locks it
+    #[serde(default = "unlock_attempts_default")]
+    pub unlock_attempts: u32,
+    // how long the first lockout lasts, doubling with every wrong password after it
+    #[serde(default = "lockout_seconds_default")]
+    pub lockout_seconds: u64,
+    // where the config and new vaults go, not written out since it is where the config is read from
+    #[serde(skip, default = "utils::base_path")]
+    dir: PathBuf,
+}
+
+impl Default for ManagerConfig {
+    fn default() -> Self {
+        Self {
+            map: BTreeMap::new(),
+            kdf: KdfParams::default(),
+            audit_log: true,
+            audit_path: None,
+            unlock_attempts: unlock_attempts_default(),
+            lockout_seconds: lockout_seconds_default(),
+            dir: utils::base_path(),
+        }
+    }
+}
+
+impl ManagerConfig {
+    pub fn load_dir(dir: PathBuf) -> anyhow::Result<Self> {
+        let mut path = dir.clone();
+        path.push(<Self as InternalConfig>::name());
+        let figment = Self::figment().merge(figment::providers::Toml::file_exact(path));
+        let mut config = match figment.extract::<Self>() {
+            Ok(config) => config,
+            Err(_e) => Self::default(),
+        };
+        config.dir = dir;
+        config.save()?;
+        Ok(config)
+    }
+
+    // hidden so it doesn't get confused for a vault
+    pub fn backup_dir(&self) -> PathBuf {
+        let mut path = self.dir.clone();
+        path.push(".backups");
+        path
+    }
+
+    pub fn audit_path(&self) -> PathBuf {
+        self.audit_path.clone().unwrap_or_else(|| {
+            let mut path = self.dir.clone();
+            path.push("audit.log");
+            path
+        })
+    }
+
+    // the name becomes a file name, so it's checked here rather than trusting every caller
+    pub fn vault_path(&self, name: &str) -> Result<PathBuf, VaultNameError> {
+        let mut path = self.dir.clone();
+        path.push(utils::vault_name(name)?);
+        Ok(path)
+    }
+}
+
+fn audit_default() -> bool {
+    true
+}
+
+fn unlock_attempts_default() -> u32 {
+    5
+}
+
+fn lockout_seconds_default() -> u64 {
+    30
 }
 
 impl<'de> InternalConfig<'de> for ManagerConfig {
     fn name() -> String {
         "pants.toml".into()
     }
+
+    fn path(&self) -> PathBuf {
+        let mut path = self.dir.clone();
+        path.push(Self::name());
+        path
+    }
 }
 
 impl<'de> BaseConfig<'de> for ManagerConfig {}
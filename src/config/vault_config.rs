@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 
 use figment::{
+    providers::Format,
     value::{Dict, Map},
-    Error, Metadata, Profile, Provider,
+    Error, Figment, Metadata, Profile, Provider,
 };
 use serde::{Deserialize, Serialize};
 
@@ -12,19 +16,64 @@ use super::internal_config::InternalConfig;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VaultConfig {
+    // the directory is where the config is found, so it doesn't need to be written out
+    #[serde(skip)]
     save_dir: PathBuf,
+    pub password_spec: Option<String>,
+    // entries that are listed first, only the names so nothing secret ends up here
+    #[serde(default)]
+    pub favorites: BTreeSet<String>,
+    // positions of the entries when using a custom sort order
+    #[serde(default)]
+    pub order: BTreeMap<String, usize>,
+    // free-form labels of the entries, entries without any aren't listed
+    #[serde(default)]
+    pub tags: BTreeMap<String, BTreeSet<String>>,
+    // remake the key with the configured costs the next time the vault is unlocked
+    #[serde(default)]
+    pub reharden: bool,
 }
 
 impl Default for VaultConfig {
     fn default() -> Self {
         let save_dir = utils::base_path();
-        Self { save_dir }
+        Self {
+            save_dir,
+            password_spec: None,
+            favorites: BTreeSet::new(),
+            order: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            reharden: false,
+        }
     }
 }
 
 impl VaultConfig {
     pub fn new(save_dir: PathBuf) -> Self {
-        Self { save_dir }
+        Self {
+            save_dir,
+            password_spec: None,
+            favorites: BTreeSet::new(),
+            order: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            reharden: false,
+        }
+    }
+    // a vault without a config file yet just uses the defaults
+    pub fn load_dir(save_dir: PathBuf) -> Self {
+        let mut config = Self::new(save_dir);
+        let path = config.path();
+        if let Ok(loaded) = Figment::from(Self::default())
+            .merge(figment::providers::Toml::file_exact(path))
+            .extract::<Self>()
+        {
+            config.password_spec = loaded.password_spec;
+            config.favorites = loaded.favorites;
+            config.order = loaded.order;
+            config.tags = loaded.tags;
+            config.reharden = loaded.reharden;
+        }
+        config
     }
     pub fn save_dir(&self) -> SaveDir {
         SaveDir::new(self.save_dir.to_path_buf())
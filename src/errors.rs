@@ -20,6 +20,8 @@ pub enum EncryptionError {
 pub enum DecryptionError {
     #[error("Failed to decrypt data")]
     Decryption,
+    #[error("Invalid password")]
+    InvalidPassword,
 }
 
 #[derive(Error, Debug)]
@@ -28,6 +30,64 @@ pub enum SchemaError {
     BadType,
     #[error("Invalid values for creating data to store")]
     BadValues,
+    #[error("TOTP secret is not valid base32")]
+    BadTotpSecret,
+    #[error("Invalid TOTP period, digits, or algorithm")]
+    BadTotpParameter,
+    #[error("Not a TOTP URI, expected otpauth://totp/...")]
+    BadTotpUri,
+    #[error("TOTP URI is missing the {0} parameter")]
+    MissingTotpParameter(&'static str),
+    #[error("Card number is not valid")]
+    BadCardNumber,
+    #[error("Card expiry should be MM/YY")]
+    BadCardExpiry,
+    #[error("Card CVV should be 3 or 4 digits")]
+    BadCardCvv,
+}
+
+#[derive(Error, Debug)]
+pub enum VaultNameError {
+    #[error("Need a name to create vault")]
+    Empty,
+    #[error("Vault names can't contain {0:?}")]
+    BadCharacter(char),
+    #[error("Vault names can't be {0:?}")]
+    Reserved(String),
+}
+
+#[derive(Error, Debug)]
+pub enum KeyError {
+    #[error("Entry names can't be empty")]
+    Empty,
+    #[error("Entry names can't contain {0:?}")]
+    BadCharacter(char),
+}
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Not a pants archive")]
+    NotArchive,
+    #[error("Archive version {0} is not supported")]
+    UnsupportedVersion(u16),
+    #[error("Archive could not be read back after writing it")]
+    Unverified,
+}
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("{0} and {1} would both be exported as {2}")]
+    SameVariable(String, String, String),
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Could not read the import file: {0}")]
+    Malformed(String),
+    #[error("Row {0} has no key")]
+    MissingKey(usize),
+    #[error("Entry {0} is invalid: {1}")]
+    BadEntry(String, SchemaError),
 }
 
 #[derive(Error, Debug)]
@@ -42,10 +102,18 @@ pub enum CommunicationError {
 
 #[derive(Debug, Error)]
 pub enum ClientError {
-    #[error("Contraints for the password spec could not be met")]
+    #[error("Constraints for the password spec could not be met")]
     BadPasswordSpec,
 }
 
+#[derive(Debug, Error)]
+pub enum SpecError {
+    #[error("Invalid password spec: {0}")]
+    Invalid(String),
+    #[error("Constraints for the password spec could not be met")]
+    Unsatisfiable,
+}
+
 #[derive(Debug, Error)]
 pub enum ManagerError {
     #[error("Vault already exists")]
@@ -54,4 +122,12 @@ pub enum ManagerError {
     VaultDoesNotExist,
     #[error("Tried to delete a non-empty vault")]
     NonEmptyVault,
+    #[error("Invalid data directory: {0}")]
+    InvalidDataDir(String),
+    #[error("Invalid key derivation settings: {0}")]
+    InvalidKdf(String),
+    #[error("Vault has no recovery key")]
+    NoRecoveryKey,
+    #[error("Locked out after too many wrong passwords, try again in {0}s")]
+    LockedOut(u64),
 }
@@ -0,0 +1,174 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use secrecy::{ExposeSecret, Secret};
+
+use crate::{errors::ExportError, reads::Reads, store::Store};
+
+// every field any kind of entry can have, each one gets a column
+pub const FIELDS: [&str; 13] = [
+    "username",
+    "password",
+    "url",
+    "secret",
+    "period",
+    "digits",
+    "algorithm",
+    "note",
+    "notes",
+    "cardholder",
+    "number",
+    "expiry",
+    "cvv",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+        }
+    }
+
+    // the output holds the plaintext of every entry so it stays wrapped as a secret
+    pub fn export(&self, entries: Reads<Store>) -> anyhow::Result<Secret<String>> {
+        match self {
+            Self::Csv => Self::to_csv(entries),
+        }
+    }
+
+    fn to_csv(entries: Reads<Store>) -> anyhow::Result<Secret<String>> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        let mut header = vec!["key", "type"];
+        header.extend(FIELDS);
+        writer.write_record(&header)?;
+        // sorted so that exports of the same vault line up
+        let entries = entries.data.into_iter().collect::<BTreeMap<_, _>>();
+        for (key, value) in entries {
+            let fields = value.as_hash();
+            let mut row = vec![key, value.repr()];
+            row.extend(FIELDS.iter().map(|field| {
+                fields
+                    .get(*field)
+                    .map(|v| v.expose_secret().to_string())
+                    .unwrap_or_default()
+            }));
+            writer.write_record(&row)?;
+        }
+        let data = String::from_utf8(writer.into_inner()?)?;
+        Ok(data.into())
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+        }
+    }
+}
+
+// `KEY='value'` lines of every entry's password, sorted by variable name so it can be sourced by
+// a shell, entries without a password are left out
+pub fn to_env(
+    entries: Reads<Store>,
+    prefix: Option<&str>,
+    export: bool,
+) -> Result<Secret<String>, ExportError> {
+    // sorted first so the same two keys are named when they clash
+    let mut variables = BTreeMap::new();
+    for (key, value) in entries.data.into_iter().collect::<BTreeMap<_, _>>() {
+        let Some(password) = value.as_hash().shift_remove("password") else {
+            continue;
+        };
+        let name = env_name(prefix, &key);
+        // one would silently replace the other in the shell
+        if let Some((other, _)) = variables.get(&name) {
+            return Err(ExportError::SameVariable(other.clone(), key, name));
+        }
+        variables.insert(name, (key, password));
+    }
+    let keyword = if export { "export " } else { "" };
+    Ok(variables
+        .into_iter()
+        .map(|(name, (_, value))| {
+            format!(
+                "{}{}={}\n",
+                keyword,
+                name,
+                shell_quote(value.expose_secret())
+            )
+        })
+        .collect::<String>()
+        .into())
+}
+
+// uppercased with anything that isn't allowed in a shell identifier replaced by `_`, e.g.
+// `github.com` with the prefix `work` is `WORK_GITHUB_COM`
+pub fn env_name(prefix: Option<&str>, key: &str) -> String {
+    let name = match prefix.filter(|prefix| !prefix.is_empty()) {
+        Some(prefix) => format!("{}_{}", prefix.trim_end_matches('_'), key),
+        None => key.to_string(),
+    };
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name.is_empty() {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+// single quotes keep everything literal, a quote itself has to end the string and be escaped
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(keys: &[&str]) -> Reads<Store> {
+        let mut reads = Reads::new();
+        for key in keys {
+            reads.insert(
+                key.to_string(),
+                Store::Password(format!("{}'s", key).into()),
+            );
+        }
+        reads
+    }
+
+    #[test]
+    fn env_lines() {
+        let env = to_env(entries(&["github.com", "1password"]), Some("work"), true).unwrap();
+        assert_eq!(
+            env.expose_secret(),
+            "export WORK_1PASSWORD='1password'\\''s'\nexport WORK_GITHUB_COM='github.com'\\''s'\n"
+        );
+    }
+
+    #[test]
+    fn clashing_names() {
+        match to_env(entries(&["github.com", "github_com"]), None, false) {
+            Err(ExportError::SameVariable(first, second, name)) => {
+                assert_eq!(first, "github.com");
+                assert_eq!(second, "github_com");
+                assert_eq!(name, "GITHUB_COM");
+            }
+            other => panic!("expected a clash, got {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     fs::{self, File},
     io::{Read, Write},
     marker::PhantomData,
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use chrono::{DateTime, Local};
@@ -14,7 +14,10 @@ use crate::{
     errors::SaveError,
     schema::Schema,
     utils::{format_date, now, read_date},
-    vault::encrypted::{RecordEncrypted, VaultEncrypted},
+    vault::{
+        encrypted::{RecordEncrypted, VaultEncrypted},
+        recovery::Recovery,
+    },
 };
 
 pub trait ProjectFile<'de, Data>
@@ -187,6 +190,7 @@ pub type VaultFile = NonTimestampedFile<VaultEncrypted>;
 pub type RecordFile = TimestampedFile<RecordEncrypted>;
 pub type BackupFile = TimestampedFile<VaultEncrypted>;
 pub type SchemaFile = NonTimestampedFile<Schema>;
+pub type RecoveryFile = NonTimestampedFile<Recovery>;
 
 pub trait Name {
     fn name() -> String;
@@ -216,6 +220,12 @@ impl Name for SchemaFile {
     }
 }
 
+impl Name for RecoveryFile {
+    fn name() -> String {
+        "recovery".to_string()
+    }
+}
+
 pub struct SaveDir {
     base_path: PathBuf,
 }
@@ -229,6 +239,14 @@ impl SaveDir {
         fs::remove_dir_all(&self.base_path)
     }
 
+    pub fn exists(&self) -> bool {
+        self.base_path.exists()
+    }
+
+    pub fn rename(&self, to: &Path) -> Result<(), std::io::Error> {
+        fs::rename(&self.base_path, to)
+    }
+
     pub fn vault_file(&self) -> VaultFile {
         self.nontimestamped_file()
     }
@@ -237,6 +255,10 @@ impl SaveDir {
         self.nontimestamped_file()
     }
 
+    pub fn recovery_file(&self) -> RecoveryFile {
+        self.nontimestamped_file()
+    }
+
     pub fn record_file(&self) -> RecordFile {
         self.timestamped_file()
     }
@@ -0,0 +1,155 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::{rngs::OsRng, seq::SliceRandom};
+
+use crate::errors::SpecError;
+
+pub const PREFIX: &str = "classes:";
+// `pin:<length>` is shorthand for a digits only spec, e.g. `pin:6`
+pub const PIN_PREFIX: &str = "pin:";
+pub const MAX_PIN_LENGTH: usize = 64;
+
+pub const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+pub const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.<>?/~";
+
+// written as `classes:<length>:<flags>` where the flags pick the character classes, `u` for
+// uppercase, `l` for lowercase, `d` for digits, and `s` for symbols, e.g. `classes:20:uld`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassSpec {
+    pub length: usize,
+    pub upper: bool,
+    pub lower: bool,
+    pub digits: bool,
+    pub symbols: bool,
+}
+
+impl Default for ClassSpec {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            upper: true,
+            lower: true,
+            digits: true,
+            symbols: true,
+        }
+    }
+}
+
+impl ClassSpec {
+    // the digits are uniformly random, so nothing stops e.g. `000000` from coming up
+    pub fn pin(length: usize) -> Self {
+        Self {
+            length,
+            upper: false,
+            lower: false,
+            digits: true,
+            symbols: false,
+        }
+    }
+
+    // parses everything after the `pin:` prefix, the length has to be between 1 and
+    // `MAX_PIN_LENGTH`
+    pub fn pin_from_str(s: &str) -> Result<Self, SpecError> {
+        s.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|l| (1..=MAX_PIN_LENGTH).contains(l))
+            .map(Self::pin)
+            .ok_or_else(|| {
+                SpecError::Invalid(format!(
+                    "pin length must be between 1 and {}",
+                    MAX_PIN_LENGTH
+                ))
+            })
+    }
+
+    pub fn is_pin(&self) -> bool {
+        *self == Self::pin(self.length)
+    }
+
+    fn pools(&self) -> Vec<Vec<char>> {
+        [
+            (self.upper, UPPER),
+            (self.lower, LOWER),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(used, _)| *used)
+        .map(|(_, pool)| pool.chars().collect())
+        .collect()
+    }
+
+    // at least one character from every chosen class, the rest from any of them
+    pub fn generate(&self) -> Option<String> {
+        let pools = self.pools();
+        if pools.is_empty() || self.length < pools.len() {
+            return None;
+        }
+        let mut rng = OsRng;
+        let mut chars = pools
+            .iter()
+            .map(|pool| pool.choose(&mut rng).copied())
+            .collect::<Option<Vec<_>>>()?;
+        let all = pools.concat();
+        for _ in chars.len()..self.length {
+            chars.push(*all.choose(&mut rng)?);
+        }
+        chars.shuffle(&mut rng);
+        Some(chars.into_iter().collect())
+    }
+}
+
+// parses everything after the `classes:` prefix
+impl FromStr for ClassSpec {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (length, flags) = s.split_once(':').unwrap_or((s, "ulds"));
+        let length = length
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|l| *l > 0)
+            .ok_or_else(|| SpecError::Invalid("length must be a positive number".into()))?;
+        let mut spec = ClassSpec {
+            length,
+            upper: false,
+            lower: false,
+            digits: false,
+            symbols: false,
+        };
+        for flag in flags.chars() {
+            match flag {
+                'u' => spec.upper = true,
+                'l' => spec.lower = true,
+                'd' => spec.digits = true,
+                's' => spec.symbols = true,
+                _ => return Err(SpecError::Invalid(format!("unknown class flag '{}'", flag))),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl Display for ClassSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_pin() && self.length <= MAX_PIN_LENGTH {
+            return write!(f, "{}{}", PIN_PREFIX, self.length);
+        }
+        write!(f, "{}{}:", PREFIX, self.length)?;
+        for (used, flag) in [
+            (self.upper, 'u'),
+            (self.lower, 'l'),
+            (self.digits, 'd'),
+            (self.symbols, 's'),
+        ] {
+            if used {
+                write!(f, "{}", flag)?;
+            }
+        }
+        Ok(())
+    }
+}
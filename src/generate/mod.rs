@@ -0,0 +1,141 @@
+use std::{fmt::Display, str::FromStr};
+
+use pants_gen::password::PasswordSpec;
+use rand::{rngs::OsRng, seq::IteratorRandom};
+
+use crate::errors::SpecError;
+
+pub mod classes;
+pub mod pronounce;
+pub mod words;
+
+use classes::ClassSpec;
+use pronounce::PronounceSpec;
+use words::WordSpec;
+
+// prefix on a character spec to leave out the characters that are easily confused
+pub const UNAMBIGUOUS_PREFIX: &str = "unambiguous:";
+pub const AMBIGUOUS: &[char] = &['l', '1', 'I', 'O', '0'];
+
+// pants-gen doesn't expose the character pools, so each ambiguous character is swapped for one
+// drawn from the rest of its class, which is the same as leaving it out of the pool and keeps
+// the spec's minimum counts intact
+fn unambiguous(c: char) -> char {
+    let pool = if c.is_ascii_uppercase() {
+        classes::UPPER
+    } else if c.is_ascii_lowercase() {
+        classes::LOWER
+    } else {
+        classes::DIGITS
+    };
+    pool.chars()
+        .filter(|c| !AMBIGUOUS.contains(c))
+        .choose(&mut OsRng)
+        .unwrap_or(c)
+}
+
+// the character class specs from pants-gen plus the other styles of generation that it doesn't
+// cover, all of them can be written as a single string in the config
+pub enum GenSpec {
+    Chars {
+        spec: PasswordSpec,
+        exclude_ambiguous: bool,
+    },
+    Words(WordSpec),
+    Classes(ClassSpec),
+    Pronounce(PronounceSpec),
+}
+
+impl GenSpec {
+    pub fn generate(&self) -> Result<String, SpecError> {
+        match self {
+            Self::Chars {
+                spec,
+                exclude_ambiguous: false,
+            } => spec.generate().ok_or(SpecError::Unsatisfiable),
+            Self::Chars {
+                spec,
+                exclude_ambiguous: true,
+            } => {
+                let password = spec.generate().ok_or(SpecError::Unsatisfiable)?;
+                Ok(password
+                    .chars()
+                    .map(|c| {
+                        if AMBIGUOUS.contains(&c) {
+                            unambiguous(c)
+                        } else {
+                            c
+                        }
+                    })
+                    .collect())
+            }
+            Self::Words(spec) => spec.generate().ok_or(SpecError::Unsatisfiable),
+            Self::Classes(spec) => spec.generate().ok_or(SpecError::Unsatisfiable),
+            Self::Pronounce(spec) => spec.generate().ok_or(SpecError::Unsatisfiable),
+        }
+    }
+
+    // bits from how the password is made when the characters alone would overstate it
+    pub fn entropy(&self) -> Option<f32> {
+        match self {
+            Self::Pronounce(spec) => Some(spec.entropy()),
+            _ => None,
+        }
+    }
+
+    pub fn exclude_ambiguous(self, value: bool) -> Self {
+        match self {
+            Self::Chars { spec, .. } => Self::Chars {
+                spec,
+                exclude_ambiguous: value,
+            },
+            other => other,
+        }
+    }
+}
+
+impl FromStr for GenSpec {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(rest) = s.strip_prefix(words::PREFIX) {
+            WordSpec::from_str(rest).map(Self::Words)
+        } else if let Some(rest) = s.strip_prefix(classes::PREFIX) {
+            ClassSpec::from_str(rest).map(Self::Classes)
+        } else if let Some(rest) = s.strip_prefix(classes::PIN_PREFIX) {
+            ClassSpec::pin_from_str(rest).map(Self::Classes)
+        } else if let Some(rest) = s.strip_prefix(pronounce::PREFIX) {
+            PronounceSpec::from_str(rest).map(Self::Pronounce)
+        } else {
+            let (rest, exclude_ambiguous) = match s.strip_prefix(UNAMBIGUOUS_PREFIX) {
+                Some(rest) => (rest, true),
+                None => (s, false),
+            };
+            PasswordSpec::from_str(rest)
+                .map(|spec| Self::Chars {
+                    spec,
+                    exclude_ambiguous,
+                })
+                .map_err(|e| SpecError::Invalid(e.to_string()))
+        }
+    }
+}
+
+impl Display for GenSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Chars {
+                spec,
+                exclude_ambiguous,
+            } => {
+                if *exclude_ambiguous {
+                    write!(f, "{}", UNAMBIGUOUS_PREFIX)?;
+                }
+                write!(f, "{}", spec)
+            }
+            Self::Words(spec) => write!(f, "{}", spec),
+            Self::Classes(spec) => write!(f, "{}", spec),
+            Self::Pronounce(spec) => write!(f, "{}", spec),
+        }
+    }
+}
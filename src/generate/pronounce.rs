@@ -0,0 +1,144 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::{rngs::OsRng, seq::SliceRandom, Rng};
+
+use crate::errors::SpecError;
+
+pub const PREFIX: &str = "pronounce:";
+
+// letters that read badly in a syllable are left out, e.g. `q` and `x`
+const CONSONANTS: &str = "bcdfghjklmnprstvwz";
+const VOWELS: &str = "aeiou";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*-_=+?";
+
+// written as `pronounce:<length>[:<flags>]` where the flags are `d` to put a digit and `s` to put
+// a symbol somewhere in it, e.g. `pronounce:12` or `pronounce:16:ds`, the letters alternate
+// between consonants and vowels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PronounceSpec {
+    pub length: usize,
+    pub digit: bool,
+    pub symbol: bool,
+}
+
+impl Default for PronounceSpec {
+    fn default() -> Self {
+        Self {
+            length: 16,
+            digit: true,
+            symbol: false,
+        }
+    }
+}
+
+impl PronounceSpec {
+    fn inserted(&self) -> usize {
+        self.digit as usize + self.symbol as usize
+    }
+
+    pub fn generate(&self) -> Option<String> {
+        let letters = self
+            .length
+            .checked_sub(self.inserted())
+            .filter(|l| *l > 0)?;
+        let mut rng = OsRng;
+        let consonants = CONSONANTS.chars().collect::<Vec<_>>();
+        let vowels = VOWELS.chars().collect::<Vec<_>>();
+        let start_vowel = rng.gen_bool(0.5);
+        let mut chars = (0..letters)
+            .map(|i| {
+                let pool = if (i % 2 == 0) == start_vowel {
+                    &vowels
+                } else {
+                    &consonants
+                };
+                pool.choose(&mut rng).copied()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        for (used, pool) in [(self.digit, DIGITS), (self.symbol, SYMBOLS)] {
+            if used {
+                let c = pool.chars().collect::<Vec<_>>();
+                let at = rng.gen_range(0..=chars.len());
+                chars.insert(at, *c.choose(&mut rng)?);
+            }
+        }
+        Some(chars.into_iter().collect())
+    }
+
+    // what the pattern actually gives, guessing from the characters alone would count every
+    // letter as one of 26 when each is only one of the consonants or one of the vowels
+    pub fn entropy(&self) -> f32 {
+        let letters = self.length.saturating_sub(self.inserted());
+        if letters == 0 {
+            return 0.0;
+        }
+        let vowels = letters / 2;
+        let consonants = letters - vowels;
+        let mut bits = 1.0
+            + consonants as f32 * (CONSONANTS.len() as f32).log2()
+            + vowels as f32 * (VOWELS.len() as f32).log2();
+        let mut positions = letters;
+        for (used, pool) in [(self.digit, DIGITS), (self.symbol, SYMBOLS)] {
+            if used {
+                positions += 1;
+                bits += (pool.len() as f32).log2() + (positions as f32).log2();
+            }
+        }
+        bits
+    }
+}
+
+// parses everything after the `pronounce:` prefix
+impl FromStr for PronounceSpec {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (length, flags) = s.split_once(':').unwrap_or((s, ""));
+        let length = length
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|l| *l > 0)
+            .ok_or_else(|| SpecError::Invalid("length must be a positive number".into()))?;
+        let mut spec = PronounceSpec {
+            length,
+            digit: false,
+            symbol: false,
+        };
+        for flag in flags.chars() {
+            match flag {
+                'd' => spec.digit = true,
+                's' => spec.symbol = true,
+                _ => {
+                    return Err(SpecError::Invalid(format!(
+                        "unknown pronounceable flag '{}'",
+                        flag
+                    )))
+                }
+            }
+        }
+        if spec.length <= spec.inserted() {
+            return Err(SpecError::Invalid(
+                "length must leave room for letters".into(),
+            ));
+        }
+        Ok(spec)
+    }
+}
+
+impl Display for PronounceSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", PREFIX, self.length)?;
+        if self.digit || self.symbol {
+            write!(f, ":")?;
+            if self.digit {
+                write!(f, "d")?;
+            }
+            if self.symbol {
+                write!(f, "s")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fmt::Display, str::FromStr};
+
+use once_cell::sync::Lazy;
+use rand::{rngs::OsRng, seq::SliceRandom, Rng};
+
+use crate::errors::SpecError;
+
+pub const PREFIX: &str = "words:";
+
+// compiled in so that generating doesn't depend on any files at runtime, the EFF large wordlist
+// can be copied in as it's published since the dice rolls in front of each word are skipped
+static WORDS: Lazy<Vec<&'static str>> = Lazy::new(|| {
+    include_str!("wordlist.txt")
+        .lines()
+        .filter_map(|line| line.split_whitespace().last())
+        .collect()
+});
+
+// written as `words:<count>[:<separator>[:<flags>]]` where the flags are `c` to capitalize each
+// word and `d` to add a trailing digit, e.g. `words:5:-` or `words:4:.:cd`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordSpec {
+    pub count: usize,
+    pub separator: String,
+    pub capitalize: bool,
+    pub digit: bool,
+}
+
+impl Default for WordSpec {
+    fn default() -> Self {
+        Self {
+            count: 5,
+            separator: "-".to_string(),
+            capitalize: false,
+            digit: false,
+        }
+    }
+}
+
+impl WordSpec {
+    pub fn generate(&self) -> Option<String> {
+        let mut rng = OsRng;
+        let words = (0..self.count)
+            .map(|_| {
+                WORDS.choose(&mut rng).map(|word| {
+                    if self.capitalize {
+                        capitalize(word)
+                    } else {
+                        word.to_string()
+                    }
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let mut phrase = words.join(&self.separator);
+        if self.digit {
+            phrase.push(char::from_digit(rng.gen_range(0..10), 10)?);
+        }
+        Some(phrase)
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// parses everything after the `words:` prefix
+impl FromStr for WordSpec {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, ':');
+        let count = parts
+            .next()
+            .and_then(|c| c.trim().parse::<usize>().ok())
+            .filter(|c| *c > 0)
+            .ok_or_else(|| SpecError::Invalid("word count must be a positive number".into()))?;
+        let separator = parts
+            .next()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| WordSpec::default().separator);
+        let mut spec = WordSpec {
+            count,
+            separator,
+            ..Default::default()
+        };
+        for flag in parts.next().unwrap_or_default().chars() {
+            match flag {
+                'c' => spec.capitalize = true,
+                'd' => spec.digit = true,
+                _ => return Err(SpecError::Invalid(format!("unknown word flag '{}'", flag))),
+            }
+        }
+        Ok(spec)
+    }
+}
+
+impl Display for WordSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}:{}", PREFIX, self.count, self.separator)?;
+        if self.capitalize || self.digit {
+            write!(f, ":")?;
+            if self.capitalize {
+                write!(f, "c")?;
+            }
+            if self.digit {
+                write!(f, "d")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wordlist_is_full_dice_list() {
+        assert_eq!(WORDS.len(), 7776);
+    }
+
+    #[test]
+    fn spec_round_trips() {
+        let spec: WordSpec = "4:.:cd".parse().unwrap();
+        assert_eq!(spec.to_string(), "words:4:.:cd");
+        let phrase = spec.generate().unwrap();
+        assert_eq!(phrase.split('.').count(), 4);
+        assert!(phrase.ends_with(|c: char| c.is_ascii_digit()));
+    }
+}
@@ -1,15 +1,24 @@
+use std::{path::PathBuf, time::Duration};
+
 use iced::{
     futures::{channel::mpsc, SinkExt},
     subscription::{self, Subscription},
 };
 
-use crate::{manager_message::ManagerMessage, output::Output, vault::manager::VaultManager};
+use crate::{
+    errors::ManagerError, manager_message::ManagerMessage, output::Output,
+    vault::manager::VaultManager,
+};
 #[derive(Debug, Clone)]
 pub enum Event {
     Connected(Connection),
+    // waiting the given time before trying again
+    Connecting(Duration),
+    ConnectionFailed(String),
     Disconnected,
-    ReceiveOutput(Output),
-    ReceiveError(String),
+    // the id of the request being answered comes first
+    ReceiveOutput(u64, Output),
+    ReceiveError(u64, String),
     // ReceiveSchema(Schema),
     // ReceiveRead(Reads<Store>),
     // ReceiveList(Vec<String>),
@@ -18,70 +27,80 @@ pub enum Event {
     // ReceiveNothing,
 }
 
-impl From<Output> for Event {
-    fn from(value: Output) -> Self {
-        Self::ReceiveOutput(value)
-        // match value {
-        //     Output::Schema(s) => Self::ReceiveSchema(s),
-        //     Output::Read(r) => Self::ReceiveRead(r),
-        //     Output::List(l) => Self::ReceiveList(l),
-        //     Output::Backup(b) => Self::ReceiveBackup(b),
-        //     Output::BackupFiles(f) => Self::ReceiveBackupFiles(f),
-        //     Output::Nothing => Self::ReceiveNothing,
-        // }
-    }
-}
+// how long to wait between attempts to bring up the manager, doubling after every failure
+const MIN_BACKOFF: Duration = Duration::from_millis(250);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
 
-#[derive(Debug)]
 enum State {
     Starting,
-    Connected(mpsc::Receiver<ManagerMessage>),
+    Connected(mpsc::Receiver<(u64, ManagerMessage)>, VaultManager),
 }
 
 #[derive(Debug, Clone)]
-pub struct Connection(mpsc::Sender<ManagerMessage>);
+pub struct Connection(mpsc::Sender<(u64, ManagerMessage)>);
 
 impl Connection {
-    pub fn send(&mut self, message: ManagerMessage) {
+    // the id is given back with the answer so it can be matched up with the request
+    pub fn send(&mut self, id: u64, message: ManagerMessage) {
         self.0
-            .try_send(message)
+            .try_send((id, message))
             .expect("Send message to echo server");
     }
 }
-pub fn connect() -> Subscription<Event> {
+pub fn connect(data_dir: Option<PathBuf>) -> Subscription<Event> {
     struct Connect;
-    let mut interface = VaultManager::default();
     subscription::channel(
         std::any::TypeId::of::<Connect>(),
         100,
         |mut output| async move {
             let mut state = State::Starting;
+            let mut backoff = MIN_BACKOFF;
 
             loop {
                 match &mut state {
-                    State::Starting => {
-                        let (sender, receiver) = mpsc::channel(100);
+                    State::Starting => match VaultManager::with_dir(data_dir.clone()) {
+                        Ok(interface) => {
+                            let (sender, receiver) = mpsc::channel(100);
 
-                        let _ = output.send(Event::Connected(Connection(sender))).await;
-                        state = State::Connected(receiver);
-                    }
-                    State::Connected(receiver) => {
+                            let _ = output.send(Event::Connected(Connection(sender))).await;
+                            state = State::Connected(receiver, interface);
+                            backoff = MIN_BACKOFF;
+                        }
+                        Err(e) => {
+                            let _ = output.send(Event::ConnectionFailed(e.to_string())).await;
+                            let _ = output.send(Event::Connecting(backoff)).await;
+                            async_std::task::sleep(backoff).await;
+                            backoff = (backoff * 2).min(MAX_BACKOFF);
+                        }
+                    },
+                    State::Connected(receiver, interface) => {
                         use iced_futures::futures::StreamExt;
 
-                        let input = receiver.select_next_some().await;
+                        // the stream only ends once every sender is gone, so start over
+                        let Some((id, input)) = receiver.next().await else {
+                            let _ = output.send(Event::Disconnected).await;
+                            state = State::Starting;
+                            continue;
+                        };
 
                         let response = interface.receive(input);
 
                         match response {
                             Ok(vault_output) => {
-                                let event = vault_output.into();
-                                let _ = output.send(event).await;
-                            }
-                            // TODO: actually pass along errors so they can be reacted to and
-                            // reported
-                            Err(e) => {
-                                let _ = output.send(Event::ReceiveError(e.to_string())).await;
+                                let _ = output.send(Event::ReceiveOutput(id, vault_output)).await;
                             }
+                            // passed on as an answer so the gui doesn't have to read the message
+                            Err(e) => match e.downcast_ref::<ManagerError>() {
+                                Some(ManagerError::LockedOut(seconds)) => {
+                                    let _ = output
+                                        .send(Event::ReceiveOutput(id, Output::LockedOut(*seconds)))
+                                        .await;
+                                }
+                                _ => {
+                                    let _ =
+                                        output.send(Event::ReceiveError(id, e.to_string())).await;
+                                }
+                            },
                         }
                     }
                 }
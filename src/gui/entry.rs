@@ -1,35 +1,157 @@
+use std::collections::BTreeSet;
+
 use iced::{
     theme,
-    widget::{button, container, row, text},
+    widget::{button, checkbox, column, container, row, text, tooltip},
     Element, Length,
 };
 
+use crate::schema::StoreKind;
+
 #[derive(Debug, Clone)]
 pub struct Entry {
     pub key: String,
+    pub kind: StoreKind,
+    pub favorite: bool,
+    // timestamps in the sortable format from `utils::format_date`
+    pub used: Option<String>,
+    pub modified: Option<String>,
+    // not modified within the rotation period
+    pub stale: bool,
+    // position when using the custom sort order, new entries go at the end
+    pub order: Option<usize>,
+    pub tags: BTreeSet<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum EntryMessage {
     Delete,
     View,
+    Rename,
+    Duplicate,
+    ToggleFavorite,
+    Rotate,
+    Select(bool),
+    MoveUp,
+    MoveDown,
 }
 
 impl Entry {
-    pub fn new(key: String, _style: String) -> Self {
-        Entry { key }
+    pub fn new(key: String, kind: StoreKind) -> Self {
+        Entry {
+            key,
+            kind,
+            favorite: false,
+            used: None,
+            modified: None,
+            stale: false,
+            order: None,
+            tags: BTreeSet::new(),
+        }
+    }
+
+    pub fn favorite(mut self, value: bool) -> Self {
+        self.favorite = value;
+        self
     }
 
-    pub fn view(&self) -> Element<EntryMessage> {
-        let value = text(self.key.clone()).width(Length::Fill);
+    // while selecting there is a checkbox in front with whether the entry is selected, the entry
+    // can only be moved when the order is custom, narrow windows get the buttons on a second line
+    pub fn view(
+        &self,
+        selected: Option<bool>,
+        movable: bool,
+        narrow: bool,
+    ) -> Element<EntryMessage> {
+        let mut value = row![text(self.key.clone())].spacing(5);
+        for tag in &self.tags {
+            value = value.push(
+                container(text(tag).size(12))
+                    .padding([1, 6])
+                    .style(theme::Container::Box),
+            );
+        }
+        let value = value.width(Length::Fill);
+        let icon = tooltip(
+            text(icon(self.kind)),
+            self.kind.to_string(),
+            tooltip::Position::Bottom,
+        );
         let view_button = button("View").on_press(EntryMessage::View);
+        let (star, tip) = if self.favorite {
+            ("★", "Unfavorite")
+        } else {
+            ("☆", "Favorite")
+        };
+        let favorite_button = tooltip(
+            button(star)
+                .on_press(EntryMessage::ToggleFavorite)
+                .style(theme::Button::Text),
+            tip,
+            tooltip::Position::Bottom,
+        );
+        let rename_button = button("Rename").on_press(EntryMessage::Rename);
+        let duplicate_button = button("Duplicate").on_press(EntryMessage::Duplicate);
         let delete_button = button("Delete")
             .on_press(EntryMessage::Delete)
             .style(theme::Button::Destructive);
-        let content = row![view_button, value, delete_button];
+        let mut content = row![];
+        if let Some(selected) = selected {
+            content = content.push(checkbox("", selected).on_toggle(EntryMessage::Select));
+        }
+        let mut content = content
+            .push(favorite_button)
+            .push(view_button)
+            .push(icon)
+            .push(value);
+        if self.stale {
+            content = content.push(tooltip(
+                button("⚠")
+                    .on_press(EntryMessage::Rotate)
+                    .style(theme::Button::Text),
+                "Not changed in a while, click to rotate",
+                tooltip::Position::Bottom,
+            ));
+        }
+        let mut actions = row![];
+        if movable {
+            actions = actions
+                .push(tooltip(
+                    button("↑").on_press(EntryMessage::MoveUp),
+                    "Move up",
+                    tooltip::Position::Bottom,
+                ))
+                .push(tooltip(
+                    button("↓").on_press(EntryMessage::MoveDown),
+                    "Move down",
+                    tooltip::Position::Bottom,
+                ));
+        }
+        let actions = actions
+            .push(duplicate_button)
+            .push(rename_button)
+            .push(delete_button);
+        let content: Element<EntryMessage> = if narrow {
+            column![content, actions].spacing(2).into()
+        } else {
+            content.push(actions).into()
+        };
         container(content)
             .width(Length::Fill)
             .height(Length::Shrink)
             .into()
     }
 }
+
+// shown in front of the key so the type of each entry is seen at a glance
+fn icon(kind: StoreKind) -> &'static str {
+    match kind {
+        StoreKind::Password => "\u{1F511}",
+        StoreKind::UsernamePassword => "\u{1F464}",
+        StoreKind::Login => "\u{1F310}",
+        StoreKind::Totp => "\u{23F1}",
+        StoreKind::Note => "\u{1F4DD}",
+        StoreKind::Card => "\u{1F4B3}",
+        StoreKind::Unknown => "?",
+    }
+}
@@ -1,7 +1,16 @@
-use iced::Theme;
+use std::path::PathBuf;
+
+use iced::widget::text_editor;
 use secrecy::Secret;
 
-use crate::{store::StoreChoice, Password};
+use crate::{
+    archive::Collision,
+    config::client_config::SortOrder,
+    generate::classes::ClassSpec,
+    import::bitwarden::BitwardenImport,
+    store::{Store, StoreChoice},
+    Password,
+};
 
 use super::{connection, vault::VaultMessage};
 
@@ -14,20 +23,102 @@ pub enum GUIMessage {
     ShowPassword,
     HidePassword,
     CopyPassword,
+    CopyTotp,
+    // the whole setup, for moving it to another authenticator
+    CopyTotpUri,
+    CopyField(String),
+    CopyEntry,
+    ShowQr,
+    ShowAbout,
+    // the menu bar as a list that can be gone through with the arrow keys
+    ShowMenu,
+    // up when true
+    MenuMove(bool),
+    MenuActivate(usize),
+    // copies the about panel's text for a bug report
+    CopyAbout,
+    // closes the code once its time is up, unless a newer one is showing
+    DismissQr,
+    CheckBreach,
+    OpenUrl(String),
+    // whether to use the custom generator and its settings
+    SetGenerator(bool, ClassSpec),
+    SetPronounceable(bool),
+    SelectPreset(String),
+    PresetNameChanged(String),
+    // saves the current custom settings under the typed name
+    SavePreset,
+    RemovePreset(String),
+    ChangeSortOrder(SortOrder),
+    // sets whether every vault is expanded
+    ExpandAll(bool),
+    ShowAuditLog,
+    AlwaysOnTop(bool),
+    BreachChecked(String, String, Result<u64, String>),
     PromptChanged(String),
     PasswordChanged(Password),
     PasswordConfirmChanged(Password),
+    OldPasswordChanged(Password),
+    ChangePassword(String),
+    RecoverPassword(String),
+    Reharden(String),
+    CheckReuse(String),
+    CheckStrength(String),
+    SetRecovery(bool),
+    Export(String),
+    Exported(Result<Option<String>, String>),
+    Import(String),
+    ImportLoaded(String, Result<Option<Vec<(String, Store)>>, String>),
+    ImportBitwarden(Option<String>),
+    BitwardenLoaded(Result<Option<BitwardenImport>, String>),
+    RestoreArchive(Collision),
+    ArchiveChosen(Collision, Result<Option<PathBuf>, String>),
     ChangeName(String),
+    SearchChanged(String),
+    // only entries with the tag are listed, picking it again shows everything
+    FilterTag(String),
+    TagsChanged(String),
     SelectStyle(StoreChoice),
     UpdateField(String, Secret<String>),
+    AddCustomField,
+    UpdateCustomField(usize, String, Secret<String>),
+    RemoveCustomField(usize),
+    EditNote(text_editor::Action),
     GeneratePassword,
-    CopyClipboard(Option<Password>),
-    ClearClipboard,
-    ChangeTheme(Theme),
+    // fills the new entry's password from the clipboard once it's read
+    PastePassword,
+    PastedPassword(Option<Password>),
+    // one of the other generated passwords, by position
+    PickCandidate(usize),
+    CopyCandidate(usize),
+    // fills the new totp entry from an `otpauth://` uri
+    TotpUriChanged(String),
+    // what was on the clipboard before and what is being copied
+    CopyClipboard(Option<Password>, Password),
+    ClearClipboard(u64),
+    ChangeTheme(String),
+    CheckSystemTheme,
+    EditAccent,
+    WindowResized(f32, f32),
+    WindowMoved(i32, i32),
+    // saves the window geometry if nothing changed it since
+    SaveWindow(u64),
+    // the global hotkey was pressed
+    RaiseWindow,
+    // the window was asked to close, only hidden when there is a tray icon to bring it back
+    CloseRequested,
+    // the id of the tray menu item that was picked
+    Tray(String),
     Event(connection::Event),
     ClosePopup,
+    DismissToast,
+    DismissError,
     TabPressed(bool),
+    Tick,
     Close,
+    Lock,
+    // removes the passwords saved in the keychain and locks everything
+    ForgetPasswords,
     Nothing,
     // Send(Message),
 }
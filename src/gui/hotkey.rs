@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
+use iced::{
+    futures::SinkExt,
+    subscription::{self, Subscription},
+};
+
+// how often the hotkey events are looked for, they don't come through iced's own events
+const POLL_TIME: Duration = Duration::from_millis(100);
+
+// the chord stays registered for as long as this is kept around
+pub struct Hotkey {
+    _manager: GlobalHotKeyManager,
+}
+
+// chords look like `ctrl+shift+KeyP`, registering fails when another program already has it
+pub fn register(chord: &str) -> anyhow::Result<Hotkey> {
+    let hotkey: HotKey = chord
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid global hotkey {}: {}", chord, e))?;
+    let manager = GlobalHotKeyManager::new()?;
+    manager
+        .register(hotkey)
+        .map_err(|e| anyhow::anyhow!("Couldn't register global hotkey {}: {}", chord, e))?;
+    Ok(Hotkey { _manager: manager })
+}
+
+// only the one chord is ever registered so any press is it
+pub fn listen() -> Subscription<()> {
+    struct Listen;
+    subscription::channel(
+        std::any::TypeId::of::<Listen>(),
+        10,
+        |mut output| async move {
+            let receiver = GlobalHotKeyEvent::receiver();
+            loop {
+                while let Ok(event) = receiver.try_recv() {
+                    if event.state == HotKeyState::Pressed {
+                        let _ = output.send(()).await;
+                    }
+                }
+                async_std::task::sleep(POLL_TIME).await;
+            }
+        },
+    )
+}
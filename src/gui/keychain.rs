@@ -0,0 +1,28 @@
+use keyring::Entry;
+use secrecy::ExposeSecret;
+
+use crate::Password;
+
+// everything is kept under the one service with an entry per vault
+const SERVICE: &str = "pants";
+
+pub fn store(vault: &str, password: &Password) -> anyhow::Result<()> {
+    Entry::new(SERVICE, vault)?.set_password(password.expose_secret())?;
+    Ok(())
+}
+
+// anything going wrong is the same as nothing being saved, the password just gets asked for
+pub fn fetch(vault: &str) -> Option<Password> {
+    Entry::new(SERVICE, vault)
+        .and_then(|entry| entry.get_password())
+        .ok()
+        .map(Password::new)
+}
+
+// nothing being saved is fine
+pub fn forget(vault: &str) -> anyhow::Result<()> {
+    match Entry::new(SERVICE, vault)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
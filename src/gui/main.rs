@@ -1,9 +1,14 @@
 use iced::{Application, Font, Settings};
-use pants_store::gui::state::manager::ManagerState;
+use pants_store::{
+    config::{client_config::ClientConfig, internal_config::BaseConfig},
+    gui::{state::manager::ManagerState, window_settings},
+};
 
 fn main() -> iced::Result {
+    let config = <ClientConfig as BaseConfig>::load_err();
     ManagerState::run(Settings {
         default_font: Font::MONOSPACE,
+        window: window_settings(&config),
         ..Default::default()
     })
 }
@@ -1,17 +1,30 @@
 use std::collections::{BTreeMap, HashMap};
 
+use display_info::DisplayInfo;
 use gui_message::GUIMessage;
-use iced::{keyboard, widget::text_input, Padding, Rectangle, Theme};
+use iced::{
+    keyboard,
+    widget::{container, text_input},
+    window, Padding, Point, Rectangle, Size, Theme,
+};
 use once_cell::sync::Lazy;
 use shortcut::Shortcut;
 
+use crate::config::client_config::ClientConfig;
+
 pub mod connection;
 pub mod entry;
 pub mod gui_message;
+pub mod hotkey;
+pub mod keychain;
+pub mod notify;
+pub mod qr;
 pub mod shortcut;
 pub mod state;
+pub mod strength;
 pub mod style;
 pub mod temp_message;
+pub mod tray;
 pub mod vault;
 pub mod widget;
 
@@ -24,6 +37,54 @@ pub fn with_padding(rect: Rectangle, padding: Padding) -> Rectangle {
     }
 }
 
+// smallest the window is restored to so it can't come back unusable
+const MIN_WINDOW_SIZE: Size = Size::new(300.0, 200.0);
+// narrower than this the menu collapses and entries put their buttons on a line of their own
+pub const NARROW_WIDTH: f32 = 600.0;
+
+pub fn window_level(always_on_top: bool) -> window::Level {
+    if always_on_top {
+        window::Level::AlwaysOnTop
+    } else {
+        window::Level::Normal
+    }
+}
+
+// the last size and position of the window, kept on a display that is still there
+pub fn window_settings(config: &ClientConfig) -> window::Settings {
+    let mut settings = window::Settings::default();
+    let displays = DisplayInfo::all().unwrap_or_default();
+    let display = config
+        .window_position
+        .and_then(|(x, y)| DisplayInfo::from_point(x, y).ok())
+        .or_else(|| displays.into_iter().find(|display| display.is_primary));
+    if let Some((width, height)) = config.window_size {
+        let mut size = Size::new(width, height).max(MIN_WINDOW_SIZE);
+        if let Some(display) = &display {
+            size = size.min(Size::new(display.width as f32, display.height as f32));
+        }
+        settings.size = size;
+    }
+    if let (Some((x, y)), Some(display)) = (config.window_position, &display) {
+        let max_x = display.x + display.width as i32 - settings.size.width as i32;
+        let max_y = display.y + display.height as i32 - settings.size.height as i32;
+        settings.position = window::Position::Specific(Point::new(
+            x.clamp(display.x, max_x.max(display.x)) as f32,
+            y.clamp(display.y, max_y.max(display.y)) as f32,
+        ));
+    }
+    // closing goes through the app so a copied secret can be cleared first, and only hides the
+    // window while it can be brought back from the tray
+    settings.exit_on_close_request = false;
+    settings.level = window_level(config.always_on_top);
+    settings
+}
+
+// follows the system's light or dark preference instead of a fixed theme
+pub const AUTO_THEME: &str = "Auto";
+// the light or dark system theme with the configured accent color
+pub const CUSTOM_THEME: &str = "Custom";
+
 pub static THEMES: Lazy<BTreeMap<String, Theme>> = Lazy::new(|| {
     Theme::ALL
         .iter()
@@ -49,6 +110,64 @@ pub static SHORTCUTS: Lazy<HashMap<String, Shortcut>> = Lazy::new(|| {
                 GUIMessage::Close,
             ),
         ),
+        (
+            "Lock".to_string(),
+            Shortcut::new(
+                keyboard::Key::Character("l".into()),
+                Some(keyboard::Modifiers::COMMAND),
+                GUIMessage::Lock,
+            ),
+        ),
+        (
+            "Generate Password".to_string(),
+            Shortcut::new(
+                keyboard::Key::Character("g".into()),
+                Some(keyboard::Modifiers::COMMAND),
+                GUIMessage::GeneratePassword,
+            ),
+        ),
+        // focused text inputs capture these themselves, so they only fire otherwise
+        (
+            "Submit".to_string(),
+            Shortcut::new(
+                keyboard::Key::Named(keyboard::key::Named::Enter),
+                None,
+                GUIMessage::Submit,
+            ),
+        ),
+        (
+            "Exit".to_string(),
+            Shortcut::new(
+                keyboard::Key::Named(keyboard::key::Named::Escape),
+                None,
+                GUIMessage::Exit,
+            ),
+        ),
+        (
+            "Menu".to_string(),
+            Shortcut::new(
+                keyboard::Key::Named(keyboard::key::Named::F10),
+                None,
+                GUIMessage::ShowMenu,
+            ),
+        ),
+        // these only do something while the keyboard menu is open
+        (
+            "Up".to_string(),
+            Shortcut::new(
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp),
+                None,
+                GUIMessage::MenuMove(true),
+            ),
+        ),
+        (
+            "Down".to_string(),
+            Shortcut::new(
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown),
+                None,
+                GUIMessage::MenuMove(false),
+            ),
+        ),
         (
             "Tab forward".to_string(),
             Shortcut::new(
@@ -69,3 +188,7 @@ pub static SHORTCUTS: Lazy<HashMap<String, Shortcut>> = Lazy::new(|| {
 });
 
 pub static INPUT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+// focus goes back here once the last modal is closed
+pub static SEARCH_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+// wraps whatever modal is open so tabbing stays inside it
+pub static MODAL_ID: Lazy<container::Id> = Lazy::new(container::Id::unique);
@@ -0,0 +1,10 @@
+use notify_rust::Notification;
+
+// only ever says what happened, never anything about what was copied
+pub fn clipboard_cleared() -> anyhow::Result<()> {
+    Notification::new()
+        .appname("pants")
+        .summary("Pants cleared the clipboard")
+        .show()?;
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use iced::widget::image;
+use qrcode::{Color, QrCode};
+
+// pixels per module and the blank modules around the code that scanners need
+const SCALE: usize = 6;
+const QUIET_ZONE: usize = 4;
+
+// drawn straight into pixels so nothing of the secret is written anywhere
+pub fn render(data: &str) -> anyhow::Result<image::Handle> {
+    let code = QrCode::new(data.as_bytes())?;
+    let modules = code.width();
+    let colors = code.to_colors();
+    let size = (modules + 2 * QUIET_ZONE) * SCALE;
+    let mut pixels = vec![255; size * size * 4];
+    for (i, color) in colors.iter().enumerate() {
+        if *color != Color::Dark {
+            continue;
+        }
+        let (x, y) = (i % modules + QUIET_ZONE, i / modules + QUIET_ZONE);
+        for row in y * SCALE..(y + 1) * SCALE {
+            for column in x * SCALE..(x + 1) * SCALE {
+                let offset = (row * size + column) * 4;
+                pixels[offset..offset + 3].fill(0);
+            }
+        }
+    }
+    Ok(image::Handle::from_pixels(size as u32, size as u32, pixels))
+}
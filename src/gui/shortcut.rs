@@ -50,6 +50,10 @@ impl Shortcut {
         }
     }
 
+    pub fn modifier(&self) -> Option<keyboard::Modifiers> {
+        self.modifier
+    }
+
     pub fn message(&self) -> &GUIMessage {
         &self.message
     }
@@ -68,6 +72,11 @@ fn modifier_display(modifier: &keyboard::Modifiers) -> &str {
 fn named_display(key: &keyboard::key::Named) -> &str {
     match *key {
         keyboard::key::Named::Tab => "Tab",
+        keyboard::key::Named::Enter => "Enter",
+        keyboard::key::Named::Escape => "Esc",
+        keyboard::key::Named::F10 => "F10",
+        keyboard::key::Named::ArrowUp => "Up",
+        keyboard::key::Named::ArrowDown => "Down",
         _ => unimplemented!(),
     }
 }
@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use iced::{
+    widget::{button, column, container, row, text},
+    Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card};
+
+// enough to tell which client was talking to which manager when reporting a bug
+#[derive(Debug, Clone)]
+pub struct AboutState {
+    pub connection: String,
+    pub data_dir: PathBuf,
+    // not known until the manager answers, which it can't while disconnected
+    pub manager_version: Option<String>,
+}
+
+impl AboutState {
+    pub fn new(connection: String, data_dir: PathBuf) -> Self {
+        Self {
+            connection,
+            data_dir,
+            manager_version: None,
+        }
+    }
+
+    // what's shown is also what gets copied
+    pub fn report(&self) -> String {
+        format!(
+            "pants-gui {}\nmanager {}\nconnection: {}\ndata directory: {}",
+            env!("CARGO_PKG_VERSION"),
+            self.manager_version.as_deref().unwrap_or("unknown"),
+            self.connection,
+            self.data_dir.display()
+        )
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text("About");
+        let report = text(self.report());
+        let copy_button = button("Copy").on_press(GUIMessage::CopyAbout);
+        let done_button = button("Done").on_press(GUIMessage::Submit);
+        Card::new(
+            header,
+            container(column![report, row![copy_button, done_button].spacing(10)].spacing(10)),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
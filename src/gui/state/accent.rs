@@ -0,0 +1,57 @@
+use iced::{
+    theme,
+    widget::{button, column, container, row, text, text_input},
+    Background, Element, Length,
+};
+
+use crate::{
+    gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID},
+    utils,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct AccentState {
+    pub color: String,
+}
+
+impl AccentState {
+    pub fn new(color: Option<String>) -> Self {
+        Self {
+            color: color.unwrap_or_default(),
+        }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text("Accent color");
+        let color_input = text_input("#3366ff", &self.color)
+            .id(INPUT_ID.clone())
+            .on_input(GUIMessage::PromptChanged)
+            .on_submit(GUIMessage::Submit);
+        // shows what the color looks like, or why it won't be used
+        let preview: Element<GUIMessage> = match utils::hex_color(&self.color) {
+            Some(color) => container(text(""))
+                .width(Length::Fixed(30.0))
+                .height(Length::Fixed(30.0))
+                .style(move |_theme: &iced::Theme| container::Appearance {
+                    background: Some(Background::Color(color)),
+                    ..Default::default()
+                })
+                .into(),
+            None => text("Not a hex color like #3366ff")
+                .style(theme::Text::Color(iced::Color::from_rgb(0.8, 0.2, 0.2)))
+                .into(),
+        };
+
+        let save_button = button("Save").on_press(GUIMessage::Submit);
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![
+                row![color_input, preview].spacing(10),
+                row![save_button, cancel_button]
+            ]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
@@ -0,0 +1,47 @@
+use iced::{
+    theme,
+    widget::{button, column, container, scrollable, text},
+    Color, Element, Length,
+};
+
+use crate::{
+    audit::AuditEvent,
+    gui::{gui_message::GUIMessage, widget::card::Card},
+};
+
+// read only, the newest events are shown first
+#[derive(Debug, Clone)]
+pub struct AuditState {
+    pub events: Vec<AuditEvent>,
+}
+
+impl AuditState {
+    pub fn new(events: Vec<AuditEvent>) -> Self {
+        Self { events }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text("Audit Log");
+        let mut rows = column![].spacing(5);
+        if self.events.is_empty() {
+            rows = rows.push(text("Nothing has been logged yet"));
+        }
+        for event in self.events.iter().rev() {
+            let mut line = text(event.to_string());
+            if !event.success() {
+                line = line.style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2)));
+            }
+            rows = rows.push(line);
+        }
+        let done_button = button("Done").on_press(GUIMessage::Submit);
+        Card::new(
+            header,
+            container(column![
+                scrollable(rows).height(Length::Fixed(300.0)),
+                done_button
+            ]),
+        )
+        .max_width(600.0)
+        .into()
+    }
+}
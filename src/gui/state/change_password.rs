@@ -0,0 +1,88 @@
+use iced::{
+    widget::{button, column, container, row, text, text_input},
+    Element, Length,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID},
+    Password,
+};
+
+#[derive(Debug, Clone)]
+pub struct ChangePasswordState {
+    pub vault: String,
+    pub old: Password,
+    pub new: Password,
+    pub confirm: Password,
+    // the old password is a recovery key instead
+    pub recovery: bool,
+}
+
+impl ChangePasswordState {
+    pub fn new(vault: String) -> Self {
+        Self {
+            vault,
+            old: String::new().into(),
+            new: String::new().into(),
+            confirm: String::new().into(),
+            recovery: false,
+        }
+    }
+
+    pub fn recovery(vault: String) -> Self {
+        Self {
+            recovery: true,
+            ..Self::new(vault)
+        }
+    }
+
+    pub fn valid(&self) -> bool {
+        !self.new.expose_secret().is_empty()
+            && self.new.expose_secret() == self.confirm.expose_secret()
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let (header, old_placeholder) = if self.recovery {
+            (
+                format!("Recover password for {}", self.vault),
+                "recovery key",
+            )
+        } else {
+            (
+                format!("Change password for {}", self.vault),
+                "current password",
+            )
+        };
+        let header = text(header);
+        let old_input = text_input(old_placeholder, self.old.expose_secret())
+            .id(INPUT_ID.clone())
+            .on_input(|p| GUIMessage::OldPasswordChanged(p.into()))
+            .on_submit(GUIMessage::Submit)
+            .width(Length::Fill)
+            .secure(true);
+        let new_input = text_input("new password", self.new.expose_secret())
+            .on_input(|p| GUIMessage::PasswordChanged(p.into()))
+            .on_submit(GUIMessage::Submit)
+            .width(Length::Fill)
+            .secure(true);
+        let confirm_input = text_input("confirm new password", self.confirm.expose_secret())
+            .on_input(|p| GUIMessage::PasswordConfirmChanged(p.into()))
+            .on_submit(GUIMessage::Submit)
+            .width(Length::Fill)
+            .secure(true);
+        let change_button = button("Change").on_press(GUIMessage::Submit);
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![
+                old_input,
+                new_input,
+                confirm_input,
+                row![change_button, cancel_button]
+            ]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
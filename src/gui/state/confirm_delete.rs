@@ -0,0 +1,90 @@
+use iced::{
+    theme,
+    widget::{button, column, container, row, scrollable, text, text_input},
+    Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID};
+
+// deleting a vault loses all of its entries, so the name has to be typed out first
+#[derive(Debug, Clone)]
+pub struct ConfirmDeleteState {
+    pub vault: String,
+    pub typed: String,
+}
+
+impl ConfirmDeleteState {
+    pub fn new(vault: String) -> Self {
+        Self {
+            vault,
+            typed: String::new(),
+        }
+    }
+
+    pub fn confirmed(&self) -> bool {
+        self.typed == self.vault
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!("Delete vault {}", self.vault));
+        let warning = text(format!(
+            "This deletes every entry in {}. Type the vault's name to confirm.",
+            self.vault
+        ));
+        let name_input = text_input(&self.vault, &self.typed)
+            .id(INPUT_ID.clone())
+            .on_input(GUIMessage::PromptChanged)
+            .on_submit(GUIMessage::Submit);
+
+        let delete_button = button("Delete")
+            .style(theme::Button::Destructive)
+            .on_press_maybe(self.confirmed().then_some(GUIMessage::Submit));
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![
+                warning,
+                name_input,
+                row![delete_button, cancel_button]
+            ]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
+
+// deleting several entries at once is easy to do by accident, so they get listed first
+#[derive(Debug, Clone)]
+pub struct ConfirmDeleteEntriesState {
+    pub vault: String,
+    pub keys: Vec<String>,
+}
+
+impl ConfirmDeleteEntriesState {
+    pub fn new(vault: String, keys: Vec<String>) -> Self {
+        Self { vault, keys }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!(
+            "Delete {} entries from {}",
+            self.keys.len(),
+            self.vault
+        ));
+        let keys = column(self.keys.iter().map(|key| text(key).into()));
+
+        let delete_button = button("Delete")
+            .style(theme::Button::Destructive)
+            .on_press(GUIMessage::Submit);
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![
+                scrollable(keys).height(200.0),
+                row![delete_button, cancel_button]
+            ]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
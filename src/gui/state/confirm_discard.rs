@@ -0,0 +1,40 @@
+use iced::{
+    theme,
+    widget::{button, column, container, row, text},
+    Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card};
+
+// only shown when an entry was typed into, closing it otherwise loses nothing
+#[derive(Debug, Clone)]
+pub struct ConfirmDiscardState {
+    // the whole app is closing rather than just the entry
+    pub quit: bool,
+}
+
+impl ConfirmDiscardState {
+    pub fn new(quit: bool) -> Self {
+        Self { quit }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text("Discard changes");
+        let warning = text(if self.quit {
+            "An entry has changes that weren't saved, quitting loses them."
+        } else {
+            "The entry has changes that weren't saved, closing it loses them."
+        });
+
+        let discard_button = button(if self.quit { "Quit" } else { "Discard" })
+            .style(theme::Button::Destructive)
+            .on_press(GUIMessage::Submit);
+        let cancel_button = button("Keep editing").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![warning, row![discard_button, cancel_button]]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
@@ -0,0 +1,48 @@
+use iced::{
+    theme,
+    widget::{button, column, container, row, scrollable, text},
+    Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card};
+
+// the old passwords are gone once they're replaced, so the entries get listed first
+#[derive(Debug, Clone)]
+pub struct ConfirmRegenerateState {
+    pub vault: String,
+    pub keys: Vec<String>,
+}
+
+impl ConfirmRegenerateState {
+    pub fn new(vault: String, keys: Vec<String>) -> Self {
+        Self { vault, keys }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!(
+            "Regenerate {} passwords in {}",
+            self.keys.len(),
+            self.vault
+        ));
+        let warning = text(
+            "Each entry gets a new password from the vault's spec, the current ones can't be \
+             recovered afterwards.",
+        );
+        let keys = column(self.keys.iter().map(|key| text(key).into()));
+
+        let regenerate_button = button("Regenerate")
+            .style(theme::Button::Destructive)
+            .on_press(GUIMessage::Submit);
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![
+                warning,
+                scrollable(keys).height(200.0),
+                row![regenerate_button, cancel_button]
+            ]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
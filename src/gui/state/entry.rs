@@ -1,103 +1,225 @@
 use iced::{
-    widget::{button, column, container, row, text, text_input},
-    Element, Length,
+    theme,
+    widget::{button, column, container, mouse_area, row, text, text_editor, text_input},
+    Alignment, Color, Element, Length,
 };
 use secrecy::ExposeSecret;
 
 use crate::{
-    gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID},
-    store::{Store, StoreChoice, StoreHash},
-    Password,
+    gui::{
+        gui_message::GUIMessage,
+        state::new_entry::{custom_fields_view, join_tags, tags_view},
+        widget::card::Card,
+        INPUT_ID,
+    },
+    schema::StoreKind,
+    store::{CustomFields, FieldKind, Store, StoreChoice, StoreHash},
+    totp::Totp,
+    utils, Password,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct EntryState {
     pub vault: String,
     pub key: String,
     pub choice: StoreChoice,
     pub value: StoreHash,
     pub hidden: bool,
+    pub note: text_editor::Content,
+    // result of the last breach check, not checked yet when missing
+    pub breaches: Option<Result<u64, String>>,
+    // when the entry was last changed, entries from before this was tracked don't have it
+    pub updated: Option<String>,
+    // secrets are only shown while the reveal button is held down
+    pub hold_to_reveal: bool,
+    pub custom: CustomFields,
+    // why the values were rejected on the last save
+    pub error: Option<String>,
+    // the read that fills in the entry, answers to anything else are not for this entry
+    pub request: Option<u64>,
+    // changed since it was read, closing asks first
+    pub edited: bool,
+    pub tags: String,
 }
 
 impl EntryState {
     pub fn view(&self) -> Element<GUIMessage> {
         let header = text(format!("{} in {}", self.key.clone(), self.vault));
-        let data_input = match &self.choice {
-            StoreChoice::Password => {
-                let prefix = text("Password:");
-                let password_input = text_input(
-                    "Password",
-                    self.value.get("password").unwrap().expose_secret(),
-                )
-                .id(INPUT_ID.clone())
-                .width(Length::Fill)
-                .on_input(|v| GUIMessage::UpdateField("password".to_string(), v.into()))
-                .secure(self.hidden);
-                let show_button = if self.hidden {
-                    button("Show").on_press(GUIMessage::ShowPassword)
-                } else {
-                    button("Hide").on_press(GUIMessage::HidePassword)
-                };
-                let copy_button = button("Copy").on_press(GUIMessage::CopyPassword);
-                let password_generate = button("Generate").on_press(GUIMessage::GeneratePassword);
-                container(row![
-                    prefix,
-                    password_input,
-                    password_generate,
-                    copy_button,
-                    show_button
-                ])
+        let mut rows = self
+            .choice
+            .fields()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (field, kind))| self.field_view(field, kind, i == 0))
+            .collect::<Vec<_>>();
+        if let Some(updated) = self
+            .updated
+            .as_ref()
+            .and_then(|date| utils::read_date(date).ok())
+        {
+            rows.insert(
+                0,
+                text(format!("updated {}", utils::time_since(updated)))
+                    .size(14)
+                    .into(),
+            );
+        }
+        if self.choice == StoreChoice::Totp {
+            let code = match self.get_totp() {
+                Some(totp) => text(format!(
+                    "Code: {} (next in {}s)",
+                    totp.code(),
+                    totp.remaining()
+                )),
+                None => text("Code: invalid secret"),
+            };
+            let copy_button = button("Copy").on_press(GUIMessage::CopyTotp);
+            let copy_uri_button = button("Copy URI").on_press(GUIMessage::CopyTotpUri);
+            rows.push(row![code, copy_button, copy_uri_button].into());
+        }
+        if self.choice.field_kind("password").is_some() {
+            rows.push(self.breach_view());
+        }
+        rows.push(tags_view(&self.tags));
+        rows.push(custom_fields_view(&self.custom, self.hidden));
+        if let Some(error) = &self.error {
+            rows.push(
+                text(error)
+                    .style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2)))
+                    .into(),
+            );
+        }
+
+        let copy_all_button = button("Copy all").on_press(GUIMessage::CopyEntry);
+        let qr_button = button("Show QR").on_press(GUIMessage::ShowQr);
+        let save_button = button("Save").on_press(GUIMessage::Submit);
+        let done_button = button("Done").on_press(GUIMessage::Exit);
+        rows.push(row![save_button, copy_all_button, qr_button, done_button].into());
+        Card::new(header, container(column(rows)))
+            .max_width(500.0)
+            .into()
+    }
+
+    // secrets are masked with the show/hide toggle, urls can be opened, and everything else is
+    // plain text
+    fn field_view(&self, field: &'static str, kind: FieldKind, first: bool) -> Element<GUIMessage> {
+        let label = text(format!("{}:", field_label(field)));
+        let value = self
+            .value
+            .get(field)
+            .map(|v| v.expose_secret().as_str())
+            .unwrap_or_default();
+        if kind == FieldKind::Multiline {
+            let note_input = text_editor(&self.note)
+                .on_action(GUIMessage::EditNote)
+                .height(200.0);
+            let copy_button = button("Copy").on_press(GUIMessage::CopyField(field.to_string()));
+            return column![row![label, copy_button], note_input].into();
+        }
+        // only the last digits of a card number are shown until it's revealed, which also
+        // means it can't be edited while hidden
+        if field == "number" && self.hidden {
+            let masked = text_input(&field_label(field), &masked_number(value)).width(Length::Fill);
+            let show_button = self.show_button();
+            let copy_button = button("Copy").on_press(GUIMessage::CopyField(field.to_string()));
+            return row![label, masked, copy_button, show_button].into();
+        }
+        let mut input = text_input(&field_label(field), value)
+            .width(Length::Fill)
+            .on_input(move |v| GUIMessage::UpdateField(field.to_string(), v.into()));
+        if first {
+            input = input.id(INPUT_ID.clone());
+        }
+        let copy_button = if field == "password" {
+            button("Copy").on_press(GUIMessage::CopyPassword)
+        } else {
+            button("Copy").on_press(GUIMessage::CopyField(field.to_string()))
+        };
+        match kind {
+            FieldKind::Secret => {
+                let show_button = self.show_button();
+                let mut row = row![label, input.secure(self.hidden)];
+                if field == "password" {
+                    row = row.push(button("Generate").on_press(GUIMessage::GeneratePassword));
+                }
+                row.push(copy_button).push(show_button).into()
             }
-            StoreChoice::UsernamePassword => {
-                let username_prefix = text("Username:");
-                let password_prefix = text("Password:");
-                let username_input = text_input(
-                    "Username",
-                    self.value.get("username").unwrap().expose_secret(),
-                )
-                .width(Length::Fill)
-                .on_input(|v| GUIMessage::UpdateField("username".to_string(), v.into()));
-                let password_input = text_input(
-                    "Password",
-                    self.value.get("password").unwrap().expose_secret(),
-                )
-                .width(Length::Fill)
-                .on_input(|v| GUIMessage::UpdateField("password".to_string(), v.into()))
-                .secure(self.hidden);
-                let show_button = if self.hidden {
-                    button("Show").on_press(GUIMessage::ShowPassword)
-                } else {
-                    button("Hide").on_press(GUIMessage::HidePassword)
-                };
-                let copy_button = button("Copy").on_press(GUIMessage::CopyPassword);
-                let password_generate = button("Generate").on_press(GUIMessage::GeneratePassword);
-                container(column![
-                    row![username_prefix, username_input],
-                    row![
-                        password_prefix,
-                        password_input,
-                        password_generate,
-                        copy_button,
-                        show_button
-                    ]
-                ])
+            FieldKind::Url => {
+                let open_button = button("Open").on_press_maybe(
+                    utils::web_url(value).map(|url| GUIMessage::OpenUrl(url.to_string())),
+                );
+                row![label, input, copy_button, open_button].into()
             }
+            FieldKind::Text | FieldKind::Multiline => row![label, input, copy_button].into(),
+        }
+    }
+
+    fn show_button(&self) -> Element<GUIMessage> {
+        if self.hold_to_reveal {
+            // a plain container since a button would capture the press itself
+            mouse_area(
+                container(text("Hold to show"))
+                    .padding(5)
+                    .style(theme::Container::Box),
+            )
+            .on_press(GUIMessage::ShowPassword)
+            .on_release(GUIMessage::HidePassword)
+            .into()
+        } else if self.hidden {
+            button("Show").on_press(GUIMessage::ShowPassword).into()
+        } else {
+            button("Hide").on_press(GUIMessage::HidePassword).into()
+        }
+    }
+
+    fn breach_view(&self) -> Element<GUIMessage> {
+        let check_button = button("Check breaches").on_press(GUIMessage::CheckBreach);
+        let status = match &self.breaches {
+            None => text(""),
+            Some(Ok(0)) => text("Not found in any known breaches"),
+            Some(Ok(count)) => text(format!("Warning: seen {} times in known breaches", count))
+                .style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2))),
+            Some(Err(e)) => text(e),
         };
+        row![check_button, status]
+            .spacing(10)
+            .align_items(Alignment::Center)
+            .into()
+    }
 
-        let save_button = button("Save").on_press(GUIMessage::Submit);
-        let done_button = button("Done").on_press(GUIMessage::Exit);
-        Card::new(
-            header,
-            container(column![data_input, row![save_button, done_button]]),
-        )
-        .max_width(500.0)
-        .into()
+    pub fn request(mut self, id: u64) -> Self {
+        self.request = Some(id);
+        self
+    }
+
+    pub fn tags(mut self, tags: BTreeSet<String>) -> Self {
+        self.tags = join_tags(&tags);
+        self
+    }
+
+    pub fn hidden(mut self, value: bool) -> Self {
+        self.hidden = value;
+        self
+    }
+
+    pub fn hold_to_reveal(mut self, value: bool) -> Self {
+        self.hold_to_reveal = value;
+        self
     }
 
     pub fn update(&mut self, value: Store) {
+        self.edited = false;
+        self.custom = value.custom_fields();
         let (choice, value) = value.split();
         self.choice = choice;
         self.value = value;
+        if let Some(note) = self
+            .choice
+            .multiline_field()
+            .and_then(|field| self.value.get(field))
+        {
+            self.note = text_editor::Content::with_text(note.expose_secret());
+        }
     }
 
     pub fn get_password(&self) -> Option<Password> {
@@ -109,21 +231,88 @@ impl EntryState {
         None
     }
 
-    pub fn from_entry(vault: String, key: String, style: String) -> Self {
-        let value = match style.as_str() {
-            "password" => Store::Password(String::new().into()),
-            "username-password" => {
-                Store::UsernamePassword(String::new().into(), String::new().into())
+    pub fn get_field(&self, key: &str) -> Option<Password> {
+        self.value.get(key).cloned()
+    }
+
+    // every field labeled on its own line, secrets other than the password are always left out
+    // and totp entries get their current code instead
+    pub fn formatted(&self, include_password: bool) -> Password {
+        let mut lines = vec![format!("{} ({})", self.key, self.vault)];
+        for (field, kind) in self.choice.fields() {
+            let Some(value) = self.value.get(field) else {
+                continue;
+            };
+            let value = value.expose_secret();
+            match kind {
+                FieldKind::Secret if field == "password" && include_password => {}
+                FieldKind::Secret => continue,
+                FieldKind::Multiline => {
+                    lines.push(format!("{}:\n{}", field_label(field), value));
+                    continue;
+                }
+                FieldKind::Url | FieldKind::Text => {}
             }
-            _ => panic!("unrecognized entry value {}", style),
-        };
-        let (choice, value) = value.split();
+            lines.push(format!("{}: {}", field_label(field), value));
+        }
+        if let Some(totp) = self.get_totp() {
+            lines.push(format!("Code: {}", totp.code()));
+        }
+        lines.join("\n").into()
+    }
+
+    pub fn get_totp(&self) -> Option<Totp> {
+        match self.choice.convert(&self.value) {
+            Ok(Store::Totp(totp)) => Some(totp),
+            _ => None,
+        }
+    }
+
+    pub fn from_entry(vault: String, key: String, kind: StoreKind) -> Self {
+        let (choice, value) = StoreChoice::from(kind).convert_default().split();
         EntryState {
             vault,
             key,
             choice,
             value,
             hidden: true,
+            note: text_editor::Content::new(),
+            breaches: None,
+            updated: None,
+            hold_to_reveal: true,
+            custom: vec![],
+            error: None,
+            request: None,
+            edited: false,
+            tags: String::new(),
+        }
+    }
+}
+
+// e.g. `•••• 1234`, short numbers are masked completely
+fn masked_number(number: &str) -> String {
+    let digits = number
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<Vec<_>>();
+    if digits.len() < 8 {
+        return "•".repeat(digits.len());
+    }
+    let last = digits[digits.len() - 4..].iter().collect::<String>();
+    format!("•••• {}", last)
+}
+
+// e.g. `username` as `Username`, with the short ones spelled out
+fn field_label(field: &str) -> String {
+    match field {
+        "url" => "URL".to_string(),
+        "cvv" => "CVV".to_string(),
+        _ => {
+            let mut chars = field.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
         }
     }
 }
@@ -1,29 +1,71 @@
-use std::{collections::BTreeMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use crate::{
+    archive::{self, Collision},
+    breach,
     config::{
-        client_config::ClientConfig,
+        client_config::{ClientConfig, ClipboardTarget, SortOrder},
         internal_config::{BaseConfig, InternalConfig},
     },
+    errors::{DecryptionError, ManagerError},
+    export::ExportFormat,
+    generate::{classes::ClassSpec, GenSpec},
     gui::{
         connection,
         entry::EntryMessage,
         gui_message::GUIMessage,
-        state::{entry::EntryState, new_entry::NewEntryState, password::PasswordState},
+        hotkey, keychain, notify, qr,
+        state::{
+            about::AboutState,
+            accent::AccentState,
+            audit::AuditState,
+            change_password::ChangePasswordState,
+            confirm_delete::{ConfirmDeleteEntriesState, ConfirmDeleteState},
+            confirm_discard::ConfirmDiscardState,
+            confirm_regenerate::ConfirmRegenerateState,
+            entry::EntryState,
+            menu::MenuState,
+            new_entry::NewEntryState,
+            password::PasswordState,
+            qr::QrState,
+            recovery::RecoveryState,
+            rename::{RenameState, RenameVaultState},
+            reuse::ReuseState,
+            weak::WeakState,
+        },
         temp_message::TempMessage,
+        tray::{self, Tray, TrayAction, RECENT_ENTRIES},
         vault::{Vault, VaultMessage},
-        INPUT_ID, SHORTCUTS, THEMES,
+        window_level, AUTO_THEME, CUSTOM_THEME, INPUT_ID, MODAL_ID, NARROW_WIDTH, SEARCH_ID,
+        SHORTCUTS, THEMES,
+    },
+    import::{
+        bitwarden::{self, BitwardenImport},
+        ImportFormat,
     },
     info::Info,
-    manager_message::ManagerMessage,
+    manager_message::{ManagerMessage, PROTOCOL_VERSION},
+    message::Message,
     output::Output,
     reads::Reads,
-    store::{Store, StoreChoice},
-    Password,
+    schema::StoreKind,
+    store::{CustomFields, Store, StoreChoice},
+    totp::Totp,
+    utils, Password,
 };
+use enum_iterator::all;
 use iced::{
+    advanced::widget::operation::{self, focusable},
     alignment, keyboard, theme,
-    widget::{self, button, column, container, row, scrollable, text, text_input},
+    widget::{
+        self, button, column, combo_box, container, row, scrollable, text, text_editor, text_input,
+        tooltip,
+    },
     window, Application, Border, Command, Element, Length, Subscription, Theme,
 };
 use iced_aw::{
@@ -33,11 +75,21 @@ use iced_aw::{
     style::MenuBarStyle,
 };
 use iced_futures::MaybeSend;
-use pants_gen::password::PasswordSpec;
 use secrecy::{ExposeSecret, Secret};
 
 use super::prompt::PromptState;
 
+// wrong passwords in a row before giving up on what was being done
+const MAX_PASSWORD_ATTEMPTS: u32 = 5;
+// how long to wait on the vault manager before giving up on a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// how many of the latest audit log events are shown
+const AUDIT_EVENTS: usize = 200;
+// how long a toast like "Copied!" stays up
+const TOAST_TIME: Duration = Duration::from_millis(1500);
+// how long a QR code of a secret stays up
+const QR_TIME: Duration = Duration::from_secs(30);
+
 pub struct ManagerState {
     config: ClientConfig,
     info: Info,
@@ -45,59 +97,218 @@ pub struct ManagerState {
     internal_state: Vec<InternalState>,
     temp_message: TempMessage,
     stored_clipboard: Option<Password>,
+    // bumped on every copy so only the clear from the latest copy does anything
+    clipboard_generation: u64,
+    // when the copy on the clipboard gets cleared, nothing is pending when missing
+    clipboard_clears_at: Option<Instant>,
     state: ConnectionState,
+    // the manager answered the handshake with another protocol, its answers can't be trusted
+    protocol_mismatch: Option<String>,
     notice: Option<String>,
+    // the id given to the last request
+    next_request: u64,
+    // requests something is waiting on and when to give up on them
+    awaiting: BTreeMap<u64, Instant>,
+    // requests that were given up on, their answers are dropped
+    timed_out: BTreeSet<u64>,
+    // changes made while not connected, sent in order once the connection is back
+    queued: Vec<(u64, ManagerMessage)>,
+    // a short confirmation like for a copy and when it goes away
+    toast: Option<(String, Instant)>,
+    error: Option<String>,
+    search: String,
+    tag_filter: Option<String>,
+    last_interaction: Instant,
+    // generate a new password once the entry being opened comes back
+    pending_rotation: bool,
+    // the last custom generator settings, kept for the session so new entries start with them
+    custom_generator: bool,
+    generator: ClassSpec,
+    pronounceable: bool,
+    // entries picked for deleting or regenerating, a vault is in selection mode while it has a set here
+    selection: BTreeMap<String, BTreeSet<String>>,
+    // what the auto theme resolves to, checked again every so often
+    system_theme: Theme,
+    // bumped on every resize or move so only the last one in a drag gets saved
+    window_generation: u64,
+    // the layout gets more compact below `NARROW_WIDTH`
+    window_width: f32,
+    // the passwords of the vaults unlocked this session
+    passwords: BTreeMap<String, Password>,
+    // a submitted password that counts as unlocking once the vault answers without an error
+    pending_unlock: Option<(u64, String, Password)>,
+    // what the last password was submitted for, so a wrong one can be retried
+    last_attempt: Option<TempMessage>,
+    password_attempts: u32,
+    // kept so the global hotkey stays registered
+    hotkey: Option<hotkey::Hotkey>,
+    tray: Option<Tray>,
+    // the read of an entry whose password is being copied from the tray
+    tray_copy: Option<u64>,
+    // a vault rename that is only applied here once the manager has done it
+    pending_rename: Option<(u64, String, String)>,
+    // the keychain is only looked at once the vaults are first known, later locks stay locked
+    keychain_loaded: bool,
 }
 
 impl Default for ManagerState {
     fn default() -> Self {
         let config: ClientConfig = <ClientConfig as BaseConfig>::load_err();
         Self {
-            config,
             info: Info::default(),
             vaults: BTreeMap::new(),
             internal_state: Vec::new(),
             temp_message: TempMessage::default(),
             stored_clipboard: None,
+            clipboard_generation: 0,
+            clipboard_clears_at: None,
             state: ConnectionState::Disconnected,
+            protocol_mismatch: None,
             notice: None,
+            next_request: 0,
+            awaiting: BTreeMap::new(),
+            timed_out: BTreeSet::new(),
+            queued: vec![],
+            toast: None,
+            error: None,
+            search: String::new(),
+            tag_filter: None,
+            last_interaction: Instant::now(),
+            pending_rotation: false,
+            selection: BTreeMap::new(),
+            custom_generator: false,
+            generator: ClassSpec::default(),
+            pronounceable: false,
+            system_theme: utils::system_theme(),
+            window_generation: 0,
+            window_width: config
+                .window_size
+                .map_or(window::Settings::default().size.width, |(width, _)| width),
+            passwords: BTreeMap::new(),
+            pending_unlock: None,
+            last_attempt: None,
+            password_attempts: 0,
+            hotkey: None,
+            tray: None,
+            tray_copy: None,
+            pending_rename: None,
+            keychain_loaded: false,
+            config,
         }
     }
 }
 
 enum ConnectionState {
     Disconnected,
+    Connecting(Duration),
     Connected(connection::Connection),
 }
 
 impl ManagerState {
-    fn get_password(&self) -> Option<Password> {
-        for state in &self.internal_state {
-            if let InternalState::Password(p) = state {
-                return Some(p.password.clone());
-            }
+    fn get_password(&self, vault: &str) -> Option<Password> {
+        self.passwords.get(vault).cloned()
+    }
+    // uses the vault's password when it's already unlocked, otherwise asks for it
+    fn ask_password(&mut self, state: PasswordState) -> Command<GUIMessage> {
+        let password = self
+            .temp_message
+            .vault()
+            .and_then(|vault| self.get_password(vault));
+        match password {
+            Some(password) => self.handle_password_submit(password),
+            None => self.push_internal_state(state),
         }
-        None
+    }
+    // rides along with anything that needs the vault's password since there is no other time
+    // the password is around, the manager decides whether one is due
+    fn scheduled_backup(&self, password: Password) -> Option<ManagerMessage> {
+        let vault = self.temp_message.vault()?;
+        (self.config.backup_interval_hours > 0).then(|| ManagerMessage::ScheduledBackup {
+            vault: vault.to_string(),
+            password,
+            dir: self.config.backup_dir.clone(),
+            interval_hours: self.config.backup_interval_hours,
+            keep: self.config.backup_keep,
+        })
     }
     fn handle_password_submit(&mut self, password: Password) -> Command<GUIMessage> {
-        let (command, messages) = match &self.temp_message {
+        let backup = self.scheduled_backup(password.clone());
+        self.last_attempt = Some(self.temp_message.clone());
+        let unlocking = self
+            .temp_message
+            .vault()
+            .map(|vault| (vault.to_string(), password.clone()));
+        let mut unlock_id = None;
+        let (command, mut messages) = match &self.temp_message {
             TempMessage::Get(vault, key) => {
+                let (vault, key) = (vault.clone(), key.clone());
                 let message = self.temp_message.with_password(password);
+                // sent here so the entry knows which answer is its own
+                let id = self.request_id();
                 self.internal_state.push(
                     EntryState::from_entry(
-                        vault.to_string(),
-                        key.to_string(),
-                        self.info.get(vault).unwrap().get(key).unwrap().to_string(),
+                        vault.clone(),
+                        key.clone(),
+                        *self.info.get(&vault).unwrap().get(&key).unwrap(),
                     )
+                    .hold_to_reveal(!self.config.sticky_reveal)
+                    .hidden(!self.config.reveal_secrets)
+                    .tags(self.info.tags(&vault, &key))
+                    .request(id)
                     .into(),
                 );
+                unlock_id = Some(id);
+                self.send_with_id(id, message);
                 self.temp_message = TempMessage::Update(
-                    vault.into(),
-                    key.to_string(),
+                    vault,
+                    key,
                     StoreChoice::default(),
                     StoreChoice::default().convert_default().as_hash(),
+                    vec![],
+                );
+                (text_input::focus(INPUT_ID.clone()), vec![])
+            }
+            // the new entry starts out empty and is filled in once the read comes back
+            TempMessage::Duplicate(vault, key) => {
+                let (vault, key) = (vault.clone(), key.clone());
+                // the entry can be gone by the time the password is given
+                let Some(kind) = self.info.get(&vault).and_then(|schema| schema.get(&key)) else {
+                    self.notice = Some(format!("{} no longer exists", key));
+                    self.internal_state = vec![];
+                    self.temp_message = TempMessage::default();
+                    return close_popup();
+                };
+                let choice = StoreChoice::from(*kind);
+                let message = self.temp_message.with_password(password);
+                let id = self.request_id();
+                let name = format!("{} copy", key);
+                let exists = self
+                    .info
+                    .get(&vault)
+                    .is_some_and(|schema| schema.data.contains_key(&name));
+                self.internal_state.push(
+                    NewEntryState::for_vault(vault.clone())
+                        .name(name.clone(), exists)
+                        .generator(
+                            self.custom_generator,
+                            self.generator.clone(),
+                            self.pronounceable,
+                        )
+                        .presets(self.config.presets.keys().cloned().collect())
+                        .tags(self.info.tags(&vault, &key))
+                        .request(id)
+                        .into(),
                 );
-                (text_input::focus(INPUT_ID.clone()), vec![message])
+                unlock_id = Some(id);
+                self.send_with_id(id, message);
+                self.temp_message = TempMessage::New(
+                    vault.into(),
+                    name,
+                    choice,
+                    choice.convert_default().as_hash(),
+                    vec![],
+                );
+                (text_input::focus(INPUT_ID.clone()), vec![])
             }
             TempMessage::Delete(..) => {
                 let message = self.temp_message.with_password(password);
@@ -105,13 +316,69 @@ impl ManagerState {
                 self.temp_message = TempMessage::default();
                 (Command::none(), vec![message, ManagerMessage::Info])
             }
-            TempMessage::New(..) => {
+            TempMessage::DeleteMany(vault, _) | TempMessage::Regenerate(vault, ..) => {
+                let message = self.temp_message.with_password(password);
+                self.selection.remove(vault);
+                self.internal_state = vec![];
+                self.temp_message = TempMessage::default();
+                (Command::none(), vec![message, ManagerMessage::Info])
+            }
+            // the tags aren't secret so they're kept apart from the entry
+            TempMessage::New(vault, key, ..) | TempMessage::Update(vault, key, ..) => {
+                let (vault, key) = (vault.clone(), key.clone());
+                let message = self.temp_message.with_password(password);
+                let mut messages = vec![message];
+                if let Some(tags) = self.editing_tags() {
+                    messages.push(ManagerMessage::SetTags { vault, key, tags });
+                }
+                messages.push(ManagerMessage::Info);
+                self.internal_state = vec![];
+                self.temp_message = TempMessage::default();
+                (Command::none(), messages)
+            }
+            TempMessage::Rename(..) => {
+                let message = self.temp_message.with_password(password);
+                self.internal_state = vec![];
+                self.temp_message = TempMessage::default();
+                (Command::none(), vec![message, ManagerMessage::Info])
+            }
+            // the decrypted entries only go to the file the user picks, nothing is kept around
+            TempMessage::Export(..) => {
+                let message = self.temp_message.with_password(password);
+                self.internal_state = vec![];
+                self.temp_message = TempMessage::default();
+                (Command::none(), vec![message])
+            }
+            TempMessage::Import(..) => {
+                let message = self.temp_message.with_password(password);
+                self.internal_state = vec![];
+                self.temp_message = TempMessage::default();
+                (Command::none(), vec![message, ManagerMessage::Info])
+            }
+            // only the names of the entries come back
+            TempMessage::CheckReuse(..) | TempMessage::CheckStrength(..) => {
+                let message = self.temp_message.with_password(password);
+                self.internal_state = vec![];
+                self.temp_message = TempMessage::default();
+                (Command::none(), vec![message])
+            }
+            TempMessage::CreateRecovery(..) => {
                 let message = self.temp_message.with_password(password);
                 self.internal_state = vec![];
                 self.temp_message = TempMessage::default();
                 (Command::none(), vec![message, ManagerMessage::Info])
             }
-            TempMessage::Update(..) => {
+            // the archive's password comes first, then the one the restored vaults will have
+            TempMessage::BackupImport(path, collision, None) => {
+                self.temp_message =
+                    TempMessage::BackupImport(path.clone(), *collision, Some(password));
+                self.internal_state.pop();
+                let focus = self.push_internal_state(
+                    PasswordState::confirm().titled("Password for the restored vaults"),
+                );
+                (focus, vec![])
+            }
+            TempMessage::ImportVaults(..) | TempMessage::BackupImport(..) => {
                 let message = self.temp_message.with_password(password);
                 self.internal_state = vec![];
                 self.temp_message = TempMessage::default();
@@ -136,7 +403,22 @@ impl ManagerState {
                 (Command::none(), vec![message, ManagerMessage::Info])
             }
         };
+        // first so a vault is backed up before it gets deleted
+        if let Some(backup) = backup {
+            let id = self.request_id();
+            self.send_with_id(id, backup);
+        }
+        // the one carrying the password always goes first, and the password is only kept once
+        // that request comes back fine
+        if !messages.is_empty() {
+            let id = self.request_id();
+            unlock_id.get_or_insert(id);
+            self.send_with_id(id, messages.remove(0));
+        }
         self.send_message(messages);
+        self.pending_unlock = unlock_id
+            .zip(unlocking)
+            .map(|(id, (vault, password))| (id, vault, password));
         command
     }
     fn active_state(&self) -> Option<&InternalState> {
@@ -153,88 +435,796 @@ impl ManagerState {
         let mut vaults = BTreeMap::new();
         for (name, schema) in info.data.iter() {
             let mut vault = Vault::new(name.into(), BTreeMap::new());
-            vault.update(schema);
+            vault.update(schema, &info, self.config.rotation_days);
             if let Some(curr_vault) = self.vaults.get(name) {
                 vault.expanded = curr_vault.expanded;
+            } else {
+                vault.expanded = self.config.expanded.contains(name);
             }
             vaults.insert(name.into(), vault);
         }
 
+        if self.config.remember_passwords && !self.keychain_loaded {
+            self.keychain_loaded = true;
+            for name in info.data.keys() {
+                if let Some(password) = keychain::fetch(name) {
+                    self.passwords.insert(name.to_string(), password);
+                }
+            }
+        }
+        // deleted vaults don't keep a saved password around
+        if self.config.remember_passwords {
+            for name in self.passwords.keys() {
+                if !info.data.contains_key(name) {
+                    let _ = keychain::forget(name);
+                }
+            }
+        }
+        self.passwords
+            .retain(|name, _| info.data.contains_key(name));
         self.vaults = vaults;
         self.info = info;
+        self.update_tray();
     }
-    fn update_entry(&mut self, data: Reads<Store>) {
-        // TODO: check if robust, could be that a response was given to a lower down state, but I
-        // find it unlikely it will get to be that way
-        if let Some(InternalState::Entry(entry)) = self.active_state_mut() {
-            for (key, value) in &data.data {
-                if *key == entry.key {
-                    entry.update(value.clone());
-                }
+
+    // only entries with a password to copy are offered
+    fn update_tray(&mut self) {
+        let Some(tray) = &mut self.tray else {
+            return;
+        };
+        let mut recent = self
+            .vaults
+            .iter()
+            .flat_map(|(name, vault)| vault.entries.values().map(move |entry| (name, entry)))
+            .filter(|(_, entry)| {
+                entry.used.is_some()
+                    && matches!(
+                        entry.kind,
+                        StoreKind::Password | StoreKind::UsernamePassword | StoreKind::Login
+                    )
+            })
+            .collect::<Vec<_>>();
+        recent.sort_by(|(_, a), (_, b)| b.used.cmp(&a.used));
+        tray.set_recent(
+            recent
+                .into_iter()
+                .take(RECENT_ENTRIES)
+                .map(|(name, entry)| (name.to_string(), entry.key.clone()))
+                .collect(),
+        );
+    }
+
+    fn show_window() -> Command<GUIMessage> {
+        Command::batch([
+            window::change_mode(window::Id::MAIN, window::Mode::Windowed),
+            window::minimize(window::Id::MAIN, false),
+            window::gain_focus(window::Id::MAIN),
+        ])
+    }
+    // re-key the vault ahead of the refreshed info so it keeps being shown expanded
+    fn rename_vault(&mut self, old: &str, new: &str) {
+        if let Some(mut vault) = self.vaults.remove(old) {
+            vault.name = new.to_string();
+            self.vaults.insert(new.to_string(), vault);
+        }
+        if let Some(password) = self.passwords.remove(old) {
+            if self.config.remember_passwords {
+                let _ = keychain::forget(old);
+                self.remember(new, &password);
             }
+            self.passwords.insert(new.to_string(), password);
         }
-        // seems dumb to loop twice
-        if let TempMessage::Update(_, update_key, ref mut choice, ref mut update_value) =
+        if self.config.expanded.remove(old) {
+            self.config.expanded.insert(new.to_string());
+            let _ = self.config.save();
+        }
+    }
+    fn save_window_later(&mut self) -> Command<GUIMessage> {
+        self.window_generation += 1;
+        let generation = self.window_generation;
+        delayed_command(Duration::from_secs(1), move |_| {
+            GUIMessage::SaveWindow(generation)
+        })
+    }
+    fn save_expanded(&mut self) -> Command<GUIMessage> {
+        self.config.expanded = self
+            .vaults
+            .iter()
+            .filter(|(_, vault)| vault.expanded)
+            .map(|(name, _)| name.to_string())
+            .collect();
+        if self.config.save().is_err() {
+            self.notice = Some("Failed to save config file".into());
+            return close_popup();
+        }
+        Command::none()
+    }
+    // fills in the new entry that is a duplicate of the one read, gives back whether there was one
+    fn fill_duplicate(&mut self, id: u64, data: &Reads<Store>) -> bool {
+        let new_state = self
+            .internal_state
+            .iter_mut()
+            .find_map(|state| match state {
+                InternalState::New(new_state) if new_state.request == Some(id) => Some(new_state),
+                _ => None,
+            });
+        let (Some(new_state), Some(value)) = (new_state, data.data.values().next()) else {
+            return false;
+        };
+        new_state.request = None;
+        new_state.fill(value.clone());
+        if let TempMessage::New(_, _, ref mut choice, ref mut fields, ref mut custom) =
             &mut self.temp_message
+        {
+            *choice = new_state.choice;
+            fields.clone_from(&new_state.value);
+            custom.clone_from(&new_state.custom);
+        }
+        true
+    }
+
+    // gives back whether a rotation was waiting on this entry, the read only goes to the entry
+    // that asked for it wherever it is in the stack
+    fn update_entry(&mut self, id: u64, data: Reads<Store>) -> bool {
+        let mut opened = false;
+        let entry = self
+            .internal_state
+            .iter_mut()
+            .find_map(|state| match state {
+                InternalState::Entry(entry) if entry.request == Some(id) => Some(entry),
+                _ => None,
+            });
+        let Some(entry) = entry else {
+            return false;
+        };
+        for (key, value) in &data.data {
+            if *key == entry.key {
+                entry.update(value.clone());
+                entry.updated = data.updated(key).cloned();
+                opened = true;
+            }
+        }
+        if let TempMessage::Update(
+            _,
+            update_key,
+            ref mut choice,
+            ref mut update_value,
+            ref mut custom,
+        ) = &mut self.temp_message
         {
             for (key, value) in data.data {
                 if *update_key == key {
                     let (new_choice, new_values) = value.split();
                     *choice = new_choice;
                     *update_value = new_values;
+                    *custom = value.custom_fields();
+                }
+            }
+        }
+        opened && std::mem::take(&mut self.pending_rotation)
+    }
+
+    fn update_field(&mut self, key: String, value: Secret<String>) {
+        match self.active_state_mut() {
+            Some(InternalState::New(new_state)) => {
+                new_state.value.insert(key.clone(), value.clone());
+                new_state.error = None;
+                new_state.edited = true;
+                if key == "password" {
+                    new_state.generated_bits = None;
                 }
+                new_state.focus_field(&key);
+            }
+            Some(InternalState::Entry(entry_state)) => {
+                entry_state.value.insert(key.clone(), value.clone());
+                entry_state.error = None;
+                entry_state.edited = true;
+            }
+            _ => {}
+        };
+        match &mut self.temp_message {
+            TempMessage::New(_, _, _, ref mut fields, _) => {
+                fields.insert(key, value);
+            }
+            TempMessage::Update(_, _, _, ref mut fields, _) => {
+                fields.insert(key, value);
             }
+            _ => {}
+        };
+    }
+
+    // the new entry would replace one already in the vault
+    fn name_taken(&self, name: &str) -> bool {
+        match &self.temp_message {
+            TempMessage::New(vault, ..) => self
+                .info
+                .get(vault)
+                .zip(utils::entry_key(name).ok())
+                .map(|(schema, key)| schema.data.contains_key(&key))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    // the tags of the entry being saved, an update can also come from somewhere without a form
+    fn editing_tags(&self) -> Option<Vec<String>> {
+        self.internal_state
+            .iter()
+            .rev()
+            .find_map(|state| match state {
+                InternalState::New(new_state) => Some(&new_state.tags),
+                InternalState::Entry(entry_state) => Some(&entry_state.tags),
+                _ => None,
+            })
+            .map(|tags| tags.split(',').map(str::to_string).collect())
+    }
+
+    // custom fields are kept the same in the form and the pending message
+    fn edit_custom_fields(&mut self, edit: impl Fn(&mut CustomFields)) {
+        match self.active_state_mut() {
+            Some(InternalState::New(new_state)) => {
+                edit(&mut new_state.custom);
+                new_state.edited = true;
+            }
+            Some(InternalState::Entry(entry_state)) => {
+                edit(&mut entry_state.custom);
+                entry_state.edited = true;
+            }
+            _ => {}
+        };
+        match &mut self.temp_message {
+            TempMessage::New(.., ref mut custom) | TempMessage::Update(.., ref mut custom) => {
+                edit(custom)
+            }
+            _ => {}
+        };
+    }
+
+    // a button for every tag in use, the one being filtered by is highlighted
+    fn tag_bar(&self) -> Element<GUIMessage> {
+        let tags = self.info.all_tags();
+        let buttons = tags.into_iter().map(|tag| {
+            let style = if self.tag_filter.as_ref() == Some(&tag) {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            button(text(&tag).size(12))
+                .style(style)
+                .on_press(GUIMessage::FilterTag(tag))
+                .into()
+        });
+        row(buttons).spacing(5).padding([0, 10]).into()
+    }
+
+    // everything in the menu bar, with the path through the menus as the label
+    fn menu_items(&self) -> Vec<(String, GUIMessage)> {
+        let checked = |label: String, on: bool| {
+            if on {
+                format!("{} \u{2713}", label)
+            } else {
+                label
+            }
+        };
+        let mut items = vec![
+            ("File > New Vault".to_string(), GUIMessage::NewVault),
+            ("File > Lock".to_string(), GUIMessage::Lock),
+            (
+                "File > Forget Saved Passwords".to_string(),
+                GUIMessage::ForgetPasswords,
+            ),
+            ("File > Quit".to_string(), GUIMessage::Close),
+            ("View > Expand All".to_string(), GUIMessage::ExpandAll(true)),
+            (
+                "View > Collapse All".to_string(),
+                GUIMessage::ExpandAll(false),
+            ),
+            ("View > Audit Log".to_string(), GUIMessage::ShowAuditLog),
+            (
+                checked(
+                    "View > Always on Top".to_string(),
+                    self.config.always_on_top,
+                ),
+                GUIMessage::AlwaysOnTop(!self.config.always_on_top),
+            ),
+        ];
+        for theme in [AUTO_THEME, CUSTOM_THEME]
+            .into_iter()
+            .chain(THEMES.keys().map(|n| n.as_str()))
+        {
+            items.push((
+                checked(
+                    format!("Config > Theme > {}", theme),
+                    theme == self.config.theme,
+                ),
+                GUIMessage::ChangeTheme(theme.to_string()),
+            ));
         }
+        items.push(("Config > Accent Color".to_string(), GUIMessage::EditAccent));
+        for order in all::<SortOrder>() {
+            items.push((
+                checked(
+                    format!("Config > Sort Entries > {}", order),
+                    order == self.config.sort_order,
+                ),
+                GUIMessage::ChangeSortOrder(order),
+            ));
+        }
+        let per_vault: [(&str, fn(String) -> GUIMessage); 7] = [
+            ("Change Password", GUIMessage::ChangePassword),
+            ("Recover Password", GUIMessage::RecoverPassword),
+            ("Re-harden Key", GUIMessage::Reharden),
+            ("Reused Passwords", GUIMessage::CheckReuse),
+            ("Weak Passwords", GUIMessage::CheckStrength),
+            ("Export", GUIMessage::Export),
+            ("Import", GUIMessage::Import),
+        ];
+        for (label, message) in per_vault {
+            for name in self.vaults.keys() {
+                items.push((
+                    format!("Config > {} > {}", label, name),
+                    message(name.to_string()),
+                ));
+            }
+        }
+        items.push((
+            "Config > Import Bitwarden > By folder".to_string(),
+            GUIMessage::ImportBitwarden(None),
+        ));
+        for name in self.vaults.keys() {
+            items.push((
+                format!("Config > Import Bitwarden > {}", name),
+                GUIMessage::ImportBitwarden(Some(name.to_string())),
+            ));
+        }
+        for collision in all::<Collision>() {
+            items.push((
+                format!("Config > Restore Archive > {}", collision),
+                GUIMessage::RestoreArchive(collision),
+            ));
+        }
+        items.push(("Help > About".to_string(), GUIMessage::ShowAbout));
+        items
+    }
+
+    fn narrow(&self) -> bool {
+        self.window_width < NARROW_WIDTH
+    }
+
+    fn connection_indicator(&self) -> Element<GUIMessage> {
+        let theme = self.get_theme();
+        let palette = theme.extended_palette();
+        let (label, color) = match &self.state {
+            ConnectionState::Connected(_) => ("Connected".to_string(), palette.success.base.color),
+            ConnectionState::Connecting(retry) => (
+                format!("Connecting, retry in {:.1}s", retry.as_secs_f32()),
+                palette.primary.base.color,
+            ),
+            ConnectionState::Disconnected => {
+                ("Disconnected".to_string(), palette.danger.base.color)
+            }
+        };
+        let label = if self.protocol_mismatch.is_some() {
+            format!("{}, needs update", label)
+        } else if self.queued.is_empty() {
+            label
+        } else {
+            format!("{}, {} changes waiting", label, self.queued.len())
+        };
+        text(label).size(12).style(theme::Text::Color(color)).into()
+    }
+
+    // counts down to the clipboard being cleared so it isn't a surprise
+    fn clipboard_indicator(&self) -> Option<Element<GUIMessage>> {
+        let clears_at = self.clipboard_clears_at?;
+        let remaining = clears_at.saturating_duration_since(Instant::now());
+        // rounded up so it doesn't show 0 while the value is still there
+        let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        Some(
+            text(format!("Clipboard clears in {}s", seconds))
+                .size(12)
+                .into(),
+        )
+    }
+
+    fn request_id(&mut self) -> u64 {
+        self.next_request += 1;
+        self.next_request
     }
 
     fn send_message(&mut self, messages: Vec<ManagerMessage>) {
         for message in messages {
-            match self.state {
-                ConnectionState::Disconnected => {}
-                ConnectionState::Connected(ref mut connection) => {
-                    connection.send(message);
+            let id = self.request_id();
+            self.send_with_id(id, message);
+        }
+    }
+
+    // the answer comes back with the same id
+    fn send_with_id(&mut self, id: u64, message: ManagerMessage) {
+        match self.state {
+            ConnectionState::Disconnected | ConnectionState::Connecting(_) => {
+                if queueable(&message) {
+                    self.queued.push((id, message));
+                }
+            }
+            ConnectionState::Connected(ref mut connection) => {
+                if awaited(&message) {
+                    self.awaiting.insert(id, Instant::now() + REQUEST_TIMEOUT);
                 }
+                connection.send(id, message);
             }
         }
     }
 
+    // puts back what the password was for and asks again, until there have been too many tries
+    fn retry_password(&mut self, attempt: TempMessage) -> Command<GUIMessage> {
+        self.password_attempts += 1;
+        self.internal_state = vec![];
+        if self.password_attempts >= MAX_PASSWORD_ATTEMPTS {
+            self.password_attempts = 0;
+            self.temp_message = TempMessage::default();
+            self.error = Some("Too many wrong passwords".into());
+            return Command::none();
+        }
+        self.temp_message = attempt;
+        let error = format!(
+            "Wrong password, try again ({} of {} attempts)",
+            self.password_attempts, MAX_PASSWORD_ATTEMPTS
+        );
+        self.push_internal_state(PasswordState::default().with_error(error))
+    }
+
+    fn remember(&mut self, vault: &str, password: &Password) {
+        if let Err(e) = keychain::store(vault, password) {
+            self.notice = Some(format!("Couldn't save the password in the keychain: {}", e));
+        }
+    }
+
+    // forget everything that was unlocked so the next sensitive action prompts again
+    fn lock(&mut self) {
+        self.internal_state = vec![];
+        self.temp_message = TempMessage::default();
+        self.pending_rotation = false;
+        self.passwords.clear();
+        self.pending_unlock = None;
+        self.last_attempt = None;
+        self.password_attempts = 0;
+    }
+
+    // the manual lock also puts the clipboard back right away and hides the entries
+    fn lock_all(&mut self) -> Command<GUIMessage> {
+        self.lock();
+        self.selection.clear();
+        for vault in self.vaults.values_mut() {
+            vault.expanded = false;
+        }
+        // the pending clear is now stale
+        self.clipboard_generation += 1;
+        self.clipboard_clears_at = None;
+        match self.stored_clipboard.take() {
+            Some(contents) => self.write_clipboard(&contents),
+            None => Command::none(),
+        }
+    }
+
+    // an entry that was typed into gets a chance to be saved first, the window might be hidden in
+    // the tray so it's brought up to ask
+    fn request_quit(&mut self) -> Command<GUIMessage> {
+        let edited = self.internal_state.iter().any(|state| match state {
+            InternalState::Entry(entry_state) => entry_state.edited,
+            InternalState::New(new_state) => new_state.edited,
+            _ => false,
+        });
+        if edited {
+            let confirm = self.push_internal_state(ConfirmDiscardState::new(true));
+            return Command::batch([Self::show_window(), confirm]);
+        }
+        self.quit()
+    }
+
+    // a copy that hasn't been cleared yet is put back right away, a clipboard command wouldn't get
+    // to run once the window is gone
+    fn quit(&mut self) -> Command<GUIMessage> {
+        if self.clipboard_clears_at.take().is_some() {
+            self.clipboard_generation += 1;
+            // nothing could be read before copying, so empty is the best that can be done
+            let contents = self
+                .stored_clipboard
+                .take()
+                .unwrap_or_else(|| String::new().into());
+            // nowhere left to say it failed
+            let _ = self.restore_clipboard_now(&contents);
+        }
+        window::close(window::Id::MAIN)
+    }
+
+    fn restore_clipboard_now(&self, contents: &Password) -> anyhow::Result<()> {
+        let mut clipboard = arboard::Clipboard::new()?;
+        #[cfg(target_os = "linux")]
+        if self.config.clipboard_target == ClipboardTarget::Primary {
+            use arboard::{LinuxClipboardKind, SetExtLinux};
+            clipboard
+                .set()
+                .clipboard(LinuxClipboardKind::Primary)
+                .text(contents.expose_secret().as_str())?;
+            return Ok(());
+        }
+        clipboard.set_text(contents.expose_secret().as_str())?;
+        Ok(())
+    }
+
+    // a request that isn't answered in time is given up on, the answer is ignored if it does
+    // show up later so it can't land on whatever is open by then
+    fn check_timeouts(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .awaiting
+            .iter()
+            .filter(|(_, deadline)| now >= **deadline)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        if expired.is_empty() {
+            return;
+        }
+        for id in expired {
+            self.awaiting.remove(&id);
+            self.timed_out.insert(id);
+        }
+        self.pending_unlock = None;
+        self.last_attempt = None;
+        self.internal_state = vec![];
+        self.temp_message = TempMessage::default();
+        self.error = Some("The vault manager didn't respond in time".into());
+    }
+
+    fn check_auto_lock(&mut self) -> Command<GUIMessage> {
+        let timeout = self.config.auto_lock_seconds;
+        // a copied value is left alone, it gets cleared on its own
+        let unlocked = !self.internal_state.is_empty() || !self.passwords.is_empty();
+        if timeout > 0 && unlocked && self.last_interaction.elapsed().as_secs() >= timeout {
+            self.lock();
+            self.notice = Some("Locked after inactivity".into());
+            return close_popup();
+        }
+        Command::none()
+    }
+
     fn get_theme(&self) -> Theme {
+        if self.config.theme == AUTO_THEME {
+            return self.system_theme.clone();
+        }
+        if self.config.theme == CUSTOM_THEME {
+            let mut palette = self.system_theme.palette();
+            if let Some(color) = self
+                .config
+                .accent_color
+                .as_deref()
+                .and_then(utils::hex_color)
+            {
+                palette.primary = color;
+            }
+            return Theme::custom(CUSTOM_THEME.to_string(), palette);
+        }
         THEMES.get(&self.config.theme).cloned().unwrap_or_default()
     }
 
+    // copy to the clipboard and restore the previous contents after the configured time, the
+    // previous contents are read before writing so they can't be read back as the new value
+    fn copy_to_clipboard(&self, value: Password) -> Command<GUIMessage> {
+        let copy =
+            move |s: Option<String>| GUIMessage::CopyClipboard(s.map(|x| x.into()), value.clone());
+        match self.config.clipboard_target {
+            ClipboardTarget::Clipboard => iced::clipboard::read(copy),
+            ClipboardTarget::Primary => iced::clipboard::read_primary(copy),
+        }
+    }
+
+    // reading, writing and restoring all go to the same selection
+    fn write_clipboard(&self, contents: &Password) -> Command<GUIMessage> {
+        let contents = contents.expose_secret().into();
+        match self.config.clipboard_target {
+            ClipboardTarget::Clipboard => iced::clipboard::write(contents),
+            ClipboardTarget::Primary => iced::clipboard::write_primary(contents),
+        }
+    }
+
     fn push_internal_state(&mut self, state: impl Into<InternalState>) -> Command<GUIMessage> {
         self.internal_state.push(state.into());
         text_input::focus(INPUT_ID.clone())
     }
 
     fn view(&self) -> Element<GUIMessage> {
-        let top_layer = self.internal_state.last().map(|state| state.view());
+        let top_layer = self.internal_state.last().map(|state| {
+            container(state.view())
+                .id(MODAL_ID.clone())
+                .width(Length::Fill)
+                .center_x()
+        });
 
         let menu = |items| Menu::new(items).max_width(180.0).offset(0.0).spacing(0.0);
-        let themes: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = THEMES
-            .iter()
-            .map(|(n, t)| {
-                let item = if *n == self.config.theme {
-                    action_selected_item(text(n), GUIMessage::ChangeTheme(t.clone()))
+        let themes: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = [AUTO_THEME, CUSTOM_THEME]
+            .into_iter()
+            .chain(THEMES.keys().map(|n| n.as_str()))
+            .map(|n| {
+                let item = if n == self.config.theme {
+                    action_selected_item(text(n), GUIMessage::ChangeTheme(n.to_string()))
                 } else {
-                    action_item(text(n), GUIMessage::ChangeTheme(t.clone()))
+                    action_item(text(n), GUIMessage::ChangeTheme(n.to_string()))
                 };
                 Item::new(item)
             })
             .collect::<Vec<_>>();
 
         let theme_menu = Menu::new(themes).max_width(200.0).offset(15.0).spacing(5.0);
-        #[rustfmt::skip]
-        let menu = menu_bar!(
-            (section_header("File"), menu(menu_items!(
-                (action_item_shortcut("New Vault".to_string()))
-                (action_item_shortcut("Quit".to_string()))
-                )
+        let orders: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = all::<SortOrder>()
+            .map(|order| {
+                let item = if order == self.config.sort_order {
+                    action_selected_item(text(order), GUIMessage::ChangeSortOrder(order))
+                } else {
+                    action_item(text(order), GUIMessage::ChangeSortOrder(order))
+                };
+                Item::new(item)
+            })
+            .collect::<Vec<_>>();
+        let sort_menu = Menu::new(orders).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::ChangePassword(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let password_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::RecoverPassword(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let recover_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::Reharden(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let reharden_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::CheckReuse(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let reuse_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::CheckStrength(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let weak_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::Export(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let export_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = self
+            .vaults
+            .keys()
+            .map(|name| {
+                Item::new(action_item(
+                    text(name),
+                    GUIMessage::Import(name.to_string()),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let import_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let mut vaults: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = vec![Item::new(
+            action_item(text("By folder"), GUIMessage::ImportBitwarden(None)),
+        )];
+        vaults.extend(self.vaults.keys().map(|name| {
+            Item::new(action_item(
+                text(name),
+                GUIMessage::ImportBitwarden(Some(name.to_string())),
             ))
-            (section_header("Config"), menu(menu_items!(
-                (submenu_item("Theme"), theme_menu)))
+        }));
+        let bitwarden_menu = Menu::new(vaults).max_width(200.0).offset(15.0).spacing(5.0);
+        let collisions: Vec<Item<GUIMessage, iced::Theme, iced::Renderer>> = all::<Collision>()
+            .map(|collision| {
+                Item::new(action_item(
+                    text(collision),
+                    GUIMessage::RestoreArchive(collision),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let restore_menu = Menu::new(collisions)
+            .max_width(200.0)
+            .offset(15.0)
+            .spacing(5.0);
+        let on_top = if self.config.always_on_top {
+            action_selected_item(text("Always on Top"), GUIMessage::AlwaysOnTop(false))
+        } else {
+            action_item(text("Always on Top"), GUIMessage::AlwaysOnTop(true))
+        };
+        let narrow = self.narrow();
+        #[rustfmt::skip]
+        let file_menu = menu(menu_items!(
+            (action_item_shortcut("New Vault".to_string()))
+            (action_item_shortcut("Lock".to_string()))
+            (action_item(text("Forget Saved Passwords"), GUIMessage::ForgetPasswords))
+            (action_item_shortcut("Quit".to_string()))
+        ));
+        #[rustfmt::skip]
+        let view_menu = menu(menu_items!(
+            (action_item(text("Expand All"), GUIMessage::ExpandAll(true)))
+            (action_item(text("Collapse All"), GUIMessage::ExpandAll(false)))
+            (action_item(text("Audit Log"), GUIMessage::ShowAuditLog))
+            (on_top)
+        ));
+        #[rustfmt::skip]
+        let config_menu = menu(menu_items!(
+            (submenu_item("Theme"), theme_menu)
+            (action_item(text("Accent Color"), GUIMessage::EditAccent))
+            (submenu_item("Sort Entries"), sort_menu)
+            (submenu_item("Change Password"), password_menu)
+            (submenu_item("Recover Password"), recover_menu)
+            (submenu_item("Re-harden Key"), reharden_menu)
+            (submenu_item("Reused Passwords"), reuse_menu)
+            (submenu_item("Weak Passwords"), weak_menu)
+            (submenu_item("Export"), export_menu)
+            (submenu_item("Import"), import_menu)
+            (submenu_item("Import Bitwarden"), bitwarden_menu)
+            (submenu_item("Restore Archive"), restore_menu)
+        ));
+        #[rustfmt::skip]
+        let help_menu = menu(menu_items!(
+            (action_item_shortcut("Menu".to_string()))
+            (action_item(text("About"), GUIMessage::ShowAbout))
+        ));
+        // the same sections, under one button when there's no room for them side by side
+        #[rustfmt::skip]
+        let menu = if narrow {
+            menu_bar!(
+                (hamburger(), menu(menu_items!(
+                    (submenu_item("File"), file_menu)
+                    (submenu_item("View"), view_menu)
+                    (submenu_item("Config"), config_menu)
+                    (submenu_item("Help"), help_menu)
+                )))
             )
-        )
+        } else {
+            menu_bar!(
+                (section_header("File"), file_menu)
+                (section_header("View"), view_menu)
+                (section_header("Config"), config_menu)
+                (section_header("Help"), help_menu)
+            )
+        }
         .draw_path(menu::DrawPath::Backdrop)
         .style(|theme:&iced::Theme| menu::Appearance{
             // path_border: Border{
@@ -244,25 +1234,74 @@ impl ManagerState {
             ..theme.appearance(&MenuBarStyle::Default)
         });
 
+        let lock_button = tooltip(
+            button("Lock").on_press(GUIMessage::Lock),
+            "Forget the entered passwords",
+            tooltip::Position::Bottom,
+        );
+        let menu = row![container(menu).width(Length::Fill), lock_button]
+            .align_items(iced::Alignment::Center)
+            .padding([0, 5]);
+
         // let new_vault = button("New Vault").on_press(GUIMessage::NewVault);
-        let content = scrollable(
-            column(self.vaults.values().map(|v| {
+        let search = text_input("Search", &self.search)
+            .id(SEARCH_ID.clone())
+            .on_input(GUIMessage::SearchChanged)
+            .width(Length::Fill);
+        let vaults: Vec<Element<GUIMessage>> = self
+            .vaults
+            .values()
+            .filter(|v| self.search.is_empty() || v.matches(&self.search))
+            .filter(|v| v.has_tag(self.tag_filter.as_deref()))
+            .map(|v| {
                 container(
-                    v.view()
-                        .map(move |message| GUIMessage::VaultMessage(message, v.name.clone())),
+                    v.view(
+                        &self.search,
+                        self.tag_filter.as_deref(),
+                        self.config.sort_order,
+                        self.selection.get(&v.name),
+                        self.passwords.contains_key(&v.name),
+                        narrow,
+                    )
+                    .map(move |message| GUIMessage::VaultMessage(message, v.name.clone())),
                 )
-                .padding(3)
+                .padding(if narrow { 1 } else { 3 })
                 .into()
-            }))
-            .padding(10),
-        );
+            })
+            .collect();
+        let content = scrollable(column(vaults).padding(if narrow { 2 } else { 10 }));
+        let mut status = row![search];
+        if let Some(indicator) = self.clipboard_indicator() {
+            status = status.push(indicator);
+        }
+        let content = column![
+            status
+                .push(self.connection_indicator())
+                .spacing(10)
+                .padding([5, 10])
+                .align_items(iced::Alignment::Center),
+            self.tag_bar(),
+            content
+        ];
 
         // let info = self.temp_message.view();
-        let primary = container(column![menu, content]);
-        let main = modal(primary, top_layer)
+        let primary = if let Some(e) = &self.error {
+            container(column![menu, error_banner(e), content])
+        } else {
+            container(column![menu, content])
+        };
+        let main: Element<GUIMessage> = modal(primary, top_layer)
             .backdrop(GUIMessage::Exit)
             .on_esc(GUIMessage::Exit)
-            .align_y(alignment::Vertical::Center);
+            .align_y(alignment::Vertical::Center)
+            .into();
+        let main = match &self.toast {
+            Some((t, _)) => floating_element(main, toast(t))
+                .anchor(floating_element::Anchor::South)
+                .hide(false)
+                .into(),
+            None => main,
+        };
         if let Some(t) = &self.notice {
             let popup = button(
                 container(text(t))
@@ -300,53 +1339,267 @@ enum InternalState {
     Entry(EntryState),
     New(NewEntryState),
     Prompt(PromptState),
+    ChangePassword(ChangePasswordState),
+    Rename(RenameState),
+    RenameVault(RenameVaultState),
+    ConfirmDelete(ConfirmDeleteState),
+    ConfirmDeleteEntries(ConfirmDeleteEntriesState),
+    ConfirmRegenerate(ConfirmRegenerateState),
+    ConfirmDiscard(ConfirmDiscardState),
+    Accent(AccentState),
+    Recovery(RecoveryState),
+    Reuse(ReuseState),
+    Weak(WeakState),
+    Audit(AuditState),
+    Qr(QrState),
+    About(AboutState),
+    Menu(MenuState),
     // NewVault(NewVaultState),
 }
 
-impl From<PasswordState> for InternalState {
-    fn from(value: PasswordState) -> Self {
-        InternalState::Password(value)
+impl From<ConfirmDiscardState> for InternalState {
+    fn from(value: ConfirmDiscardState) -> Self {
+        InternalState::ConfirmDiscard(value)
     }
 }
 
-impl From<EntryState> for InternalState {
-    fn from(value: EntryState) -> Self {
-        InternalState::Entry(value)
+impl From<AccentState> for InternalState {
+    fn from(value: AccentState) -> Self {
+        InternalState::Accent(value)
     }
 }
 
-impl From<NewEntryState> for InternalState {
-    fn from(value: NewEntryState) -> Self {
-        InternalState::New(value)
+impl From<RecoveryState> for InternalState {
+    fn from(value: RecoveryState) -> Self {
+        InternalState::Recovery(value)
     }
 }
 
-impl From<PromptState> for InternalState {
-    fn from(value: PromptState) -> Self {
-        InternalState::Prompt(value)
+impl From<ReuseState> for InternalState {
+    fn from(value: ReuseState) -> Self {
+        InternalState::Reuse(value)
     }
 }
 
-// impl From<NewVaultState> for InternalState {
-//     fn from(value: NewVaultState) -> Self {
-//         InternalState::NewVault(value)
-//     }
-// }
+impl From<QrState> for InternalState {
+    fn from(value: QrState) -> Self {
+        InternalState::Qr(value)
+    }
+}
 
-fn delayed_command(
-    time: u64,
-    callback: impl FnOnce(()) -> GUIMessage + 'static + MaybeSend,
-) -> Command<GUIMessage> {
-    Command::perform(
-        async move {
-            let _ = async_std::task::sleep(std::time::Duration::from_secs(time)).await;
-        },
+impl From<MenuState> for InternalState {
+    fn from(value: MenuState) -> Self {
+        InternalState::Menu(value)
+    }
+}
+
+impl From<AboutState> for InternalState {
+    fn from(value: AboutState) -> Self {
+        InternalState::About(value)
+    }
+}
+
+impl From<AuditState> for InternalState {
+    fn from(value: AuditState) -> Self {
+        InternalState::Audit(value)
+    }
+}
+
+impl From<WeakState> for InternalState {
+    fn from(value: WeakState) -> Self {
+        InternalState::Weak(value)
+    }
+}
+
+impl From<PasswordState> for InternalState {
+    fn from(value: PasswordState) -> Self {
+        InternalState::Password(value)
+    }
+}
+
+impl From<EntryState> for InternalState {
+    fn from(value: EntryState) -> Self {
+        InternalState::Entry(value)
+    }
+}
+
+impl From<NewEntryState> for InternalState {
+    fn from(value: NewEntryState) -> Self {
+        InternalState::New(value)
+    }
+}
+
+impl From<PromptState> for InternalState {
+    fn from(value: PromptState) -> Self {
+        InternalState::Prompt(value)
+    }
+}
+
+impl From<ChangePasswordState> for InternalState {
+    fn from(value: ChangePasswordState) -> Self {
+        InternalState::ChangePassword(value)
+    }
+}
+
+impl From<RenameState> for InternalState {
+    fn from(value: RenameState) -> Self {
+        InternalState::Rename(value)
+    }
+}
+
+impl From<RenameVaultState> for InternalState {
+    fn from(value: RenameVaultState) -> Self {
+        InternalState::RenameVault(value)
+    }
+}
+
+impl From<ConfirmDeleteState> for InternalState {
+    fn from(value: ConfirmDeleteState) -> Self {
+        InternalState::ConfirmDelete(value)
+    }
+}
+
+impl From<ConfirmRegenerateState> for InternalState {
+    fn from(value: ConfirmRegenerateState) -> Self {
+        InternalState::ConfirmRegenerate(value)
+    }
+}
+
+impl From<ConfirmDeleteEntriesState> for InternalState {
+    fn from(value: ConfirmDeleteEntriesState) -> Self {
+        InternalState::ConfirmDeleteEntries(value)
+    }
+}
+
+// impl From<NewVaultState> for InternalState {
+//     fn from(value: NewVaultState) -> Self {
+//         InternalState::NewVault(value)
+//     }
+// }
+
+fn delayed_command(
+    time: Duration,
+    callback: impl FnOnce(()) -> GUIMessage + 'static + MaybeSend,
+) -> Command<GUIMessage> {
+    Command::perform(
+        async move {
+            let _ = async_std::task::sleep(time).await;
+        },
         callback,
     )
 }
 
+// requests that something on screen waits on, the rest only update things in the background
+fn awaited(message: &ManagerMessage) -> bool {
+    match message {
+        ManagerMessage::Export { .. }
+        | ManagerMessage::CheckBreach { .. }
+        | ManagerMessage::CheckReuse { .. }
+        | ManagerMessage::CheckStrength { .. } => true,
+        ManagerMessage::VaultMessage(_, message) => {
+            matches!(message, Message::Get(..) | Message::GetAll(_))
+        }
+        _ => false,
+    }
+}
+
+// changes are kept until the connection is back, anything that only reads would be stale or
+// unexpected by the time it's answered so it's dropped
+fn queueable(message: &ManagerMessage) -> bool {
+    match message {
+        ManagerMessage::Empty
+        | ManagerMessage::List
+        | ManagerMessage::Info
+        | ManagerMessage::AuditLog(_)
+        | ManagerMessage::Version
+        | ManagerMessage::Handshake(_)
+        | ManagerMessage::Export { .. }
+        | ManagerMessage::CheckBreach { .. }
+        | ManagerMessage::CheckReuse { .. }
+        | ManagerMessage::CheckStrength { .. } => false,
+        ManagerMessage::VaultMessage(_, message) => !matches!(
+            message,
+            Message::Get(..) | Message::GetAll(_) | Message::Schema | Message::BackupList
+        ),
+        _ => true,
+    }
+}
+
+fn toast<'a>(message: &str) -> Element<'a, GUIMessage> {
+    container(text(message))
+        .padding([5, 15])
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Appearance {
+                border: Border {
+                    color: palette.primary.strong.color,
+                    width: 1.0,
+                    radius: 4.0.into(),
+                },
+                background: Some(palette.background.weak.color.into()),
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
 fn close_popup() -> Command<GUIMessage> {
-    delayed_command(5, |_| GUIMessage::ClosePopup)
+    delayed_command(Duration::from_secs(5), |_| GUIMessage::ClosePopup)
+}
+
+async fn load_import() -> Result<Option<Vec<(String, Store)>>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .add_filter("CSV or JSON", &ImportFormat::extensions())
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+    let format = ImportFormat::from_path(file.path())
+        .ok_or_else(|| "Unrecognized import format, expected a .csv or .json file".to_string())?;
+    let data = String::from_utf8(file.read().await).map_err(|e| e.to_string())?;
+    format.parse(&data).map(Some).map_err(|e| e.to_string())
+}
+
+async fn load_bitwarden(vault: Option<String>) -> Result<Option<BitwardenImport>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .add_filter("Bitwarden JSON", &["json"])
+        .pick_file()
+        .await
+    else {
+        return Ok(None);
+    };
+    let data = String::from_utf8(file.read().await).map_err(|e| e.to_string())?;
+    bitwarden::parse(&data, vault.as_deref())
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+// the header is checked here so a wrong file is caught before asking for the password
+async fn pick_archive() -> Result<Option<PathBuf>, String> {
+    let Some(file) = rfd::AsyncFileDialog::new().pick_file().await else {
+        return Ok(None);
+    };
+    let path = file.path().to_path_buf();
+    archive::read(&path).map_err(|e| e.to_string())?;
+    Ok(Some(path))
+}
+
+// a cancelled dialog isn't an error, there just isn't anything to report
+async fn save_export(vault: String, data: Secret<String>) -> Result<Option<String>, String> {
+    let format = ExportFormat::default();
+    let Some(file) = rfd::AsyncFileDialog::new()
+        .set_file_name(format!("{}.{}", vault, format.extension()))
+        .add_filter(format.to_string(), &[format.extension()])
+        .save_file()
+        .await
+    else {
+        return Ok(None);
+    };
+    file.write(data.expose_secret().as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write export: {}", e))?;
+    Ok(Some(file.path().display().to_string()))
 }
 
 impl InternalState {
@@ -356,6 +1609,21 @@ impl InternalState {
             Self::New(new_state) => new_state.view(),
             Self::Entry(entry_state) => entry_state.view(),
             Self::Prompt(prompt_state) => prompt_state.view(),
+            Self::ChangePassword(change_state) => change_state.view(),
+            Self::Rename(rename_state) => rename_state.view(),
+            Self::RenameVault(rename_state) => rename_state.view(),
+            Self::ConfirmDelete(confirm_state) => confirm_state.view(),
+            Self::ConfirmDeleteEntries(confirm_state) => confirm_state.view(),
+            Self::ConfirmRegenerate(confirm_state) => confirm_state.view(),
+            Self::ConfirmDiscard(confirm_state) => confirm_state.view(),
+            Self::Accent(accent_state) => accent_state.view(),
+            Self::Recovery(recovery_state) => recovery_state.view(),
+            Self::Reuse(reuse_state) => reuse_state.view(),
+            Self::Weak(weak_state) => weak_state.view(),
+            Self::Audit(audit_state) => audit_state.view(),
+            Self::Qr(qr_state) => qr_state.view(),
+            Self::About(about_state) => about_state.view(),
+            Self::Menu(menu_state) => menu_state.view(),
             // Self::NewVault(new_vault_state) => new_vault_state.view(),
         }
     }
@@ -372,111 +1640,587 @@ impl Application for ManagerState {
     }
 
     fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
-        (Self::default(), Command::none())
+        let mut state = Self::default();
+        if let Some(chord) = state.config.global_hotkey.clone() {
+            match hotkey::register(&chord) {
+                Ok(hotkey) => state.hotkey = Some(hotkey),
+                Err(e) => state.error = Some(e.to_string()),
+            }
+        }
+        if state.config.minimize_to_tray {
+            match Tray::new() {
+                Ok(tray) => state.tray = Some(tray),
+                Err(e) => state.error = Some(e.to_string()),
+            }
+        }
+        (state, Command::none())
+    }
+
+    fn view(&self) -> Element<Self::Message> {
+        self.view()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let connection_subscriber =
+            connection::connect(self.config.data_dir.clone()).map(GUIMessage::Event);
+
+        let keyboard_subscriber = keyboard::on_key_press(|key, modifiers| {
+            // the ones with a modifier go first, otherwise Shift+Tab could be taken as Tab
+            let mut shortcuts = SHORTCUTS.values().collect::<Vec<_>>();
+            shortcuts.sort_by_key(|shortcut| shortcut.modifier().is_none());
+            for shortcut in shortcuts {
+                let res = shortcut.check(&key, &modifiers);
+                if res.is_some() {
+                    return res;
+                }
+            }
+            None
+        });
+
+        // let keyboard_subscriber = keyboard::on_key_press(|key, modifiers| {
+        //     // println!("{:?}, {:?}", key, modifiers);
+        //     println!("{:?}", keyboard::Modifiers::COMMAND);
+        //     match (key.as_ref(), modifiers) {
+        //         (key::Key::Character("n"), keyboard::Modifiers::COMMAND) => {
+        //             Some(GUIMessage::NewVault)
+        //         }
+        //         (key::Key::Character("q"), keyboard::Modifiers::COMMAND) => Some(GUIMessage::Close),
+        //         (key::Key::Named(key::Named::Tab), _) => {
+        //             Some(GUIMessage::TabPressed(modifiers.shift()))
+        //         }
+        //         _ => {
+        //             // println!("{:?}, {:?}", key, modifiers);
+        //             None
+        //         }
+        //     }
+        //     // let keyboard::Key::Named(key) = key else {
+        //     //     return None;
+        //     // };
+        //     //
+        //     // match (key, modifiers) {
+        //     //     (key::Named::Tab, _) => Some(Message::TabPressed {
+        //     //         shift: modifiers.shift(),
+        //     //     }),
+        //     //     (key::Named::ArrowUp, keyboard::Modifiers::SHIFT) => {
+        //     //         Some(Message::ToggleFullscreen(window::Mode::Fullscreen))
+        //     //     }
+        //     //     (key::Named::ArrowDown, keyboard::Modifiers::SHIFT) => {
+        //     //         Some(Message::ToggleFullscreen(window::Mode::Windowed))
+        //     //     }
+        //     //     _ => None,
+        //     // }
+        // });
+
+        let window_subscriber = iced::event::listen_with(|event, _| match event {
+            iced::Event::Window(_, window::Event::Resized { width, height }) => {
+                Some(GUIMessage::WindowResized(width as f32, height as f32))
+            }
+            iced::Event::Window(_, window::Event::Moved { x, y }) => {
+                Some(GUIMessage::WindowMoved(x, y))
+            }
+            iced::Event::Window(_, window::Event::CloseRequested) => {
+                Some(GUIMessage::CloseRequested)
+            }
+            _ => None,
+        });
+
+        let mut subscriptions = vec![
+            connection_subscriber,
+            keyboard_subscriber,
+            window_subscriber,
+        ];
+
+        if self.tray.is_some() {
+            subscriptions.push(tray::listen().map(GUIMessage::Tray));
+        }
+
+        if self.hotkey.is_some() {
+            subscriptions.push(hotkey::listen().map(|_| GUIMessage::RaiseWindow));
+        }
+
+        // keeps the displayed TOTP code and countdowns current and checks for idling
+        let showing_totp = matches!(
+            self.active_state(),
+            Some(InternalState::Entry(entry_state)) if entry_state.choice == StoreChoice::Totp
+        );
+        if showing_totp
+            || self.clipboard_clears_at.is_some()
+            || self.config.auto_lock_seconds > 0
+            || !self.awaiting.is_empty()
+        {
+            subscriptions.push(iced::time::every(Duration::from_secs(1)).map(|_| GUIMessage::Tick));
+        }
+
+        // the system preference can change at any time, but checking when the window comes
+        // back into focus catches most of it
+        if self.config.theme == AUTO_THEME || self.config.theme == CUSTOM_THEME {
+            subscriptions.push(
+                iced::time::every(Duration::from_secs(60)).map(|_| GUIMessage::CheckSystemTheme),
+            );
+            subscriptions.push(iced::event::listen_with(|event, _| match event {
+                iced::Event::Window(_, window::Event::Focused) => {
+                    Some(GUIMessage::CheckSystemTheme)
+                }
+                _ => None,
+            }));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+
+    fn theme(&self) -> Theme {
+        self.get_theme()
     }
 
+    // focus goes back to the main view once the last modal is gone, the button that opened it
+    // can't hold focus
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        let had_modal = !self.internal_state.is_empty();
+        let command = self.handle_message(message);
+        if had_modal && self.internal_state.is_empty() {
+            Command::batch(vec![command, text_input::focus(SEARCH_ID.clone())])
+        } else {
+            command
+        }
+    }
+}
+
+impl ManagerState {
+    fn handle_message(&mut self, message: GUIMessage) -> Command<GUIMessage> {
+        match message {
+            GUIMessage::Tick
+            | GUIMessage::Event(_)
+            | GUIMessage::ClearClipboard(_)
+            | GUIMessage::CopyClipboard(..)
+            | GUIMessage::DismissToast
+            | GUIMessage::DismissQr
+            | GUIMessage::ClosePopup => {}
+            _ => self.last_interaction = Instant::now(),
+        }
+
         match message {
             GUIMessage::Event(event) => match event {
                 connection::Event::Connected(connection) => {
                     self.state = ConnectionState::Connected(connection);
+                    self.protocol_mismatch = None;
+                    // answered before anything queued so a mismatch is known first
+                    self.send_message(vec![ManagerMessage::Handshake(PROTOCOL_VERSION)]);
+                    let queued = std::mem::take(&mut self.queued);
+                    if !queued.is_empty() {
+                        self.notice = Some(format!("Sent {} waiting changes", queued.len()));
+                    }
+                    for (id, message) in queued {
+                        self.send_with_id(id, message);
+                    }
                     self.send_message(vec![ManagerMessage::Info]);
                 }
+                connection::Event::Connecting(retry) => {
+                    self.state = ConnectionState::Connecting(retry);
+                }
+                connection::Event::ConnectionFailed(e) => {
+                    self.error = Some(format!("Could not start the vault manager: {}", e));
+                }
                 connection::Event::Disconnected => {
                     self.state = ConnectionState::Disconnected;
                 }
-                connection::Event::ReceiveOutput(output) => match output {
-                    Output::Info(info) => {
-                        // println!("Received info: {:?}", info);
-                        self.update(info);
+                connection::Event::ReceiveOutput(id, output) => {
+                    // whatever was waiting on it was already given up on
+                    if self.timed_out.remove(&id) {
+                        return Command::none();
                     }
-                    Output::Read(value) => {
-                        // println!("Received read: {:?}", value);
-                        self.update_entry(value);
+                    self.awaiting.remove(&id);
+                    let unlocked = self
+                        .pending_unlock
+                        .as_ref()
+                        .is_some_and(|(pending, ..)| *pending == id);
+                    if self
+                        .pending_rename
+                        .as_ref()
+                        .is_some_and(|(pending, ..)| *pending == id)
+                    {
+                        if let Some((_, old, new)) = self.pending_rename.take() {
+                            if !matches!(output, Output::Error(_) | Output::LockedOut(_)) {
+                                self.rename_vault(&old, &new);
+                            }
+                        }
                     }
-                    Output::Nothing => {}
-                    _ => todo!(),
-                },
+                    if unlocked
+                        && !matches!(
+                            output,
+                            Output::Error(_) | Output::LockedOut(_) | Output::Nothing
+                        )
+                    {
+                        if let Some((_, vault, password)) = self.pending_unlock.take() {
+                            if self.config.remember_passwords
+                                && self.passwords.get(&vault).map(|p| p.expose_secret())
+                                    != Some(password.expose_secret())
+                            {
+                                self.remember(&vault, &password);
+                            }
+                            self.passwords.insert(vault, password);
+                            self.last_attempt = None;
+                            self.password_attempts = 0;
+                        }
+                    }
+                    match output {
+                        Output::Info(info) => {
+                            self.update(info);
+                        }
+                        Output::Read(value) if self.tray_copy == Some(id) => {
+                            self.tray_copy = None;
+                            let password = value
+                                .data
+                                .into_values()
+                                .next()
+                                .and_then(|store| store.as_hash().shift_remove("password"));
+                            if let Some(password) = password {
+                                return self.copy_to_clipboard(password);
+                            }
+                        }
+                        Output::Read(value) => {
+                            if self.fill_duplicate(id, &value) {
+                                return Command::none();
+                            }
+                            if self.update_entry(id, value) {
+                                return delayed_command(Duration::ZERO, |_| {
+                                    GUIMessage::GeneratePassword
+                                });
+                            }
+                        }
+                        Output::Backup(backup) => {
+                            self.notice = Some(format!("Previous vault backed up to {}", backup));
+                            return close_popup();
+                        }
+                        Output::Archive(path, vaults) => {
+                            self.notice = Some(format!(
+                                "Archived {} to {}",
+                                vaults.join(", "),
+                                path.display()
+                            ));
+                            return close_popup();
+                        }
+                        Output::Export(vault, data) => {
+                            return Command::perform(
+                                save_export(vault, data),
+                                GUIMessage::Exported,
+                            );
+                        }
+                        Output::Import(imported, skipped) => {
+                            let mut notice = format!("Imported {} entries", imported);
+                            if !skipped.is_empty() {
+                                notice.push_str(&format!(
+                                    ", skipped {} existing: {}",
+                                    skipped.len(),
+                                    skipped.join(", ")
+                                ));
+                            }
+                            self.notice = Some(notice);
+                            return close_popup();
+                        }
+                        Output::Regenerated(regenerated, skipped) => {
+                            let mut notice = format!("Regenerated {} passwords", regenerated);
+                            if !skipped.is_empty() {
+                                notice.push_str(&format!(
+                                    ", skipped {} without a password: {}",
+                                    skipped.len(),
+                                    skipped.join(", ")
+                                ));
+                            }
+                            self.notice = Some(notice);
+                            return close_popup();
+                        }
+                        Output::PasswordHash(vault, key, hash) => {
+                            return Command::perform(breach::check(hash), move |result| {
+                                GUIMessage::BreachChecked(vault, key, result)
+                            });
+                        }
+                        Output::RecoveryKey(key) => {
+                            return self.push_internal_state(RecoveryState::new(key));
+                        }
+                        Output::Reused(vault, groups) => {
+                            return self.push_internal_state(ReuseState::new(vault, groups));
+                        }
+                        Output::Weak(vault, entries) => {
+                            return self.push_internal_state(WeakState::new(vault, entries));
+                        }
+                        Output::Audit(events) => {
+                            return self.push_internal_state(AuditState::new(events));
+                        }
+                        Output::ProtocolMismatch(client, manager) => {
+                            let update = if client < manager {
+                                "this app"
+                            } else {
+                                "the vault manager"
+                            };
+                            let message = format!(
+                                "This app speaks protocol version {} but the vault manager speaks \
+                                 version {}, please update {} so they match",
+                                client, manager, update
+                            );
+                            self.error = Some(message.clone());
+                            self.protocol_mismatch = Some(message);
+                        }
+                        Output::Version(version) => {
+                            if let Some(InternalState::About(about_state)) = self.active_state_mut()
+                            {
+                                about_state.manager_version = Some(version);
+                            }
+                        }
+                        Output::Error(e) => {
+                            self.pending_unlock = None;
+                            if e == DecryptionError::InvalidPassword.to_string() {
+                                if let Some(attempt) = self.last_attempt.take() {
+                                    return self.retry_password(attempt);
+                                }
+                            }
+                            self.error = Some(e);
+                        }
+                        // the vault isn't taking passwords for a while, so say so where it was
+                        // asked for
+                        Output::LockedOut(seconds) => {
+                            let e = ManagerError::LockedOut(seconds).to_string();
+                            self.pending_unlock = None;
+                            self.internal_state = vec![];
+                            self.temp_message = TempMessage::default();
+                            match self.last_attempt.take() {
+                                Some(attempt) => {
+                                    self.temp_message = attempt;
+                                    return self.push_internal_state(
+                                        PasswordState::default().with_error(e),
+                                    );
+                                }
+                                None => self.error = Some(e),
+                            }
+                        }
+                        // never requested by the gui
+                        Output::Schema(_) | Output::List(_) | Output::BackupFiles(_) => {}
+                        Output::Nothing => {}
+                    }
+                }
 
-                connection::Event::ReceiveError(e) => {
+                connection::Event::ReceiveError(id, e) => {
+                    if self.timed_out.remove(&id) {
+                        return Command::none();
+                    }
+                    self.awaiting.remove(&id);
+                    self.pending_unlock = None;
+                    self.pending_rename = None;
                     self.internal_state = vec![];
                     self.temp_message = TempMessage::default();
-                    self.notice = Some(format!("Encountered an error: {}", e));
-                    return close_popup();
+                    // after a mismatch this is likely a misread, so keep pointing at the cause
+                    self.error = match &self.protocol_mismatch {
+                        Some(mismatch) => Some(mismatch.clone()),
+                        None => Some(format!("Encountered an error: {}", e)),
+                    };
                 }
             },
             GUIMessage::ClosePopup => {
                 self.notice = None;
             }
+            GUIMessage::DismissError => {
+                self.error = None;
+            }
+            GUIMessage::SearchChanged(search) => {
+                self.search = search;
+            }
+            GUIMessage::FilterTag(tag) => {
+                self.tag_filter = if self.tag_filter.as_ref() == Some(&tag) {
+                    None
+                } else {
+                    Some(tag)
+                };
+            }
+            GUIMessage::TagsChanged(tags) => match self.active_state_mut() {
+                Some(InternalState::New(new_state)) => {
+                    new_state.tags = tags;
+                    new_state.edited = true;
+                }
+                Some(InternalState::Entry(entry_state)) => {
+                    entry_state.tags = tags;
+                    entry_state.edited = true;
+                }
+                _ => {}
+            },
             // GUIMessage::Send(message) => self.send_message(vec![message]),
             GUIMessage::VaultMessage(message, vault) => match message {
                 VaultMessage::Entry(entry_message, key) => match entry_message {
                     EntryMessage::Delete => {
                         self.temp_message = TempMessage::Delete(vault, key);
                         if self.needs_password() {
-                            return self.push_internal_state(PasswordState::default());
+                            return self.ask_password(PasswordState::default());
+                        }
+                    }
+                    EntryMessage::Rename => {
+                        return self.push_internal_state(RenameState::new(vault, key));
+                    }
+                    EntryMessage::Duplicate => {
+                        self.temp_message = TempMessage::Duplicate(vault, key);
+                        return self.ask_password(PasswordState::default());
+                    }
+                    // the vault already turns these into its own messages
+                    EntryMessage::ToggleFavorite
+                    | EntryMessage::MoveUp
+                    | EntryMessage::MoveDown => {}
+                    EntryMessage::Select(selected) => {
+                        if let Some(selection) = self.selection.get_mut(&vault) {
+                            if selected {
+                                selection.insert(key);
+                            } else {
+                                selection.remove(&key);
+                            }
                         }
                     }
-                    EntryMessage::View => {
+                    EntryMessage::View | EntryMessage::Rotate => {
+                        self.pending_rotation = matches!(entry_message, EntryMessage::Rotate);
                         self.temp_message = TempMessage::Get(vault, key.clone());
 
                         if self.needs_password() {
-                            return self.push_internal_state(PasswordState::default());
+                            return self.ask_password(PasswordState::default());
                         }
                     }
                 },
                 VaultMessage::NewEntry => {
+                    let choice = StoreChoice::from(self.config.default_store_type);
                     self.temp_message = TempMessage::New(
                         vault.to_string(),
                         String::new(),
-                        StoreChoice::default(),
-                        StoreChoice::default().convert_default().as_hash(),
+                        choice,
+                        choice.convert_default().as_hash(),
+                        vec![],
                     );
-                    let command = self.push_internal_state(NewEntryState::for_vault(vault));
-                    let gen_password = delayed_command(0, |_| GUIMessage::GeneratePassword);
+                    let command = self.push_internal_state(
+                        NewEntryState::for_vault(vault)
+                            .choice(choice)
+                            .generator(
+                                self.custom_generator,
+                                self.generator.clone(),
+                                self.pronounceable,
+                            )
+                            .presets(self.config.presets.keys().cloned().collect()),
+                    );
+                    // the configured type might not have a password to fill in
+                    if !choice.convert_default().as_hash().contains_key("password") {
+                        return command;
+                    }
+                    let gen_password =
+                        delayed_command(Duration::ZERO, |_| GUIMessage::GeneratePassword);
                     return Command::batch(vec![command, gen_password]);
                 }
-                VaultMessage::Delete => {
-                    if self.info.get(&vault).unwrap().is_empty() {
+                VaultMessage::ToggleFavorite(key) => {
+                    let entry = self
+                        .vaults
+                        .get_mut(&vault)
+                        .and_then(|v| v.entries.get_mut(&key));
+                    if let Some(entry) = entry {
+                        entry.favorite = !entry.favorite;
+                        let favorite = entry.favorite;
                         self.send_message(vec![
-                            ManagerMessage::DeleteEmptyVault(vault),
+                            ManagerMessage::SetFavorite {
+                                vault,
+                                key,
+                                favorite,
+                            },
                             ManagerMessage::Info,
                         ]);
-                    } else {
-                        self.temp_message = TempMessage::DeleteVault(vault);
-                    }
-                    if self.needs_password() {
-                        return self.push_internal_state(PasswordState::default());
                     }
                 }
-                VaultMessage::Toggle => {
+                VaultMessage::Move(key, direction) => {
                     if let Some(value) = self.vaults.get_mut(&vault) {
-                        value.toggle();
+                        let keys = value.moved(&key, direction);
+                        // reorder locally so the move shows up before the info comes back
+                        for (i, key) in keys.iter().enumerate() {
+                            if let Some(entry) = value.entries.get_mut(key) {
+                                entry.order = Some(i);
+                            }
+                        }
+                        self.send_message(vec![
+                            ManagerMessage::SetOrder { vault, keys },
+                            ManagerMessage::Info,
+                        ]);
                     }
                 }
-            },
-
-            GUIMessage::PasswordChanged(p) => {
-                if let Some(InternalState::Password(password_state)) = self.active_state_mut() {
-                    password_state.password = p;
+                VaultMessage::ToggleSelect => {
+                    if self.selection.remove(&vault).is_none() {
+                        self.selection.insert(vault, BTreeSet::new());
+                    }
                 }
-            }
-            GUIMessage::PasswordConfirmChanged(p) => {
-                if let Some(InternalState::Password(password_state)) = self.active_state_mut() {
-                    password_state.confirm = Some(p);
+                VaultMessage::DeleteSelected => {
+                    if let Some(selection) = self.selection.get(&vault) {
+                        let keys = selection.iter().cloned().collect();
+                        return self
+                            .push_internal_state(ConfirmDeleteEntriesState::new(vault, keys));
+                    }
+                }
+                VaultMessage::RegenerateSelected => {
+                    if let Some(selection) = self.selection.get(&vault) {
+                        let keys = selection.iter().cloned().collect();
+                        return self.push_internal_state(ConfirmRegenerateState::new(vault, keys));
+                    }
+                }
+                VaultMessage::Rename => {
+                    return self.push_internal_state(RenameVaultState::new(vault));
+                }
+                VaultMessage::Delete => {
+                    if self.info.get(&vault).unwrap().is_empty() {
+                        self.send_message(vec![
+                            ManagerMessage::DeleteEmptyVault(vault),
+                            ManagerMessage::Info,
+                        ]);
+                    } else {
+                        return self.push_internal_state(ConfirmDeleteState::new(vault));
+                    }
+                }
+                VaultMessage::Lock => {
+                    self.passwords.remove(&vault);
+                }
+                VaultMessage::Toggle => {
+                    if let Some(value) = self.vaults.get_mut(&vault) {
+                        value.toggle();
+                    }
+                    return self.save_expanded();
+                }
+            },
+
+            GUIMessage::PasswordChanged(p) => match self.active_state_mut() {
+                Some(InternalState::Password(password_state)) => password_state.password = p,
+                Some(InternalState::ChangePassword(change_state)) => change_state.new = p,
+                _ => {}
+            },
+            GUIMessage::PasswordConfirmChanged(p) => match self.active_state_mut() {
+                Some(InternalState::Password(password_state)) => password_state.confirm = Some(p),
+                Some(InternalState::ChangePassword(change_state)) => change_state.confirm = p,
+                _ => {}
+            },
+            GUIMessage::OldPasswordChanged(p) => {
+                if let Some(InternalState::ChangePassword(change_state)) = self.active_state_mut() {
+                    change_state.old = p;
                 }
             }
-            GUIMessage::PromptChanged(p) => {
-                if let Some(InternalState::Prompt(prompt_state)) = self.active_state_mut() {
+            GUIMessage::PromptChanged(p) => match self.active_state_mut() {
+                Some(InternalState::Prompt(prompt_state)) => {
                     prompt_state.vault = p;
+                    prompt_state.error = None;
                 }
-            }
+                Some(InternalState::Rename(rename_state)) => rename_state.name = p,
+                Some(InternalState::RenameVault(rename_state)) => {
+                    rename_state.name = p;
+                    rename_state.error = None;
+                }
+                Some(InternalState::ConfirmDelete(confirm_state)) => confirm_state.typed = p,
+                Some(InternalState::Accent(accent_state)) => accent_state.color = p,
+                _ => {}
+            },
 
             GUIMessage::ChangeName(n) => {
+                let exists = self.name_taken(&n);
                 if let Some(InternalState::New(new_state)) = self.active_state_mut() {
                     new_state.name.clone_from(&n);
+                    new_state.edited = true;
+                    new_state.name_error = None;
+                    new_state.exists = exists;
+                    new_state.focus = 0;
                 }
-                if let TempMessage::New(_, ref mut key, _, _) = &mut self.temp_message {
+                if let TempMessage::New(_, ref mut key, ..) = &mut self.temp_message {
                     *key = n;
                 }
             }
@@ -484,54 +2228,191 @@ impl Application for ManagerState {
                 if let Some(InternalState::New(new_state)) = self.active_state_mut() {
                     new_state.choice = choice;
                     new_state.value = choice.convert_default().as_hash();
+                    new_state.note = text_editor::Content::new();
+                    new_state.error = None;
                 }
-                if let TempMessage::New(_, _, ref mut style, ref mut value) = &mut self.temp_message
+                if let TempMessage::New(_, _, ref mut style, ref mut value, _) =
+                    &mut self.temp_message
                 {
                     *style = choice;
                     *value = choice.convert_default().as_hash();
                 }
             }
-            GUIMessage::UpdateField(k, v) => {
-                match self.active_state_mut() {
+            GUIMessage::UpdateField(k, v) => self.update_field(k, v),
+            GUIMessage::AddCustomField => {
+                self.edit_custom_fields(|custom| custom.push((String::new(), String::new().into())))
+            }
+            GUIMessage::UpdateCustomField(i, name, value) => self.edit_custom_fields(|custom| {
+                if let Some(field) = custom.get_mut(i) {
+                    *field = (name.clone(), value.clone());
+                }
+            }),
+            GUIMessage::RemoveCustomField(i) => self.edit_custom_fields(|custom| {
+                if i < custom.len() {
+                    custom.remove(i);
+                }
+            }),
+            GUIMessage::EditNote(action) => {
+                // clicking or selecting in the editor shouldn't count as a change
+                let edit = action.is_edit();
+                let note = match self.active_state_mut() {
                     Some(InternalState::New(new_state)) => {
-                        new_state.value.insert(k.clone(), v.clone());
+                        new_state.note.perform(action);
+                        new_state
+                            .choice
+                            .multiline_field()
+                            .map(|field| (field, new_state.note.text()))
                     }
                     Some(InternalState::Entry(entry_state)) => {
-                        entry_state.value.insert(k.clone(), v.clone());
+                        entry_state.note.perform(action);
+                        entry_state
+                            .choice
+                            .multiline_field()
+                            .map(|field| (field, entry_state.note.text()))
                     }
-                    _ => {}
+                    _ => None,
                 };
-                match &mut self.temp_message {
-                    TempMessage::New(_, _, _, ref mut value) => {
-                        value.insert(k, v);
+                if let Some((field, note)) = note.filter(|_| edit) {
+                    // the editor always reports a trailing newline
+                    let note = note.strip_suffix('\n').unwrap_or(&note).to_string();
+                    self.update_field(field.to_string(), note.into());
+                }
+            }
+            GUIMessage::PastePassword => {
+                return iced::clipboard::read(|s| GUIMessage::PastedPassword(s.map(Password::new)));
+            }
+            // an empty clipboard or one without text leaves the field as it was
+            GUIMessage::PastedPassword(password) => {
+                if let Some(password) = password.filter(|p| !p.expose_secret().is_empty()) {
+                    if matches!(self.active_state(), Some(InternalState::New(_))) {
+                        self.update_field("password".to_string(), password);
                     }
-                    TempMessage::Update(_, _, _, ref mut value) => {
-                        value.insert(k, v);
+                }
+            }
+            // a uri that parses fills in the fields, the name only when there isn't one yet
+            GUIMessage::TotpUriChanged(uri) => {
+                let parsed = Totp::from_uri(&uri);
+                if let Ok(totp) = &parsed {
+                    let (_, fields) = Store::Totp(totp.clone()).split();
+                    for (field, value) in fields {
+                        self.update_field(field, value);
                     }
-                    _ => {}
+                }
+                let label = Totp::label_from_uri(&uri).filter(|_| parsed.is_ok());
+                let exists = label.as_ref().is_some_and(|label| self.name_taken(label));
+                let mut name = None;
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.error = match &parsed {
+                        Err(e) if !uri.trim().is_empty() => Some(e.to_string()),
+                        _ => None,
+                    };
+                    new_state.totp_uri = uri;
+                    if let Some(label) = label.filter(|_| new_state.name.trim().is_empty()) {
+                        new_state.name.clone_from(&label);
+                        new_state.exists = exists;
+                        new_state.name_error = None;
+                        name = Some(label);
+                    }
+                }
+                if let (Some(name), TempMessage::New(_, ref mut key, ..)) =
+                    (name, &mut self.temp_message)
+                {
+                    *key = name;
+                }
+            }
+            // same as generating it, so the strength is still known
+            GUIMessage::PickCandidate(index) => {
+                let Some(InternalState::New(new_state)) = self.active_state_mut() else {
+                    return Command::none();
+                };
+                let Some(password) = new_state.candidates.get(index).cloned() else {
+                    return Command::none();
                 };
+                new_state
+                    .value
+                    .insert("password".to_string(), password.clone());
+                new_state.generated_bits = new_state.candidate_bits;
+                if let TempMessage::New(_, _, _, ref mut value, _) = &mut self.temp_message {
+                    value.insert("password".to_string(), password);
+                }
+            }
+            GUIMessage::CopyCandidate(index) => {
+                if let Some(InternalState::New(new_state)) = self.active_state() {
+                    if let Some(password) = new_state.candidates.get(index).cloned() {
+                        return self.copy_to_clipboard(password);
+                    }
+                }
             }
             GUIMessage::GeneratePassword => {
-                let spec = PasswordSpec::from_str(&self.config.password_spec).unwrap();
-                let password: Secret<String> = spec.generate().unwrap().into();
+                // the shortcut can fire from anywhere, only entries being edited have a password
+                if !matches!(
+                    self.active_state(),
+                    Some(InternalState::New(_)) | Some(InternalState::Entry(_))
+                ) {
+                    return Command::none();
+                }
+                // custom settings take precedence, then the vault's own spec, then the client's
+                let custom = match self.active_state() {
+                    Some(InternalState::New(new_state)) => new_state.custom_spec(),
+                    _ => None,
+                };
+                let vault_spec = self
+                    .temp_message
+                    .vault()
+                    .and_then(|vault| self.info.spec(vault));
+                let spec = match (custom, vault_spec) {
+                    (Some(spec), _) => Ok(spec),
+                    (None, Some(spec)) => GenSpec::from_str(spec),
+                    // the client config is edited by hand, say where the bad spec came from
+                    (None, None) => match GenSpec::from_str(&self.config.password_spec) {
+                        Ok(spec) => Ok(spec),
+                        Err(e) => {
+                            self.notice = Some(format!("Invalid password spec in config: {}", e));
+                            return close_popup();
+                        }
+                    },
+                };
+                // only a new entry shows the others to pick from
+                let count = match self.active_state() {
+                    Some(InternalState::New(_)) => self.config.password_candidates(),
+                    _ => 1,
+                };
+                let generated = spec.and_then(|spec| {
+                    let passwords = (0..count)
+                        .map(|_| spec.generate().map(Password::from))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok((passwords, spec.entropy()))
+                });
+                let (candidates, bits) = match generated {
+                    Ok(generated) => generated,
+                    Err(e) => {
+                        self.notice = Some(e.to_string());
+                        return close_popup();
+                    }
+                };
+                let password = candidates[0].clone();
                 match self.active_state_mut() {
                     Some(InternalState::New(new_state)) => {
                         new_state
                             .value
                             .insert("password".to_string(), password.clone());
+                        new_state.generated_bits = bits;
+                        new_state.candidates = candidates;
+                        new_state.candidate_bits = bits;
                     }
                     Some(InternalState::Entry(entry_state)) => {
                         entry_state
                             .value
                             .insert("password".to_string(), password.clone());
+                        entry_state.edited = true;
                     }
                     _ => {}
                 };
                 match &mut self.temp_message {
-                    TempMessage::New(_, _, _, ref mut value) => {
+                    TempMessage::New(_, _, _, ref mut value, _) => {
                         value.insert("password".to_string(), password);
                     }
-                    TempMessage::Update(_, _, _, ref mut value) => {
+                    TempMessage::Update(_, _, _, ref mut value, _) => {
                         value.insert("password".to_string(), password);
                     }
                     _ => {}
@@ -551,13 +2432,42 @@ impl Application for ManagerState {
                             }
                         }
                         InternalState::New(new_state) => {
-                            if let Some(schema) = self.info.get(&new_state.vault) {
+                            let vault = new_state.vault.clone();
+                            let name = new_state.name.clone();
+                            if let Some(schema) = self.info.get(&vault) {
                                 // println!("{:?}", schema);
+                                let empty = schema.is_empty();
+                                // an existing name was already flagged in the view, so this
+                                // overwrites it
+                                let key = match utils::entry_key(&name) {
+                                    Ok(key) => key,
+                                    Err(e) => {
+                                        if let Some(InternalState::New(new_state)) =
+                                            self.active_state_mut()
+                                        {
+                                            new_state.name_error = Some(e.to_string());
+                                        }
+                                        return Command::none();
+                                    }
+                                };
+                                if let TempMessage::New(_, ref mut name, ..) =
+                                    &mut self.temp_message
+                                {
+                                    *name = key;
+                                }
                                 if self.temp_message.complete() {
-                                    if schema.is_empty() {
-                                        return self.push_internal_state(PasswordState::confirm());
-                                    } else if !schema.data.contains_key(&new_state.name) {
-                                        return self.push_internal_state(PasswordState::default());
+                                    if let Err(e) = self.temp_message.valid() {
+                                        if let Some(InternalState::New(new_state)) =
+                                            self.active_state_mut()
+                                        {
+                                            new_state.error = Some(e.to_string());
+                                        }
+                                        return Command::none();
+                                    }
+                                    if empty {
+                                        return self.ask_password(PasswordState::confirm());
+                                    } else {
+                                        return self.ask_password(PasswordState::default());
                                     }
                                 } else {
                                     self.notice = Some("Fill all fields before submitting".into());
@@ -570,14 +2480,15 @@ impl Application for ManagerState {
                                 if schema.data.contains_key(&entry_state.key)
                                     && self.temp_message.complete()
                                 {
-                                    if let Some(password) = self.get_password() {
-                                        let message = self.temp_message.with_password(password);
-                                        self.send_message(vec![message]);
-                                        self.temp_message = TempMessage::default();
-                                        self.internal_state = vec![];
-                                    } else {
-                                        return self.push_internal_state(PasswordState::default());
+                                    if let Err(e) = self.temp_message.valid() {
+                                        if let Some(InternalState::Entry(entry_state)) =
+                                            self.active_state_mut()
+                                        {
+                                            entry_state.error = Some(e.to_string());
+                                        }
+                                        return Command::none();
                                     }
+                                    return self.ask_password(PasswordState::default());
                                 } else {
                                     self.notice = Some("Fill all fields before submitting".into());
                                     return close_popup();
@@ -595,21 +2506,201 @@ impl Application for ManagerState {
                         //     }
                         // }
                         InternalState::Prompt(prompt_state) => {
-                            if !self.info.data.contains_key(&prompt_state.vault)
-                                && !prompt_state.vault.is_empty()
-                            {
-                                let message = ManagerMessage::NewVault(prompt_state.vault.clone());
-                                self.send_message(vec![message, ManagerMessage::Info]);
+                            let name = utils::vault_name(&prompt_state.vault)
+                                .map_err(|e| e.to_string())
+                                .and_then(|name| {
+                                    if self.info.data.contains_key(&name) {
+                                        Err("This vault already exists".to_string())
+                                    } else {
+                                        Ok(name)
+                                    }
+                                });
+                            match name {
+                                Ok(name) => {
+                                    let recovery = prompt_state
+                                        .recovery
+                                        .then(|| TempMessage::CreateRecovery(name.clone()));
+                                    let message = ManagerMessage::NewVault(name);
+                                    self.send_message(vec![message, ManagerMessage::Info]);
+                                    self.internal_state.pop();
+                                    // the vault's password is set along with the recovery key
+                                    if let Some(temp_message) = recovery {
+                                        self.temp_message = temp_message;
+                                        return self.push_internal_state(PasswordState::confirm());
+                                    }
+                                }
+                                Err(e) => {
+                                    if let Some(InternalState::Prompt(prompt_state)) =
+                                        self.active_state_mut()
+                                    {
+                                        prompt_state.error = Some(e);
+                                    }
+                                }
+                            }
+                        }
+                        InternalState::Rename(rename_state) => {
+                            let name = match utils::entry_key(&rename_state.name) {
+                                Ok(name) => name,
+                                Err(e) => {
+                                    self.notice = Some(e.to_string());
+                                    return close_popup();
+                                }
+                            };
+                            let exists = self
+                                .info
+                                .get(&rename_state.vault)
+                                .map(|schema| schema.data.contains_key(&name))
+                                .unwrap_or(false);
+                            if name == rename_state.key {
                                 self.internal_state.pop();
+                            } else if exists {
+                                self.notice = Some("An entry with that name already exists".into());
+                                return close_popup();
                             } else {
-                                if self.info.data.contains_key(&prompt_state.vault) {
-                                    self.notice = Some("This vault already exists".into());
-                                    return close_popup();
+                                self.temp_message = TempMessage::Rename(
+                                    rename_state.vault.clone(),
+                                    rename_state.key.clone(),
+                                    name,
+                                );
+                                return self.ask_password(PasswordState::default());
+                            }
+                        }
+                        InternalState::RenameVault(rename_state) => {
+                            let name = utils::vault_name(&rename_state.name)
+                                .map_err(|e| e.to_string())
+                                .and_then(|name| {
+                                    if name != rename_state.vault
+                                        && self.info.data.contains_key(&name)
+                                    {
+                                        Err("This vault already exists".to_string())
+                                    } else {
+                                        Ok(name)
+                                    }
+                                });
+                            match name {
+                                Ok(name) if name == rename_state.vault => {
+                                    self.internal_state.pop();
                                 }
-                                if prompt_state.vault.is_empty() {
-                                    self.notice = Some("Need a name to create vault".into());
-                                    return close_popup();
+                                Ok(new) => {
+                                    let old = rename_state.vault.clone();
+                                    let id = self.request_id();
+                                    self.pending_rename = Some((id, old.clone(), new.clone()));
+                                    self.send_with_id(id, ManagerMessage::RenameVault { old, new });
+                                    self.send_message(vec![ManagerMessage::Info]);
+                                    self.internal_state.pop();
+                                }
+                                Err(e) => {
+                                    if let Some(InternalState::RenameVault(rename_state)) =
+                                        self.active_state_mut()
+                                    {
+                                        rename_state.error = Some(e);
+                                    }
+                                }
+                            }
+                        }
+                        InternalState::ConfirmDelete(confirm_state) => {
+                            if confirm_state.confirmed() {
+                                self.temp_message =
+                                    TempMessage::DeleteVault(confirm_state.vault.clone());
+                                return self.ask_password(PasswordState::default());
+                            } else {
+                                self.notice = Some("Vault name does not match".into());
+                                return close_popup();
+                            }
+                        }
+                        InternalState::Accent(accent_state) => {
+                            if utils::hex_color(&accent_state.color).is_none() {
+                                self.notice = Some("Not a valid hex color".into());
+                                return close_popup();
+                            }
+                            self.config.accent_color = Some(accent_state.color.trim().to_string());
+                            self.config.theme = CUSTOM_THEME.to_string();
+                            self.internal_state.pop();
+                            if self.config.save().is_err() {
+                                self.notice = Some("Failed to save config file".into());
+                                return close_popup();
+                            }
+                        }
+                        InternalState::Recovery(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Reuse(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Weak(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Audit(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Qr(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::About(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Menu(menu_state) => {
+                            let message = menu_state.message(menu_state.selected);
+                            self.internal_state.pop();
+                            if let Some(message) = message {
+                                return delayed_command(Duration::ZERO, move |_| message);
+                            }
+                        }
+                        InternalState::ConfirmDeleteEntries(confirm_state) => {
+                            self.temp_message = TempMessage::DeleteMany(
+                                confirm_state.vault.clone(),
+                                confirm_state.keys.clone(),
+                            );
+                            self.internal_state.pop();
+                            return self.ask_password(PasswordState::default());
+                        }
+                        InternalState::ConfirmDiscard(confirm_state) => {
+                            if confirm_state.quit {
+                                return self.quit();
+                            }
+                            self.temp_message = TempMessage::default();
+                            self.internal_state = vec![];
+                        }
+                        // always asks for the password since the old passwords are lost
+                        InternalState::ConfirmRegenerate(confirm_state) => {
+                            let spec = self
+                                .info
+                                .spec(&confirm_state.vault)
+                                .unwrap_or(&self.config.password_spec)
+                                .to_string();
+                            self.temp_message = TempMessage::Regenerate(
+                                confirm_state.vault.clone(),
+                                confirm_state.keys.clone(),
+                                spec,
+                            );
+                            self.internal_state.pop();
+                            return self.push_internal_state(PasswordState::default());
+                        }
+                        InternalState::ChangePassword(change_state) => {
+                            if change_state.valid() {
+                                // the old password stops working
+                                self.passwords.remove(&change_state.vault);
+                                if self.config.remember_passwords {
+                                    let _ = keychain::forget(&change_state.vault);
                                 }
+                                let message = if change_state.recovery {
+                                    ManagerMessage::Recover {
+                                        vault: change_state.vault.clone(),
+                                        recovery: change_state.old.clone(),
+                                        new: change_state.new.clone(),
+                                    }
+                                } else {
+                                    ManagerMessage::ChangePassword {
+                                        vault: change_state.vault.clone(),
+                                        old: change_state.old.clone(),
+                                        new: change_state.new.clone(),
+                                    }
+                                };
+                                self.send_message(vec![message, ManagerMessage::Info]);
+                                self.internal_state.pop();
+                            } else {
+                                self.notice = Some("New passwords do not match".into());
+                                return close_popup();
                             }
                         }
                     }
@@ -623,7 +2714,13 @@ impl Application for ManagerState {
                                 TempMessage::Delete(..) => {
                                     self.temp_message = TempMessage::default();
                                 }
-                                TempMessage::Get(..) => {
+                                TempMessage::DeleteMany(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::Regenerate(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::Get(..) | TempMessage::Duplicate(..) => {
                                     self.temp_message = TempMessage::default();
                                 }
                                 TempMessage::DeleteVault(..) => {
@@ -632,23 +2729,98 @@ impl Application for ManagerState {
                                 TempMessage::DeleteEmptyVault(..) => {
                                     self.temp_message = TempMessage::default();
                                 }
+                                TempMessage::Rename(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::Export(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::Import(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::ImportVaults(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::BackupImport(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::CreateRecovery(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::CheckReuse(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
+                                TempMessage::CheckStrength(..) => {
+                                    self.temp_message = TempMessage::default();
+                                }
                                 TempMessage::Update(..) => {}
                                 TempMessage::New(..) => {}
                                 TempMessage::Empty => {}
                             }
                             self.internal_state.pop();
                         }
-                        InternalState::Entry(_entry_state) => {
+                        InternalState::Entry(entry_state) => {
+                            if entry_state.edited {
+                                return self.push_internal_state(ConfirmDiscardState::new(false));
+                            }
                             self.temp_message = TempMessage::default();
                             self.internal_state = vec![];
                         }
-                        InternalState::New(_new_state) => {
+                        InternalState::New(new_state) => {
+                            if new_state.edited {
+                                return self.push_internal_state(ConfirmDiscardState::new(false));
+                            }
                             self.temp_message = TempMessage::default();
                             self.internal_state = vec![];
                         }
                         InternalState::Prompt(_) => {
                             self.internal_state.pop();
                         }
+                        InternalState::ChangePassword(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Rename(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::RenameVault(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::ConfirmDelete(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::ConfirmDeleteEntries(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::ConfirmRegenerate(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::ConfirmDiscard(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Accent(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Recovery(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Reuse(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Weak(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Audit(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Qr(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::About(_) => {
+                            self.internal_state.pop();
+                        }
+                        InternalState::Menu(_) => {
+                            self.internal_state.pop();
+                        }
                     }
                 }
             }
@@ -671,115 +2843,580 @@ impl Application for ManagerState {
                     }
                 }
             }
-            GUIMessage::CopyPassword => {
-                if let Some(InternalState::Entry(entry_state)) = self.active_state_mut() {
+            GUIMessage::CopyPassword => match self.active_state() {
+                Some(InternalState::Entry(entry_state)) => {
                     if let Some(p) = entry_state.get_password() {
-                        return Command::batch(vec![
-                            iced::clipboard::read(|s| {
-                                GUIMessage::CopyClipboard(s.map(|x| x.into()))
-                            }),
-                            iced::clipboard::write(p.expose_secret().into()),
-                            delayed_command(self.config.clipboard_time, |_| {
-                                GUIMessage::ClearClipboard
-                            }),
-                        ]);
+                        return self.copy_to_clipboard(p);
+                    }
+                }
+                Some(InternalState::Recovery(recovery_state)) => {
+                    return self.copy_to_clipboard(recovery_state.key.clone());
+                }
+                // only copied, nothing is sent to the vault until the entry is created
+                Some(InternalState::New(new_state)) => {
+                    if let Some(p) = new_state.value.get("password") {
+                        return self.copy_to_clipboard(p.clone());
+                    }
+                }
+                _ => {}
+            },
+            GUIMessage::CopyField(key) => {
+                if let Some(InternalState::Entry(entry_state)) = self.active_state() {
+                    if let Some(value) = entry_state.get_field(&key) {
+                        return self.copy_to_clipboard(value);
                     }
                 }
             }
-            GUIMessage::CopyClipboard(data) => self.stored_clipboard = data,
-            GUIMessage::ClearClipboard => {
-                let contents: Secret<String> = self
-                    .stored_clipboard
-                    .clone()
-                    .unwrap_or_else(|| Secret::new(String::new()));
-                self.stored_clipboard = None;
-                return iced::clipboard::write(contents.expose_secret().into());
+            GUIMessage::CopyEntry => {
+                if let Some(InternalState::Entry(entry_state)) = self.active_state() {
+                    // the copy includes the password, so only while the vault is still unlocked
+                    if self.get_password(&entry_state.vault).is_none() {
+                        self.notice = Some("Unlock the vault again to copy the entry".into());
+                        return close_popup();
+                    }
+                    let formatted = entry_state.formatted(self.config.copy_entry_password);
+                    return self.copy_to_clipboard(formatted);
+                }
+            }
+            GUIMessage::SetGenerator(custom, spec) => {
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.custom_generator = custom;
+                    new_state.generator = spec.clone();
+                    new_state.preset = None;
+                    new_state.preset_spec = None;
+                }
+                self.custom_generator = custom;
+                self.generator = spec;
+            }
+            GUIMessage::SetPronounceable(pronounceable) => {
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.pronounceable = pronounceable;
+                    new_state.preset = None;
+                    new_state.preset_spec = None;
+                }
+                self.pronounceable = pronounceable;
+            }
+            GUIMessage::SelectPreset(name) => {
+                let Some(spec) = self.config.presets.get(&name) else {
+                    return Command::none();
+                };
+                let spec = match GenSpec::from_str(spec) {
+                    Ok(spec) => spec,
+                    Err(e) => {
+                        self.notice = Some(format!("Invalid spec for preset {}: {}", name, e));
+                        return close_popup();
+                    }
+                };
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.select_preset(name, spec);
+                    let (generator, pronounceable) =
+                        (new_state.generator.clone(), new_state.pronounceable);
+                    self.custom_generator = true;
+                    self.generator = generator;
+                    self.pronounceable = pronounceable;
+                }
+            }
+            GUIMessage::PresetNameChanged(name) => {
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.preset_name = name;
+                }
+            }
+            GUIMessage::SavePreset => {
+                let Some(InternalState::New(new_state)) = self.active_state() else {
+                    return Command::none();
+                };
+                let name = new_state.preset_name.trim().to_string();
+                let Some(spec) = new_state.custom_spec() else {
+                    return Command::none();
+                };
+                self.config.presets.insert(name.clone(), spec.to_string());
+                let names = self.config.presets.keys().cloned().collect();
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.presets = combo_box::State::new(names);
+                    new_state.preset = Some(name);
+                }
+                if self.config.save().is_err() {
+                    self.notice = Some("Could not save the preset".into());
+                    return close_popup();
+                }
+            }
+            GUIMessage::RemovePreset(name) => {
+                self.config.presets.remove(&name);
+                let names = self.config.presets.keys().cloned().collect();
+                if let Some(InternalState::New(new_state)) = self.active_state_mut() {
+                    new_state.presets = combo_box::State::new(names);
+                    new_state.preset = None;
+                }
+                if self.config.save().is_err() {
+                    self.notice = Some("Could not save the presets".into());
+                    return close_popup();
+                }
+            }
+            GUIMessage::OpenUrl(url) => {
+                // the url comes straight from the entry, no need to go back to the vault
+                let Some(url) = utils::web_url(&url) else {
+                    return Command::none();
+                };
+                if let Err(e) = open::that_detached(url.as_str()) {
+                    self.notice = Some(format!("Could not open {}: {}", url, e));
+                    return close_popup();
+                }
+            }
+            GUIMessage::CheckBreach => {
+                if let Some(InternalState::Entry(entry_state)) = self.active_state() {
+                    let Some(password) = self.get_password(&entry_state.vault) else {
+                        return Command::none();
+                    };
+                    let message = ManagerMessage::CheckBreach {
+                        vault: entry_state.vault.clone(),
+                        key: entry_state.key.clone(),
+                        password,
+                    };
+                    self.send_message(vec![message]);
+                }
+            }
+            GUIMessage::BreachChecked(vault, key, result) => {
+                if let Some(InternalState::Entry(entry_state)) = self.active_state_mut() {
+                    if entry_state.vault == vault && entry_state.key == key {
+                        entry_state.breaches = Some(result);
+                    }
+                }
+            }
+            GUIMessage::CopyTotp => {
+                if let Some(InternalState::Entry(entry_state)) = self.active_state() {
+                    if let Some(totp) = entry_state.get_totp() {
+                        return self.copy_to_clipboard(totp.code().into());
+                    }
+                }
+            }
+            GUIMessage::CopyTotpUri => {
+                let Some(InternalState::Entry(entry_state)) = self.active_state() else {
+                    return Command::none();
+                };
+                let unlocked = self.get_password(&entry_state.vault).is_some();
+                let uri = entry_state
+                    .get_totp()
+                    .map(|totp| Password::from(totp.uri(&entry_state.key)));
+                // the secret is as sensitive as a password, so only while the vault is unlocked
+                if !unlocked {
+                    self.notice = Some("Unlock the vault again to copy the URI".into());
+                    return close_popup();
+                }
+                match uri {
+                    Some(uri) => return self.copy_to_clipboard(uri),
+                    None => {
+                        self.notice = Some("The secret isn't valid, so there's no URI".into());
+                        return close_popup();
+                    }
+                }
+            }
+            GUIMessage::CopyClipboard(previous, value) => {
+                // when a copy replaces one that wasn't cleared yet, the clipboard holds that copy
+                // and what was there before it is already stored
+                if self.clipboard_clears_at.is_none() {
+                    self.stored_clipboard = previous;
+                }
+                self.clipboard_clears_at =
+                    Some(Instant::now() + Duration::from_secs(self.config.clipboard_time));
+                self.clipboard_generation += 1;
+                let generation = self.clipboard_generation;
+                self.toast = Some(("Copied!".to_string(), Instant::now() + TOAST_TIME));
+                return Command::batch(vec![
+                    self.write_clipboard(&value),
+                    delayed_command(Duration::from_secs(self.config.clipboard_time), move |_| {
+                        GUIMessage::ClearClipboard(generation)
+                    }),
+                    delayed_command(TOAST_TIME, |_| GUIMessage::DismissToast),
+                ]);
+            }
+            // a later toast keeps showing until its own time is up
+            GUIMessage::DismissToast => {
+                if self
+                    .toast
+                    .as_ref()
+                    .is_some_and(|(_, expires)| Instant::now() >= *expires)
+                {
+                    self.toast = None;
+                }
+            }
+            GUIMessage::ClearClipboard(generation) => {
+                // a newer copy is still on the clipboard and will clear itself later
+                if generation != self.clipboard_generation {
+                    return Command::none();
+                }
+                self.clipboard_clears_at = None;
+                // nothing could be read before copying, e.g. it was empty or not text, so leave it
+                if let Some(contents) = self.stored_clipboard.take() {
+                    if self.config.notify_on_clear {
+                        let _ = notify::clipboard_cleared();
+                    }
+                    return self.write_clipboard(&contents);
+                }
             }
             GUIMessage::NewVault => return self.push_internal_state(PromptState::default()),
+            GUIMessage::Export(vault) => {
+                // always ask again since the export is in plaintext
+                self.temp_message = TempMessage::Export(vault, ExportFormat::default());
+                return self.push_internal_state(PasswordState::default());
+            }
+            GUIMessage::Exported(result) => match result {
+                Ok(Some(path)) => {
+                    self.notice = Some(format!("Exported to {}", path));
+                    return close_popup();
+                }
+                Ok(None) => {}
+                Err(e) => self.error = Some(e),
+            },
+            GUIMessage::Import(vault) => {
+                return Command::perform(load_import(), move |result| {
+                    GUIMessage::ImportLoaded(vault, result)
+                });
+            }
+            GUIMessage::ImportLoaded(vault, result) => match result {
+                Ok(Some(entries)) => {
+                    if entries.is_empty() {
+                        self.notice = Some("Nothing to import".into());
+                        return close_popup();
+                    }
+                    // importing into an empty vault sets its password
+                    let empty = self
+                        .info
+                        .get(&vault)
+                        .map_or(true, |schema| schema.is_empty());
+                    self.temp_message = TempMessage::Import(vault, entries);
+                    return self.ask_password(PasswordState::new(empty));
+                }
+                Ok(None) => {}
+                Err(e) => self.error = Some(e),
+            },
+            GUIMessage::ImportBitwarden(vault) => {
+                return Command::perform(load_bitwarden(vault), GUIMessage::BitwardenLoaded);
+            }
+            GUIMessage::BitwardenLoaded(result) => match result {
+                Ok(Some(import)) => {
+                    self.notice = Some(import.summary());
+                    if import.entries() == 0 {
+                        return close_popup();
+                    }
+                    // a new or empty vault takes this password, so it has to be typed twice
+                    let creates = import.vaults.keys().any(|vault| {
+                        self.info
+                            .get(vault)
+                            .map_or(true, |schema| schema.is_empty())
+                    });
+                    self.temp_message = TempMessage::ImportVaults(import);
+                    let focus = self.push_internal_state(PasswordState::new(creates));
+                    return Command::batch(vec![focus, close_popup()]);
+                }
+                Ok(None) => {}
+                Err(e) => self.error = Some(e),
+            },
+            GUIMessage::RestoreArchive(collision) => {
+                return Command::perform(pick_archive(), move |result| {
+                    GUIMessage::ArchiveChosen(collision, result)
+                });
+            }
+            GUIMessage::ArchiveChosen(collision, result) => match result {
+                Ok(Some(path)) => {
+                    self.temp_message = TempMessage::BackupImport(path, collision, None);
+                    return self
+                        .push_internal_state(PasswordState::default().titled("Archive password"));
+                }
+                Ok(None) => {}
+                Err(e) => self.error = Some(e),
+            },
+            GUIMessage::ChangePassword(vault) => {
+                return self.push_internal_state(ChangePasswordState::new(vault))
+            }
+            GUIMessage::RecoverPassword(vault) => {
+                return self.push_internal_state(ChangePasswordState::recovery(vault))
+            }
+            GUIMessage::Reharden(vault) => {
+                self.send_message(vec![ManagerMessage::Reharden(vault.clone())]);
+                self.notice = Some(format!(
+                    "The key for {} will be remade the next time it's unlocked",
+                    vault
+                ));
+                return close_popup();
+            }
+            GUIMessage::CheckReuse(vault) => {
+                self.temp_message = TempMessage::CheckReuse(vault);
+                return self.ask_password(PasswordState::default());
+            }
+            GUIMessage::CheckStrength(vault) => {
+                self.temp_message = TempMessage::CheckStrength(vault);
+                return self.ask_password(PasswordState::default());
+            }
+            GUIMessage::SetRecovery(recovery) => {
+                if let Some(InternalState::Prompt(prompt_state)) = self.active_state_mut() {
+                    prompt_state.recovery = recovery;
+                }
+            }
+            GUIMessage::ShowQr => {
+                let Some(InternalState::Entry(entry_state)) = self.active_state() else {
+                    return Command::none();
+                };
+                let unlocked = self.get_password(&entry_state.vault).is_some();
+                let key = entry_state.key.clone();
+                // authenticator apps take the whole setup rather than a single code
+                let data: Option<Password> = match entry_state.get_totp() {
+                    Some(totp) => Some(totp.uri(&key).into()),
+                    None => entry_state.get_password(),
+                };
+                // same as copying, only while the vault is still unlocked
+                if !unlocked {
+                    self.notice = Some("Unlock the vault again to show the code".into());
+                    return close_popup();
+                }
+                let Some(data) = data else {
+                    self.notice = Some("Nothing to show as a code".into());
+                    return close_popup();
+                };
+                match qr::render(data.expose_secret()) {
+                    Ok(code) => {
+                        self.internal_state
+                            .push(QrState::new(key, code, Instant::now() + QR_TIME).into());
+                        return delayed_command(QR_TIME, |_| GUIMessage::DismissQr);
+                    }
+                    Err(e) => {
+                        self.notice = Some(format!("Could not make the code: {}", e));
+                        return close_popup();
+                    }
+                }
+            }
+            GUIMessage::DismissQr => {
+                if let Some(InternalState::Qr(qr_state)) = self.active_state() {
+                    if Instant::now() >= qr_state.expires {
+                        self.internal_state.pop();
+                    }
+                }
+            }
+            // the manager only answers while connected, otherwise its version stays unknown
+            GUIMessage::ShowAbout => {
+                let connection = match &self.state {
+                    ConnectionState::Connected(_) => "connected".to_string(),
+                    ConnectionState::Connecting(_) => "connecting".to_string(),
+                    ConnectionState::Disconnected => "disconnected".to_string(),
+                };
+                let data_dir = self
+                    .config
+                    .data_dir
+                    .clone()
+                    .unwrap_or_else(utils::base_path);
+                self.internal_state
+                    .push(AboutState::new(connection, data_dir).into());
+                self.send_message(vec![ManagerMessage::Version]);
+            }
+            GUIMessage::CopyAbout => {
+                if let Some(InternalState::About(about_state)) = self.active_state() {
+                    let report: Password = about_state.report().into();
+                    self.toast = Some(("Copied!".to_string(), Instant::now() + TOAST_TIME));
+                    return Command::batch(vec![
+                        self.write_clipboard(&report),
+                        delayed_command(TOAST_TIME, |_| GUIMessage::DismissToast),
+                    ]);
+                }
+            }
+            GUIMessage::ShowAuditLog => {
+                self.send_message(vec![ManagerMessage::AuditLog(AUDIT_EVENTS)]);
+            }
+            GUIMessage::ExpandAll(expanded) => {
+                for vault in self.vaults.values_mut() {
+                    vault.expanded = expanded;
+                }
+                return self.save_expanded();
+            }
+            GUIMessage::AlwaysOnTop(on_top) => {
+                self.config.always_on_top = on_top;
+                let level = window::change_level(window::Id::MAIN, window_level(on_top));
+                if self.config.save().is_err() {
+                    self.notice = Some("Failed to save config file".into());
+                    return Command::batch([level, close_popup()]);
+                }
+                return level;
+            }
+            GUIMessage::ChangeSortOrder(order) => {
+                self.config.sort_order = order;
+                if self.config.save().is_err() {
+                    self.notice = Some("Failed to save config file".into());
+                    return close_popup();
+                }
+            }
+            GUIMessage::WindowResized(width, height) => {
+                self.window_width = width;
+                self.config.window_size = Some((width, height));
+                return self.save_window_later();
+            }
+            GUIMessage::WindowMoved(x, y) => {
+                self.config.window_position = Some((x, y));
+                return self.save_window_later();
+            }
+            GUIMessage::SaveWindow(generation) => {
+                if generation == self.window_generation && self.config.save().is_err() {
+                    self.notice = Some("Failed to save config file".into());
+                    return close_popup();
+                }
+            }
+            GUIMessage::EditAccent => {
+                return self
+                    .push_internal_state(AccentState::new(self.config.accent_color.clone()));
+            }
+            GUIMessage::CheckSystemTheme => self.system_theme = utils::system_theme(),
             GUIMessage::ChangeTheme(theme) => {
-                self.config.theme = theme.to_string();
+                if theme == AUTO_THEME || theme == CUSTOM_THEME {
+                    self.system_theme = utils::system_theme();
+                }
+                self.config.theme = theme;
                 if self.config.save().is_err() {
                     self.notice = Some("Failed to save config file".into());
                     return close_popup();
                 }
             }
             GUIMessage::TabPressed(shift) => {
+                match self.active_state_mut() {
+                    Some(InternalState::New(new_state)) => {
+                        return text_input::focus(new_state.tab(shift));
+                    }
+                    // kept inside the modal rather than wandering off to what's behind it
+                    Some(_) => {
+                        return if shift {
+                            Command::widget(operation::scope(
+                                MODAL_ID.clone().into(),
+                                focusable::focus_previous(),
+                            ))
+                        } else {
+                            Command::widget(operation::scope(
+                                MODAL_ID.clone().into(),
+                                focusable::focus_next(),
+                            ))
+                        };
+                    }
+                    None => {}
+                }
                 return if shift {
                     widget::focus_previous()
                 } else {
                     widget::focus_next()
+                };
+            }
+            // only opened over the main view, the other modals have their own buttons
+            GUIMessage::ShowMenu => {
+                if self.internal_state.is_empty() {
+                    let items = self.menu_items();
+                    return self.push_internal_state(MenuState::new(items));
+                }
+            }
+            GUIMessage::MenuMove(up) => {
+                if let Some(InternalState::Menu(menu_state)) = self.active_state_mut() {
+                    return menu_state.step(up);
+                }
+            }
+            GUIMessage::MenuActivate(index) => {
+                if let Some(InternalState::Menu(menu_state)) = self.active_state() {
+                    let message = menu_state.message(index);
+                    self.internal_state.pop();
+                    if let Some(message) = message {
+                        return delayed_command(Duration::ZERO, move |_| message);
+                    }
+                }
+            }
+            GUIMessage::Close => return self.request_quit(),
+            GUIMessage::RaiseWindow => return Self::show_window(),
+            GUIMessage::CloseRequested => {
+                if self.tray.is_some() {
+                    return window::change_mode(window::Id::MAIN, window::Mode::Hidden);
+                }
+                return self.request_quit();
+            }
+            GUIMessage::Tray(id) => {
+                match self.tray.as_ref().and_then(|tray| tray.action(&id)) {
+                    Some(TrayAction::Show) => return Self::show_window(),
+                    Some(TrayAction::Quit) => return self.request_quit(),
+                    Some(TrayAction::Copy(vault, key)) => match self.get_password(&vault) {
+                        Some(password) => {
+                            let id = self.request_id();
+                            self.tray_copy = Some(id);
+                            self.send_with_id(
+                                id,
+                                ManagerMessage::VaultMessage(vault, Message::Get(password, key)),
+                            );
+                        }
+                        // the vault has to be unlocked in the window first
+                        None => {
+                            self.temp_message = TempMessage::Get(vault, key);
+                            return Command::batch([
+                                Self::show_window(),
+                                self.ask_password(PasswordState::default()),
+                            ]);
+                        }
+                    },
+                    None => {}
+                }
+            }
+            // also redraws time dependent views
+            GUIMessage::Tick => {
+                self.check_timeouts();
+                return self.check_auto_lock();
+            }
+            GUIMessage::Lock => return self.lock_all(),
+            GUIMessage::ForgetPasswords => {
+                let failed = self
+                    .info
+                    .data
+                    .keys()
+                    .filter(|name| keychain::forget(name).is_err())
+                    .count();
+                if failed > 0 {
+                    self.notice = Some(format!(
+                        "Couldn't remove {} saved passwords from the keychain",
+                        failed
+                    ));
                 }
+                return self.lock_all();
             }
-            GUIMessage::Close => return window::close(window::Id::MAIN),
             GUIMessage::Nothing => {}
         }
 
         Command::none()
     }
+}
 
-    fn view(&self) -> Element<Self::Message> {
-        self.view()
-    }
-
-    fn subscription(&self) -> Subscription<Self::Message> {
-        let connection_subscriber = connection::connect().map(GUIMessage::Event);
-
-        let keyboard_subscriber = keyboard::on_key_press(|key, modifiers| {
-            for (_, shortcut) in SHORTCUTS.iter() {
-                let res = shortcut.check(&key, &modifiers);
-                if res.is_some() {
-                    return res;
-                }
+fn error_banner<'a>(error: &str) -> Element<'a, GUIMessage> {
+    let dismiss = button("Dismiss")
+        .style(theme::Button::Destructive)
+        .on_press(GUIMessage::DismissError);
+    container(row![text(error).width(Length::Fill), dismiss])
+        .width(Length::Fill)
+        .padding(5)
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Appearance {
+                text_color: Some(palette.danger.weak.text),
+                background: Some(palette.danger.weak.color.into()),
+                ..Default::default()
             }
-            None
-        });
-
-        // let keyboard_subscriber = keyboard::on_key_press(|key, modifiers| {
-        //     // println!("{:?}, {:?}", key, modifiers);
-        //     println!("{:?}", keyboard::Modifiers::COMMAND);
-        //     match (key.as_ref(), modifiers) {
-        //         (key::Key::Character("n"), keyboard::Modifiers::COMMAND) => {
-        //             Some(GUIMessage::NewVault)
-        //         }
-        //         (key::Key::Character("q"), keyboard::Modifiers::COMMAND) => Some(GUIMessage::Close),
-        //         (key::Key::Named(key::Named::Tab), _) => {
-        //             Some(GUIMessage::TabPressed(modifiers.shift()))
-        //         }
-        //         _ => {
-        //             // println!("{:?}, {:?}", key, modifiers);
-        //             None
-        //         }
-        //     }
-        //     // let keyboard::Key::Named(key) = key else {
-        //     //     return None;
-        //     // };
-        //     //
-        //     // match (key, modifiers) {
-        //     //     (key::Named::Tab, _) => Some(Message::TabPressed {
-        //     //         shift: modifiers.shift(),
-        //     //     }),
-        //     //     (key::Named::ArrowUp, keyboard::Modifiers::SHIFT) => {
-        //     //         Some(Message::ToggleFullscreen(window::Mode::Fullscreen))
-        //     //     }
-        //     //     (key::Named::ArrowDown, keyboard::Modifiers::SHIFT) => {
-        //     //         Some(Message::ToggleFullscreen(window::Mode::Windowed))
-        //     //     }
-        //     //     _ => None,
-        //     // }
-        // });
-
-        Subscription::batch(vec![connection_subscriber, keyboard_subscriber])
-    }
-
-    fn theme(&self) -> Theme {
-        self.get_theme()
-    }
+        })
+        .into()
 }
 
 fn section_header<'a>(label: &str) -> button::Button<'a, GUIMessage, iced::Theme, iced::Renderer> {
     base_button(text(label), Some(GUIMessage::Nothing))
 }
 
+// the collapsed menu bar, the tooltip stands in for a label
+fn hamburger<'a>() -> Element<'a, GUIMessage, iced::Theme, iced::Renderer> {
+    let shortcut = SHORTCUTS
+        .get("Menu")
+        .map(|shortcut| format!("Menu ({})", shortcut.key_display()))
+        .unwrap_or_else(|| "Menu".to_string());
+    tooltip(
+        section_header("\u{2630}"),
+        shortcut,
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
 fn submenu_item<'a>(label: &str) -> button::Button<'a, GUIMessage, iced::Theme, iced::Renderer> {
     base_button(
         row![
@@ -0,0 +1,84 @@
+use iced::{
+    theme,
+    widget::{button, column, container, scrollable, text},
+    Command, Element, Length,
+};
+use once_cell::sync::Lazy;
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card};
+
+static LIST_ID: Lazy<scrollable::Id> = Lazy::new(scrollable::Id::unique);
+
+// the menu bar's actions as a flat list, for getting to them without the mouse
+#[derive(Debug, Clone)]
+pub struct MenuState {
+    // the path through the menus to the action and what it sends
+    pub items: Vec<(String, GUIMessage)>,
+    pub selected: usize,
+}
+
+impl MenuState {
+    pub fn new(items: Vec<(String, GUIMessage)>) -> Self {
+        Self { items, selected: 0 }
+    }
+
+    // wraps around at either end, and keeps the selection in view
+    pub fn step(&mut self, up: bool) -> Command<GUIMessage> {
+        if self.items.is_empty() {
+            return Command::none();
+        }
+        let last = self.items.len() - 1;
+        self.selected = match (up, self.selected) {
+            (true, 0) => last,
+            (true, selected) => selected - 1,
+            (false, selected) if selected >= last => 0,
+            (false, selected) => selected + 1,
+        };
+        let y = if last == 0 {
+            0.0
+        } else {
+            self.selected as f32 / last as f32
+        };
+        scrollable::snap_to(LIST_ID.clone(), scrollable::RelativeOffset { x: 0.0, y })
+    }
+
+    pub fn message(&self, index: usize) -> Option<GUIMessage> {
+        self.items.get(index).map(|(_, message)| message.clone())
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text("Menu");
+        let hint = text("Up and Down to move, Enter to pick, Esc to close").size(12);
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, (label, _))| {
+                let style = if index == self.selected {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Text
+                };
+                button(text(label))
+                    .width(Length::Fill)
+                    .style(style)
+                    .on_press(GUIMessage::MenuActivate(index))
+                    .into()
+            })
+            .collect::<Vec<_>>();
+        Card::new(
+            header,
+            container(
+                column![
+                    hint,
+                    scrollable(column(items))
+                        .id(LIST_ID.clone())
+                        .height(Length::Fixed(300.0))
+                ]
+                .spacing(10),
+            ),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
@@ -1,5 +1,18 @@
+pub mod about;
+pub mod accent;
+pub mod audit;
+pub mod change_password;
+pub mod confirm_delete;
+pub mod confirm_discard;
+pub mod confirm_regenerate;
 pub mod entry;
 pub mod manager;
+pub mod menu;
 pub mod new_entry;
 pub mod password;
 pub mod prompt;
+pub mod qr;
+pub mod recovery;
+pub mod rename;
+pub mod reuse;
+pub mod weak;
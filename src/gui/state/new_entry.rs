@@ -1,21 +1,68 @@
+use std::collections::BTreeSet;
+
 use iced::{
-    widget::{button, column, container, pick_list, row, text, text_input},
-    Element, Length,
+    theme,
+    widget::{
+        button, checkbox, column, combo_box, container, pick_list, row, slider, text, text_editor,
+        text_input,
+    },
+    Color, Element, Length,
 };
+use once_cell::sync::Lazy;
 use secrecy::ExposeSecret;
 
 use crate::{
-    gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID},
-    store::{StoreChoice, StoreHash},
+    generate::{classes::ClassSpec, pronounce::PronounceSpec, GenSpec},
+    gui::{gui_message::GUIMessage, strength::strength_bar, widget::card::Card, INPUT_ID},
+    store::{CustomFields, FieldKind, Store, StoreChoice, StoreHash},
+    Password,
 };
 
-#[derive(Debug, Clone)]
+// never given to a widget, focusing it takes focus away from every input so Enter submits
+static SUBMIT_ID: Lazy<text_input::Id> = Lazy::new(text_input::Id::unique);
+
+#[derive(Debug)]
 pub struct NewEntryState {
     pub vault: String,
     pub name: String,
     pub choice: StoreChoice,
     pub value: StoreHash,
     pub hidden: bool,
+    pub note: text_editor::Content,
+    // generate from these instead of the configured spec when custom
+    pub custom_generator: bool,
+    pub generator: ClassSpec,
+    // alternate consonants and vowels, only the length and whether to add a digit or symbol apply
+    pub pronounceable: bool,
+    // what the generated password is really worth, until it's edited by hand
+    pub generated_bits: Option<f32>,
+    // the other passwords from the last generate, all worth the same bits
+    pub candidates: Vec<Password>,
+    pub candidate_bits: Option<f32>,
+    pub presets: combo_box::State<String>,
+    // the picked preset, its spec is only used directly when the settings above can't show it
+    pub preset: Option<String>,
+    pub preset_spec: Option<String>,
+    // what the current settings get saved as
+    pub preset_name: String,
+    pub custom: CustomFields,
+    // why the name was rejected on the last submit
+    pub name_error: Option<String>,
+    // the name is already taken in the vault, so submitting replaces that entry
+    pub exists: bool,
+    // why the values were rejected on the last submit, e.g. a card number that doesn't check out
+    pub error: Option<String>,
+    // position in the focus order, clicking into an input isn't seen so this is only a guess
+    // until it's typed in
+    pub focus: usize,
+    // the read of the entry being duplicated, the fields are filled in once it's answered
+    pub request: Option<u64>,
+    // typed into, a generated password or a duplicate's values don't count
+    pub edited: bool,
+    // as typed, split on commas once it's saved
+    pub tags: String,
+    // a pasted `otpauth://` uri, only kept so the input shows what was pasted
+    pub totp_uri: String,
 }
 
 impl Default for NewEntryState {
@@ -26,6 +73,26 @@ impl Default for NewEntryState {
             choice: StoreChoice::default(),
             value: StoreChoice::default().convert_default().as_hash(),
             hidden: true,
+            note: text_editor::Content::new(),
+            custom_generator: false,
+            generator: ClassSpec::default(),
+            pronounceable: false,
+            generated_bits: None,
+            candidates: vec![],
+            candidate_bits: None,
+            presets: combo_box::State::new(vec![]),
+            preset: None,
+            preset_spec: None,
+            preset_name: String::new(),
+            custom: vec![],
+            name_error: None,
+            exists: false,
+            error: None,
+            focus: 0,
+            request: None,
+            edited: false,
+            tags: String::new(),
+            totp_uri: String::new(),
         }
     }
 }
@@ -38,7 +105,265 @@ impl NewEntryState {
             choice: StoreChoice::default(),
             value: StoreChoice::default().convert_default().as_hash(),
             hidden: true,
+            note: text_editor::Content::new(),
+            custom_generator: false,
+            generator: ClassSpec::default(),
+            pronounceable: false,
+            generated_bits: None,
+            candidates: vec![],
+            candidate_bits: None,
+            presets: combo_box::State::new(vec![]),
+            preset: None,
+            preset_spec: None,
+            preset_name: String::new(),
+            custom: vec![],
+            name_error: None,
+            exists: false,
+            error: None,
+            focus: 0,
+            request: None,
+            edited: false,
+            tags: String::new(),
+            totp_uri: String::new(),
+        }
+    }
+    // the name, then the single line fields of the entry, then the custom fields, and the submit
+    // button last, the notes editor can't be focused this way
+    fn focus_order(&self) -> Vec<text_input::Id> {
+        let mut order = vec![INPUT_ID.clone()];
+        order.extend(
+            self.choice
+                .fields()
+                .into_iter()
+                .filter(|(_, kind)| *kind != FieldKind::Multiline)
+                .map(|(field, _)| field_id(field)),
+        );
+        for i in 0..self.custom.len() {
+            order.push(custom_id(i, false));
+            order.push(custom_id(i, true));
+        }
+        order.push(SUBMIT_ID.clone());
+        order
+    }
+    // moves along the focus order, wrapping around at either end, and gives the input to focus
+    pub fn tab(&mut self, backwards: bool) -> text_input::Id {
+        let order = self.focus_order();
+        let current = self.focus.min(order.len() - 1);
+        self.focus = if backwards {
+            (current + order.len() - 1) % order.len()
+        } else {
+            (current + 1) % order.len()
+        };
+        order[self.focus].clone()
+    }
+    // typing into an input means it has focus
+    pub fn focus_field(&mut self, field: &str) {
+        if let Some(i) = self
+            .focus_order()
+            .iter()
+            .position(|id| *id == field_id(field))
+        {
+            self.focus = i;
+        }
+    }
+    fn submit_focused(&self) -> bool {
+        self.focus + 1 == self.focus_order().len()
+    }
+    pub fn name(mut self, name: String, exists: bool) -> Self {
+        self.name = name;
+        self.exists = exists;
+        self
+    }
+    pub fn request(mut self, id: u64) -> Self {
+        self.request = Some(id);
+        self
+    }
+    // takes everything from the entry being duplicated
+    pub fn fill(&mut self, value: Store) {
+        self.custom = value.custom_fields();
+        let (choice, value) = value.split();
+        self.choice = choice;
+        self.value = value;
+        if let Some(note) = self
+            .choice
+            .multiline_field()
+            .and_then(|field| self.value.get(field))
+        {
+            self.note = text_editor::Content::with_text(note.expose_secret());
+        }
+    }
+    pub fn choice(mut self, choice: StoreChoice) -> Self {
+        self.choice = choice;
+        self.value = choice.convert_default().as_hash();
+        self
+    }
+    pub fn tags(mut self, tags: BTreeSet<String>) -> Self {
+        self.tags = join_tags(&tags);
+        self
+    }
+    pub fn presets(mut self, names: Vec<String>) -> Self {
+        self.presets = combo_box::State::new(names);
+        self
+    }
+    // fills in the settings from a preset, ones that aren't made of character classes are kept as
+    // they are
+    pub fn select_preset(&mut self, name: String, spec: GenSpec) {
+        self.custom_generator = true;
+        self.preset_spec = None;
+        match spec {
+            GenSpec::Classes(spec) => {
+                self.generator = spec;
+                self.pronounceable = false;
+            }
+            GenSpec::Pronounce(spec) => {
+                self.generator = ClassSpec {
+                    length: spec.length,
+                    digits: spec.digit,
+                    symbols: spec.symbol,
+                    ..ClassSpec::default()
+                };
+                self.pronounceable = true;
+            }
+            other => self.preset_spec = Some(other.to_string()),
+        }
+        self.preset_name = name.clone();
+        self.preset = Some(name);
+    }
+    pub fn generator(mut self, custom: bool, spec: ClassSpec, pronounceable: bool) -> Self {
+        self.custom_generator = custom;
+        self.generator = spec;
+        self.pronounceable = pronounceable;
+        self
+    }
+    // the spec to use for generating when it isn't the configured one
+    pub fn custom_spec(&self) -> Option<GenSpec> {
+        if !self.custom_generator {
+            return None;
+        }
+        if let Some(spec) = self.preset_spec.as_ref().and_then(|s| s.parse().ok()) {
+            return Some(spec);
+        }
+        if self.pronounceable {
+            return Some(GenSpec::Pronounce(PronounceSpec {
+                length: self.generator.length,
+                digit: self.generator.digits,
+                symbol: self.generator.symbols,
+            }));
+        }
+        Some(GenSpec::Classes(self.generator.clone()))
+    }
+    // generating again and copying, so a password can be tried out before the entry is created,
+    // or one made somewhere else can be pasted in
+    fn generate_buttons(&self) -> Element<GUIMessage> {
+        let empty = self
+            .value
+            .get("password")
+            .map_or(true, |p| p.expose_secret().is_empty());
+        let generate = button(if empty { "Generate" } else { "Regenerate" })
+            .on_press(GUIMessage::GeneratePassword);
+        let copy = button("Copy").on_press_maybe((!empty).then_some(GUIMessage::CopyPassword));
+        let paste = button("Paste").on_press(GUIMessage::PastePassword);
+        row![generate, copy, paste].into()
+    }
+    // only shown when there's more than one to pick from, hidden along with the password
+    fn candidates_view(&self) -> Element<GUIMessage> {
+        if self.candidates.len() < 2 {
+            return column![].into();
         }
+        let rows = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let value = text_input("", candidate.expose_secret())
+                    .width(Length::Fill)
+                    .secure(self.hidden);
+                let pick = button("Use").on_press(GUIMessage::PickCandidate(index));
+                let copy = button("Copy").on_press(GUIMessage::CopyCandidate(index));
+                row![value, pick, copy].spacing(5).into()
+            });
+        column![text("Candidates:").size(14), column(rows).spacing(2)]
+            .spacing(2)
+            .into()
+    }
+
+    fn generator_view(&self) -> Element<GUIMessage> {
+        let spec = self.generator.clone();
+        let custom = checkbox("Custom generation", self.custom_generator).on_toggle({
+            let spec = spec.clone();
+            move |custom| GUIMessage::SetGenerator(custom, spec.clone())
+        });
+        if !self.custom_generator {
+            return custom.into();
+        }
+        let length = slider(4..=64, spec.length as u32, {
+            let spec = spec.clone();
+            move |length| {
+                GUIMessage::SetGenerator(
+                    true,
+                    ClassSpec {
+                        length: length as usize,
+                        ..spec.clone()
+                    },
+                )
+            }
+        });
+        let class = |label: &'static str, used: bool, set: fn(&mut ClassSpec, bool)| {
+            let spec = spec.clone();
+            checkbox(label, used).on_toggle(move |value| {
+                let mut spec = spec.clone();
+                set(&mut spec, value);
+                GUIMessage::SetGenerator(true, spec)
+            })
+        };
+        let pin = button("PIN").on_press(GUIMessage::SetGenerator(true, ClassSpec::pin(6)));
+        let pronounceable =
+            checkbox("Pronounceable", self.pronounceable).on_toggle(GUIMessage::SetPronounceable);
+        // the letters are always lowercase, so only adding a digit or symbol can be picked
+        let classes = if self.pronounceable {
+            row![
+                class("Digit", spec.digits, |s, v| s.digits = v),
+                class("Symbol", spec.symbols, |s, v| s.symbols = v),
+            ]
+        } else {
+            row![
+                class("Upper", spec.upper, |s, v| s.upper = v),
+                class("Lower", spec.lower, |s, v| s.lower = v),
+                class("Digits", spec.digits, |s, v| s.digits = v),
+                class("Symbols", spec.symbols, |s, v| s.symbols = v),
+            ]
+        };
+        let preset = combo_box(
+            &self.presets,
+            "Preset",
+            self.preset.as_ref(),
+            GUIMessage::SelectPreset,
+        );
+        let preset_name = text_input("Preset name", &self.preset_name)
+            .on_input(GUIMessage::PresetNameChanged)
+            .width(150.0);
+        let save = button("Save").on_press_maybe(
+            (!self.preset_name.trim().is_empty()).then_some(GUIMessage::SavePreset),
+        );
+        let remove =
+            button("Remove").on_press_maybe(self.preset.clone().map(GUIMessage::RemovePreset));
+        let presets = row![preset, preset_name, save, remove].spacing(10);
+        // the settings don't apply while generating from a preset they can't show
+        if let Some(spec) = &self.preset_spec {
+            return column![
+                row![custom, pin].spacing(10),
+                presets,
+                text(format!("Spec: {}", spec))
+            ]
+            .into();
+        }
+        column![
+            row![custom, pin, pronounceable].spacing(10),
+            presets,
+            row![text(format!("Length: {}", spec.length)), length].spacing(10),
+            classes.spacing(10)
+        ]
+        .into()
     }
     pub fn view(&self) -> Element<GUIMessage> {
         let header = text(format!("New entry for {}", self.vault));
@@ -61,16 +386,26 @@ impl NewEntryState {
                 )
                 .width(Length::Fill)
                 .on_input(|v| GUIMessage::UpdateField("password".to_string(), v.into()))
+                .id(field_id("password"))
                 .on_submit(GUIMessage::Submit)
                 .secure(self.hidden);
-                let password_generate = button("Generate").on_press(GUIMessage::GeneratePassword);
+                let password_generate = self.generate_buttons();
                 let toggle_show = if self.hidden {
                     button("Show").on_press(GUIMessage::ShowPassword)
                 } else {
                     button("Hide").on_press(GUIMessage::HidePassword)
                 };
 
-                container(row![prefix, password_input, password_generate, toggle_show])
+                let strength = strength_bar(
+                    self.value.get("password").unwrap().expose_secret(),
+                    self.generated_bits,
+                );
+
+                container(column![
+                    row![prefix, password_input, password_generate, toggle_show],
+                    strength,
+                    self.candidates_view()
+                ])
             }
             StoreChoice::UsernamePassword => {
                 let username_prefix = text("Username:");
@@ -81,6 +416,7 @@ impl NewEntryState {
                 )
                 .width(Length::Fill)
                 .on_input(|v| GUIMessage::UpdateField("username".to_string(), v.into()))
+                .id(field_id("username"))
                 .on_submit(GUIMessage::Submit);
                 let password_input = text_input(
                     "Password",
@@ -88,15 +424,20 @@ impl NewEntryState {
                 )
                 .width(Length::Fill)
                 .on_input(|v| GUIMessage::UpdateField("password".to_string(), v.into()))
+                .id(field_id("password"))
                 .on_submit(GUIMessage::Submit)
                 .secure(self.hidden);
 
-                let password_generate = button("Generate").on_press(GUIMessage::GeneratePassword);
+                let password_generate = self.generate_buttons();
                 let toggle_show = if self.hidden {
                     button("Show").on_press(GUIMessage::ShowPassword)
                 } else {
                     button("Hide").on_press(GUIMessage::HidePassword)
                 };
+                let strength = strength_bar(
+                    self.value.get("password").unwrap().expose_secret(),
+                    self.generated_bits,
+                );
                 container(column![
                     row![username_prefix, username_input],
                     row![
@@ -104,11 +445,168 @@ impl NewEntryState {
                         password_input,
                         password_generate,
                         toggle_show
+                    ],
+                    strength,
+                    self.candidates_view()
+                ])
+            }
+            StoreChoice::Login => {
+                let username_prefix = text("Username:");
+                let password_prefix = text("Password:");
+                let url_prefix = text("URL:");
+                let username_input = text_input(
+                    "Username",
+                    self.value.get("username").unwrap().expose_secret(),
+                )
+                .width(Length::Fill)
+                .on_input(|v| GUIMessage::UpdateField("username".to_string(), v.into()))
+                .id(field_id("username"))
+                .on_submit(GUIMessage::Submit);
+                let url_input = text_input("URL", self.value.get("url").unwrap().expose_secret())
+                    .width(Length::Fill)
+                    .on_input(|v| GUIMessage::UpdateField("url".to_string(), v.into()))
+                    .id(field_id("url"))
+                    .on_submit(GUIMessage::Submit);
+                let password_input = text_input(
+                    "Password",
+                    self.value.get("password").unwrap().expose_secret(),
+                )
+                .width(Length::Fill)
+                .on_input(|v| GUIMessage::UpdateField("password".to_string(), v.into()))
+                .id(field_id("password"))
+                .on_submit(GUIMessage::Submit)
+                .secure(self.hidden);
+
+                let password_generate = self.generate_buttons();
+                let toggle_show = if self.hidden {
+                    button("Show").on_press(GUIMessage::ShowPassword)
+                } else {
+                    button("Hide").on_press(GUIMessage::HidePassword)
+                };
+                let strength = strength_bar(
+                    self.value.get("password").unwrap().expose_secret(),
+                    self.generated_bits,
+                );
+                container(column![
+                    row![username_prefix, username_input],
+                    row![url_prefix, url_input],
+                    row![
+                        password_prefix,
+                        password_input,
+                        password_generate,
+                        toggle_show
+                    ],
+                    strength,
+                    self.candidates_view()
+                ])
+            }
+            StoreChoice::Totp => {
+                let secret_prefix = text("Secret:");
+                let secret_input =
+                    text_input("Secret", self.value.get("secret").unwrap().expose_secret())
+                        .width(Length::Fill)
+                        .on_input(|v| GUIMessage::UpdateField("secret".to_string(), v.into()))
+                        .id(field_id("secret"))
+                        .on_submit(GUIMessage::Submit)
+                        .secure(self.hidden);
+                let toggle_show = if self.hidden {
+                    button("Show").on_press(GUIMessage::ShowPassword)
+                } else {
+                    button("Hide").on_press(GUIMessage::HidePassword)
+                };
+                let period_prefix = text("Period:");
+                let period_input =
+                    text_input("30", self.value.get("period").unwrap().expose_secret())
+                        .on_input(|v| GUIMessage::UpdateField("period".to_string(), v.into()))
+                        .id(field_id("period"))
+                        .on_submit(GUIMessage::Submit);
+                let digits_prefix = text("Digits:");
+                let digits_input =
+                    text_input("6", self.value.get("digits").unwrap().expose_secret())
+                        .on_input(|v| GUIMessage::UpdateField("digits".to_string(), v.into()))
+                        .id(field_id("digits"))
+                        .on_submit(GUIMessage::Submit);
+                let algorithm_prefix = text("Algorithm:");
+                let algorithm_input =
+                    text_input("SHA1", self.value.get("algorithm").unwrap().expose_secret())
+                        .on_input(|v| GUIMessage::UpdateField("algorithm".to_string(), v.into()))
+                        .id(field_id("algorithm"))
+                        .on_submit(GUIMessage::Submit);
+                let uri_prefix = text("URI:");
+                let uri_input = text_input("otpauth://totp/...", &self.totp_uri)
+                    .width(Length::Fill)
+                    .on_input(GUIMessage::TotpUriChanged)
+                    .on_paste(GUIMessage::TotpUriChanged);
+                container(column![
+                    row![uri_prefix, uri_input],
+                    row![secret_prefix, secret_input, toggle_show],
+                    row![
+                        period_prefix,
+                        period_input,
+                        digits_prefix,
+                        digits_input,
+                        algorithm_prefix,
+                        algorithm_input
                     ]
                 ])
             }
+            StoreChoice::Note => {
+                let note_prefix = text("Note:");
+                let note_input = text_editor(&self.note)
+                    .on_action(GUIMessage::EditNote)
+                    .height(200.0);
+                container(column![note_prefix, note_input])
+            }
+            StoreChoice::Card => {
+                let cardholder_prefix = text("Cardholder:");
+                let cardholder_input = text_input(
+                    "Cardholder",
+                    self.value.get("cardholder").unwrap().expose_secret(),
+                )
+                .width(Length::Fill)
+                .on_input(|v| GUIMessage::UpdateField("cardholder".to_string(), v.into()))
+                .id(field_id("cardholder"))
+                .on_submit(GUIMessage::Submit);
+                let number_prefix = text("Number:");
+                let number_input =
+                    text_input("Number", self.value.get("number").unwrap().expose_secret())
+                        .width(Length::Fill)
+                        .on_input(|v| GUIMessage::UpdateField("number".to_string(), v.into()))
+                        .id(field_id("number"))
+                        .on_submit(GUIMessage::Submit)
+                        .secure(self.hidden);
+                let toggle_show = if self.hidden {
+                    button("Show").on_press(GUIMessage::ShowPassword)
+                } else {
+                    button("Hide").on_press(GUIMessage::HidePassword)
+                };
+                let expiry_prefix = text("Expiry:");
+                let expiry_input =
+                    text_input("MM/YY", self.value.get("expiry").unwrap().expose_secret())
+                        .on_input(|v| GUIMessage::UpdateField("expiry".to_string(), v.into()))
+                        .id(field_id("expiry"))
+                        .on_submit(GUIMessage::Submit);
+                let cvv_prefix = text("CVV:");
+                let cvv_input = text_input("CVV", self.value.get("cvv").unwrap().expose_secret())
+                    .on_input(|v| GUIMessage::UpdateField("cvv".to_string(), v.into()))
+                    .id(field_id("cvv"))
+                    .on_submit(GUIMessage::Submit)
+                    .secure(self.hidden);
+                container(column![
+                    row![cardholder_prefix, cardholder_input],
+                    row![number_prefix, number_input, toggle_show],
+                    row![expiry_prefix, expiry_input, cvv_prefix, cvv_input]
+                ])
+            }
+        };
+        let create_button =
+            button(if self.exists { "Overwrite" } else { "Create" }).on_press(GUIMessage::Submit);
+        // shows that Enter submits now
+        let create_button = if self.submit_focused() {
+            create_button.style(theme::Button::Positive)
+        } else {
+            create_button
         };
-        let create_button = button("Create").on_press(GUIMessage::Submit);
         let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
         // let header = container(header).style(|theme: &iced::Theme| {
         //     let palette = theme.extended_palette();
@@ -130,16 +628,84 @@ impl NewEntryState {
         //     })
         //     .max_width(500.0);
         // card.into()
+        let mut content = column![row![name_prefix, name_input]];
+        if let Some(error) = &self.name_error {
+            content =
+                content.push(text(error).style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2))));
+        } else if self.exists {
+            content = content.push(
+                text("An entry with this name already exists and will be overwritten")
+                    .style(theme::Text::Color(Color::from_rgb(0.8, 0.6, 0.1))),
+            );
+        }
+        content = content.push(style_choice).push(data_input);
+        if let Some(error) = &self.error {
+            content =
+                content.push(text(error).style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2))));
+        }
+        if self.choice.field_kind("password").is_some() {
+            content = content.push(self.generator_view());
+        }
+        if self.choice.field_kind("notes").is_some() {
+            let notes_input = text_editor(&self.note)
+                .on_action(GUIMessage::EditNote)
+                .height(100.0);
+            content = content.push(column![text("Notes:"), notes_input]);
+        }
+        content = content
+            .push(tags_view(&self.tags))
+            .push(custom_fields_view(&self.custom, self.hidden));
         Card::new(
             header,
-            container(column![
-                row![name_prefix, name_input],
-                style_choice,
-                data_input,
-                row![create_button, cancel_button]
-            ]),
+            container(content.push(row![create_button, cancel_button])),
         )
         .max_width(500.0)
         .into()
     }
 }
+
+pub fn tags_view(tags: &str) -> Element<GUIMessage> {
+    let tags_input =
+        text_input("Tags, separated by commas", tags).on_input(GUIMessage::TagsChanged);
+    row![text("Tags:"), tags_input].spacing(5).into()
+}
+
+pub fn join_tags(tags: &BTreeSet<String>) -> String {
+    tags.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+// name and value inputs for each custom field, values are masked along with the password
+pub fn custom_fields_view(custom: &CustomFields, hidden: bool) -> Element<GUIMessage> {
+    let mut rows = column![];
+    for (i, (name, value)) in custom.iter().enumerate() {
+        let name_input = text_input("Field name", name)
+            .id(custom_id(i, false))
+            .width(Length::FillPortion(1))
+            .on_input({
+                let value = value.clone();
+                move |name| GUIMessage::UpdateCustomField(i, name, value.clone())
+            });
+        let value_input = text_input("Value", value.expose_secret())
+            .id(custom_id(i, true))
+            .width(Length::FillPortion(2))
+            .on_input({
+                let name = name.clone();
+                move |value| GUIMessage::UpdateCustomField(i, name.clone(), value.into())
+            })
+            .secure(hidden);
+        let remove_button = button("X").on_press(GUIMessage::RemoveCustomField(i));
+        rows = rows.push(row![name_input, value_input, remove_button]);
+    }
+    rows.push(button("+ Add field").on_press(GUIMessage::AddCustomField))
+        .into()
+}
+
+// stable ids so tabbing goes through the inputs in the order they're shown
+fn field_id(field: &str) -> text_input::Id {
+    text_input::Id::new(format!("field-{}", field))
+}
+
+fn custom_id(i: usize, value: bool) -> text_input::Id {
+    let part = if value { "value" } else { "name" };
+    text_input::Id::new(format!("custom-{}-{}", i, part))
+}
@@ -1,6 +1,7 @@
 use iced::{
+    theme,
     widget::{button, column, container, text, text_input},
-    Element, Length,
+    Color, Element, Length,
 };
 use secrecy::ExposeSecret;
 
@@ -11,15 +12,21 @@ use crate::{
 
 #[derive(Debug, Clone)]
 pub struct PasswordState {
+    // what the password is for, when it isn't a vault's
+    pub title: String,
     pub password: Password,
     pub confirm: Option<Password>,
+    // why the last password didn't work
+    pub error: Option<String>,
 }
 
 impl Default for PasswordState {
     fn default() -> Self {
         Self {
+            title: "Vault password".into(),
             password: String::new().into(),
             confirm: None,
+            error: None,
         }
     }
 }
@@ -27,14 +34,24 @@ impl Default for PasswordState {
 impl PasswordState {
     pub fn new(confirm: bool) -> Self {
         PasswordState {
+            title: "Vault password".into(),
             password: String::new().into(),
             confirm: if confirm {
                 Some(String::new().into())
             } else {
                 None
             },
+            error: None,
         }
     }
+    pub fn titled(mut self, title: &str) -> Self {
+        self.title = title.into();
+        self
+    }
+    pub fn with_error(mut self, error: String) -> Self {
+        self.error = Some(error);
+        self
+    }
     pub fn confirm() -> Self {
         Self::new(true)
     }
@@ -46,13 +63,16 @@ impl PasswordState {
         }
     }
     pub fn view(&self) -> Element<GUIMessage> {
-        let header = text("Vault password");
-        let password_input = text_input("vault password", self.password.clone().expose_secret())
-            .id(INPUT_ID.clone())
-            .on_input(|p| GUIMessage::PasswordChanged(p.into()))
-            .on_submit(GUIMessage::Submit)
-            .width(Length::Fill)
-            .secure(true);
+        let header = text(&self.title);
+        let password_input = text_input(
+            &self.title.to_lowercase(),
+            self.password.clone().expose_secret(),
+        )
+        .id(INPUT_ID.clone())
+        .on_input(|p| GUIMessage::PasswordChanged(p.into()))
+        .on_submit(GUIMessage::Submit)
+        .width(Length::Fill)
+        .secure(true);
         let password_input = if let Some(confirm) = &self.confirm {
             let confirm_input = text_input("confirm password", confirm.expose_secret())
                 .on_input(|p| GUIMessage::PasswordConfirmChanged(p.into()))
@@ -63,6 +83,11 @@ impl PasswordState {
         } else {
             column![password_input]
         };
+        let password_input = match &self.error {
+            Some(error) => password_input
+                .push(text(error).style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2)))),
+            None => password_input,
+        };
         let cancel = button("Cancel").on_press(GUIMessage::Exit);
         Card::new(header, container(column![password_input, cancel]))
             .max_width(500.0)
@@ -1,6 +1,7 @@
 use iced::{
-    widget::{button, column, container, row, text, text_input},
-    Element,
+    theme,
+    widget::{button, checkbox, column, container, row, text, text_input},
+    Color, Element,
 };
 
 use crate::gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID};
@@ -8,6 +9,10 @@ use crate::gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID};
 #[derive(Debug, Clone, Default)]
 pub struct PromptState {
     pub vault: String,
+    // whether to make a recovery key along with the vault
+    pub recovery: bool,
+    // why the name was rejected on the last submit
+    pub error: Option<String>,
 }
 
 impl PromptState {
@@ -17,12 +22,18 @@ impl PromptState {
             .id(INPUT_ID.clone())
             .on_input(GUIMessage::PromptChanged)
             .on_submit(GUIMessage::Submit);
+        let mut name = column![name_input];
+        if let Some(error) = &self.error {
+            name = name.push(text(error).style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2))));
+        }
+        let recovery =
+            checkbox("Create a recovery key", self.recovery).on_toggle(GUIMessage::SetRecovery);
 
         let create_button = button("Create").on_press(GUIMessage::Submit);
         let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
         Card::new(
             header,
-            container(column![name_input, row![create_button, cancel_button]]),
+            container(column![name, recovery, row![create_button, cancel_button]]),
         )
         .max_width(500.0)
         .into()
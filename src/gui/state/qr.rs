@@ -0,0 +1,37 @@
+use std::time::Instant;
+
+use iced::{
+    widget::{button, column, container, image, text},
+    Alignment, Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card};
+
+// the code is as good as the secret itself, so it only stays up for a little while
+#[derive(Debug, Clone)]
+pub struct QrState {
+    pub key: String,
+    pub code: image::Handle,
+    pub expires: Instant,
+}
+
+impl QrState {
+    pub fn new(key: String, code: image::Handle, expires: Instant) -> Self {
+        Self { key, code, expires }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!("Scan {}", self.key));
+        let done_button = button("Done").on_press(GUIMessage::Submit);
+        Card::new(
+            header,
+            container(
+                column![image(self.code.clone()), done_button]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+            ),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
@@ -0,0 +1,39 @@
+use iced::{
+    widget::{button, column, container, row, text},
+    Element,
+};
+use secrecy::ExposeSecret;
+
+use crate::{
+    gui::{gui_message::GUIMessage, widget::card::Card},
+    Password,
+};
+
+// the only time the recovery key is shown, it's gone once this is closed
+#[derive(Debug, Clone)]
+pub struct RecoveryState {
+    pub key: Password,
+}
+
+impl RecoveryState {
+    pub fn new(key: Password) -> Self {
+        Self { key }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text("Recovery key");
+        let info = text(
+            "This key can set a new password for the vault if the current one is forgotten. \
+             Write it down somewhere safe, it will not be shown again.",
+        );
+        let key = text(self.key.expose_secret()).size(20);
+        let copy_button = button("Copy").on_press(GUIMessage::CopyPassword);
+        let done_button = button("Done").on_press(GUIMessage::Submit);
+        Card::new(
+            header,
+            container(column![info, key, row![copy_button, done_button].spacing(10)].spacing(10)),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
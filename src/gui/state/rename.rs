@@ -0,0 +1,80 @@
+use iced::{
+    theme,
+    widget::{button, column, container, row, text, text_input},
+    Color, Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card, INPUT_ID};
+
+#[derive(Debug, Clone)]
+pub struct RenameState {
+    pub vault: String,
+    pub key: String,
+    pub name: String,
+}
+
+impl RenameState {
+    pub fn new(vault: String, key: String) -> Self {
+        Self {
+            vault,
+            name: key.clone(),
+            key,
+        }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!("Rename {} in {}", self.key, self.vault));
+        let name_input = text_input("New name", &self.name)
+            .id(INPUT_ID.clone())
+            .on_input(GUIMessage::PromptChanged)
+            .on_submit(GUIMessage::Submit);
+
+        let rename_button = button("Rename").on_press(GUIMessage::Submit);
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![name_input, row![rename_button, cancel_button]]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenameVaultState {
+    pub vault: String,
+    pub name: String,
+    // why the name was rejected on the last submit
+    pub error: Option<String>,
+}
+
+impl RenameVaultState {
+    pub fn new(vault: String) -> Self {
+        Self {
+            name: vault.clone(),
+            vault,
+            error: None,
+        }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!("Rename vault {}", self.vault));
+        let name_input = text_input("New name", &self.name)
+            .id(INPUT_ID.clone())
+            .on_input(GUIMessage::PromptChanged)
+            .on_submit(GUIMessage::Submit);
+        let mut name = column![name_input];
+        if let Some(error) = &self.error {
+            name = name.push(text(error).style(theme::Text::Color(Color::from_rgb(0.8, 0.2, 0.2))));
+        }
+
+        let rename_button = button("Rename").on_press(GUIMessage::Submit);
+        let cancel_button = button("Cancel").on_press(GUIMessage::Exit);
+        Card::new(
+            header,
+            container(column![name, row![rename_button, cancel_button]]),
+        )
+        .max_width(500.0)
+        .into()
+    }
+}
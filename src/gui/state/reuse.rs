@@ -0,0 +1,38 @@
+use iced::{
+    widget::{button, column, container, text},
+    Element,
+};
+
+use crate::gui::{gui_message::GUIMessage, widget::card::Card};
+
+// only the names of the entries are known here, the passwords never leave the vault
+#[derive(Debug, Clone)]
+pub struct ReuseState {
+    pub vault: String,
+    pub groups: Vec<Vec<String>>,
+}
+
+impl ReuseState {
+    pub fn new(vault: String, groups: Vec<Vec<String>>) -> Self {
+        Self { vault, groups }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!("Reused passwords in {}", self.vault));
+        let mut rows = column![].spacing(10);
+        if self.groups.is_empty() {
+            rows = rows.push(text("No entries share a password"));
+        } else {
+            rows = rows.push(text(
+                "Each group of entries shares the same password, consider changing all but one",
+            ));
+            for group in &self.groups {
+                rows = rows.push(text(format!("- {}", group.join(", "))));
+            }
+        }
+        let done_button = button("Done").on_press(GUIMessage::Submit);
+        Card::new(header, container(rows.push(done_button)))
+            .max_width(500.0)
+            .into()
+    }
+}
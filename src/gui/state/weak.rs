@@ -0,0 +1,48 @@
+use iced::{
+    theme,
+    widget::{button, column, container, row, text},
+    Color, Element,
+};
+
+use crate::{
+    gui::{gui_message::GUIMessage, widget::card::Card},
+    strength::Strength,
+};
+
+// the ratings are all that's known here, the passwords never leave the vault
+#[derive(Debug, Clone)]
+pub struct WeakState {
+    pub vault: String,
+    pub entries: Vec<(String, Strength)>,
+}
+
+impl WeakState {
+    pub fn new(vault: String, entries: Vec<(String, Strength)>) -> Self {
+        Self { vault, entries }
+    }
+
+    pub fn view(&self) -> Element<GUIMessage> {
+        let header = text(format!("Weak passwords in {}", self.vault));
+        let mut rows = column![].spacing(10);
+        if self.entries.is_empty() {
+            rows = rows.push(text("Every password is rated strong"));
+        }
+        for (key, strength) in &self.entries {
+            let color = match strength {
+                Strength::Weak => Color::from_rgb(0.8, 0.2, 0.2),
+                _ => Color::from_rgb(0.8, 0.6, 0.1),
+            };
+            rows = rows.push(
+                row![
+                    text(key),
+                    text(strength.to_string()).style(theme::Text::Color(color))
+                ]
+                .spacing(10),
+            );
+        }
+        let done_button = button("Done").on_press(GUIMessage::Submit);
+        Card::new(header, container(rows.push(done_button)))
+            .max_width(500.0)
+            .into()
+    }
+}
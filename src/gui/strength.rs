@@ -0,0 +1,30 @@
+use iced::{
+    theme,
+    widget::{progress_bar, row, text},
+    Element, Length,
+};
+
+use crate::{
+    gui::gui_message::GUIMessage,
+    strength::{entropy, Strength, MAX_BITS},
+};
+
+// a known estimate for how the password was generated is trusted over the guess when it's lower
+pub fn strength_bar<'a>(password: &str, estimate: Option<f32>) -> Element<'a, GUIMessage> {
+    let guess = entropy(password);
+    let bits = estimate.map_or(guess, |estimate| estimate.min(guess));
+    let strength = Strength::from_bits(bits);
+    let style = match strength {
+        Strength::Weak => theme::ProgressBar::Danger,
+        Strength::Fair => theme::ProgressBar::Primary,
+        Strength::Strong => theme::ProgressBar::Success,
+    };
+    let bar = progress_bar(0.0..=MAX_BITS, bits.min(MAX_BITS))
+        .height(8.0)
+        .width(Length::Fill)
+        .style(style);
+    row![bar, text(strength.to_string()).size(12)]
+        .spacing(5)
+        .align_items(iced::Alignment::Center)
+        .into()
+}
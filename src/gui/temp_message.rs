@@ -1,3 +1,5 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
 use iced::{
     widget::{container, text},
     Element,
@@ -5,9 +7,13 @@ use iced::{
 use secrecy::ExposeSecret;
 
 use crate::{
+    archive::Collision,
+    errors::SchemaError,
+    export::ExportFormat,
+    import::bitwarden::BitwardenImport,
     manager_message::ManagerMessage,
     message::Message,
-    store::{StoreChoice, StoreHash},
+    store::{CustomFields, Store, StoreChoice, StoreHash},
     Password,
 };
 
@@ -19,11 +25,25 @@ pub enum TempMessage {
     #[default]
     Empty,
     Delete(String, String),
+    DeleteMany(String, Vec<String>),
     DeleteVault(String),
     DeleteEmptyVault(String),
     Get(String, String),
-    New(String, String, StoreChoice, StoreHash),
-    Update(String, String, StoreChoice, StoreHash),
+    // reads the entry to start a new one from
+    Duplicate(String, String),
+    New(String, String, StoreChoice, StoreHash, CustomFields),
+    Update(String, String, StoreChoice, StoreHash, CustomFields),
+    Rename(String, String, String),
+    Export(String, ExportFormat),
+    Import(String, Vec<(String, Store)>),
+    ImportVaults(BitwardenImport),
+    // the archive's password once it's been given, the vault password is asked for after
+    BackupImport(PathBuf, Collision, Option<Password>),
+    CreateRecovery(String),
+    CheckReuse(String),
+    CheckStrength(String),
+    // the keys and the spec to generate from
+    Regenerate(String, Vec<String>, String),
 }
 
 impl TempMessage {
@@ -31,61 +51,169 @@ impl TempMessage {
         match self {
             Self::Empty => false,
             Self::Delete(..) => true,
+            Self::DeleteMany(..) => true,
+            Self::Regenerate(..) => true,
             Self::Get(..) => true,
+            Self::Duplicate(..) => true,
             Self::New(..) => true,
             Self::Update(..) => true,
+            Self::Rename(..) => true,
+            Self::Export(..) => true,
+            Self::Import(..) => true,
+            Self::ImportVaults(..) => true,
+            Self::BackupImport(..) => true,
+            Self::CreateRecovery(..) => true,
+            Self::CheckReuse(..) => true,
+            Self::CheckStrength(..) => true,
             Self::DeleteVault(..) => true,
             Self::DeleteEmptyVault(..) => false,
         }
     }
 
+    pub fn vault(&self) -> Option<&str> {
+        match self {
+            Self::Empty | Self::ImportVaults(_) | Self::BackupImport(..) => None,
+            Self::Delete(vault, _)
+            | Self::DeleteMany(vault, _)
+            | Self::Regenerate(vault, ..)
+            | Self::DeleteVault(vault)
+            | Self::DeleteEmptyVault(vault)
+            | Self::Get(vault, _)
+            | Self::Duplicate(vault, _)
+            | Self::New(vault, ..)
+            | Self::Update(vault, ..)
+            | Self::Rename(vault, ..)
+            | Self::Export(vault, _)
+            | Self::Import(vault, _)
+            | Self::CreateRecovery(vault)
+            | Self::CheckReuse(vault)
+            | Self::CheckStrength(vault) => Some(vault),
+        }
+    }
+
     pub fn complete(&self) -> bool {
         match self {
             Self::Empty => true,
-            Self::New(_, name, _, fields) => {
-                let mut filled = true;
-                for (_, value) in fields.iter() {
-                    if value.expose_secret().is_empty() {
-                        filled = false;
-                        break;
-                    }
-                }
-                !name.is_empty() && filled
-            }
-            Self::Update(_, name, _, fields) => {
-                let mut filled = true;
-                for (_, value) in fields.iter() {
-                    if value.expose_secret().is_empty() {
-                        filled = false;
-                        break;
-                    }
-                }
-                !name.is_empty() && filled
+            Self::New(_, name, choice, fields, custom) => {
+                !name.is_empty() && Self::filled(choice, fields) && Self::named(custom)
+            }
+            Self::Update(_, name, choice, fields, custom) => {
+                !name.is_empty() && Self::filled(choice, fields) && Self::named(custom)
             }
             Self::Get(_, name) => !name.is_empty(),
+            Self::Duplicate(_, name) => !name.is_empty(),
             Self::Delete(_, name) => !name.is_empty(),
+            Self::DeleteMany(_, keys) => !keys.is_empty(),
+            Self::Regenerate(_, keys, _) => !keys.is_empty(),
+            Self::Rename(_, old, new) => !new.is_empty() && old != new,
+            Self::Export(..) => true,
+            Self::Import(_, entries) => !entries.is_empty(),
+            Self::ImportVaults(import) => import.entries() > 0,
+            Self::BackupImport(..) => true,
+            Self::CreateRecovery(..) => true,
+            Self::CheckReuse(..) => true,
+            Self::CheckStrength(..) => true,
             Self::DeleteVault(..) => true,
             Self::DeleteEmptyVault(..) => true,
         }
     }
 
+    // what counts as filled depends on the kind of entry, e.g. an empty note is fine
+    fn filled(choice: &StoreChoice, fields: &StoreHash) -> bool {
+        fields
+            .iter()
+            .all(|(key, value)| choice.is_optional(key) || !value.expose_secret().is_empty())
+    }
+
+    // custom fields need a name and the names can't repeat
+    fn named(custom: &CustomFields) -> bool {
+        let mut names = BTreeSet::new();
+        custom
+            .iter()
+            .all(|(name, _)| !name.trim().is_empty() && names.insert(name.trim()))
+    }
+
+    // the fields can all be filled in and still not make a valid entry
+    pub fn valid(&self) -> Result<(), SchemaError> {
+        match self {
+            Self::New(_, _, choice, fields, _) => choice.convert(fields).map(|_| ()),
+            Self::Update(_, _, choice, fields, _) => choice.convert(fields).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+
     pub fn with_password(&self, password: Password) -> ManagerMessage {
         match self {
             Self::Delete(vault, key) => ManagerMessage::VaultMessage(
                 vault.into(),
                 Message::Delete(password, key.to_string()),
             ),
-            Self::Get(vault, key) => {
+            Self::DeleteMany(vault, keys) => ManagerMessage::VaultMessage(
+                vault.into(),
+                Message::DeleteMany(password, keys.clone()),
+            ),
+            Self::Regenerate(vault, keys, spec) => ManagerMessage::Regenerate {
+                vault: vault.into(),
+                password,
+                keys: keys.clone(),
+                spec: spec.clone(),
+            },
+            Self::Get(vault, key) | Self::Duplicate(vault, key) => {
                 ManagerMessage::VaultMessage(vault.into(), Message::Get(password, key.to_string()))
             }
-            Self::New(vault, key, choice, value) => ManagerMessage::VaultMessage(
+            Self::New(vault, key, choice, value, custom) => ManagerMessage::VaultMessage(
                 vault.into(),
-                Message::Update(password, key.clone(), choice.convert(value).unwrap()),
+                Message::Update(
+                    password,
+                    key.clone(),
+                    choice.convert(value).unwrap().with_fields(custom.clone()),
+                ),
             ),
-            Self::Update(vault, key, choice, value) => ManagerMessage::VaultMessage(
+            Self::Update(vault, key, choice, value, custom) => ManagerMessage::VaultMessage(
                 vault.into(),
-                Message::Update(password, key.clone(), choice.convert(value).unwrap()),
+                Message::Update(
+                    password,
+                    key.clone(),
+                    choice.convert(value).unwrap().with_fields(custom.clone()),
+                ),
             ),
+            Self::Rename(vault, old, new) => ManagerMessage::VaultMessage(
+                vault.into(),
+                Message::Rename(password, old.to_string(), new.to_string()),
+            ),
+            Self::Export(vault, format) => ManagerMessage::Export {
+                vault: vault.into(),
+                password,
+                format: *format,
+            },
+            Self::Import(vault, entries) => ManagerMessage::Import {
+                vault: vault.into(),
+                password,
+                entries: entries.clone(),
+                overwrite: false,
+            },
+            Self::ImportVaults(import) => ManagerMessage::ImportVaults {
+                password,
+                vaults: import.vaults.clone(),
+                overwrite: false,
+            },
+            Self::BackupImport(path, collision, archive_password) => ManagerMessage::BackupImport {
+                path: path.clone(),
+                archive_password: archive_password.clone().unwrap_or_else(|| password.clone()),
+                password,
+                collision: *collision,
+            },
+            Self::CreateRecovery(vault) => {
+                ManagerMessage::VaultMessage(vault.into(), Message::CreateRecovery(password))
+            }
+            Self::CheckReuse(vault) => ManagerMessage::CheckReuse {
+                vault: vault.into(),
+                password,
+            },
+            Self::CheckStrength(vault) => ManagerMessage::CheckStrength {
+                vault: vault.into(),
+                password,
+            },
             Self::DeleteVault(vault) => ManagerMessage::DeleteVault(vault.into(), password),
             Self::DeleteEmptyVault(vault) => ManagerMessage::DeleteEmptyVault(vault.into()),
             Self::Empty => ManagerMessage::Info,
@@ -98,6 +226,22 @@ impl TempMessage {
                 let info = text(format!("Working on deleting {} in {}", key, vault));
                 container(info).into()
             }
+            TempMessage::DeleteMany(vault, keys) => {
+                let info = text(format!(
+                    "Working on deleting {} entries in {}",
+                    keys.len(),
+                    vault
+                ));
+                container(info).into()
+            }
+            TempMessage::Regenerate(vault, keys, _) => {
+                let info = text(format!(
+                    "Working on regenerating {} passwords in {}",
+                    keys.len(),
+                    vault
+                ));
+                container(info).into()
+            }
             TempMessage::DeleteVault(vault) => {
                 let info = text(format!("Working on deleting {}", vault));
                 container(info).into()
@@ -110,14 +254,60 @@ impl TempMessage {
                 let info = text(format!("Working on getting {} in {}", key, vault));
                 container(info).into()
             }
-            TempMessage::New(vault, key, _, _) => {
+            TempMessage::Duplicate(vault, key) => {
+                let info = text(format!("Working on duplicating {} in {}", key, vault));
+                container(info).into()
+            }
+            TempMessage::New(vault, key, ..) => {
                 let info = text(format!("Working on a new entry {} in {}", key, vault));
                 container(info).into()
             }
-            TempMessage::Update(vault, key, _, _) => {
+            TempMessage::Update(vault, key, ..) => {
                 let info = text(format!("Working on updating entry {} in {}", key, vault));
                 container(info).into()
             }
+            TempMessage::Rename(vault, old, new) => {
+                let info = text(format!(
+                    "Working on renaming {} to {} in {}",
+                    old, new, vault
+                ));
+                container(info).into()
+            }
+            TempMessage::Export(vault, format) => {
+                let info = text(format!("Working on exporting {} as {}", vault, format));
+                container(info).into()
+            }
+            TempMessage::Import(vault, entries) => {
+                let info = text(format!(
+                    "Working on importing {} entries into {}",
+                    entries.len(),
+                    vault
+                ));
+                container(info).into()
+            }
+            TempMessage::ImportVaults(import) => {
+                let info = text(format!("Working on importing, {}", import.summary()));
+                container(info).into()
+            }
+            TempMessage::BackupImport(path, ..) => {
+                let info = text(format!("Working on restoring {}", path.display()));
+                container(info).into()
+            }
+            TempMessage::CreateRecovery(vault) => {
+                let info = text(format!("Working on a recovery key for {}", vault));
+                container(info).into()
+            }
+            TempMessage::CheckReuse(vault) => {
+                let info = text(format!(
+                    "Working on checking {} for reused passwords",
+                    vault
+                ));
+                container(info).into()
+            }
+            TempMessage::CheckStrength(vault) => {
+                let info = text(format!("Working on checking {} for weak passwords", vault));
+                container(info).into()
+            }
             Self::Empty => {
                 let info = text("Working on nothing");
                 container(info).into()
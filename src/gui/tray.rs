@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use iced::{
+    futures::SinkExt,
+    subscription::{self, Subscription},
+};
+use tray_icon::{
+    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    Icon, TrayIcon, TrayIconBuilder,
+};
+
+// how often the menu events are looked for, they don't come through iced's own events
+const POLL_TIME: Duration = Duration::from_millis(100);
+// how many of the most recently used entries are offered for copying
+pub const RECENT_ENTRIES: usize = 10;
+const ICON_SIZE: u32 = 32;
+
+const SHOW_ID: &str = "show";
+const QUIT_ID: &str = "quit";
+const COPY_PREFIX: &str = "copy-";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrayAction {
+    Show,
+    Quit,
+    // vault and key of the entry to copy the password of
+    Copy(String, String),
+}
+
+// the icon stays in the tray for as long as this is kept around
+pub struct Tray {
+    icon: TrayIcon,
+    // vault and key for each copy item, in the order they are listed
+    recent: Vec<(String, String)>,
+}
+
+impl Tray {
+    pub fn new() -> anyhow::Result<Self> {
+        let icon = TrayIconBuilder::new()
+            .with_tooltip("Pants")
+            .with_icon(icon()?)
+            .with_menu(Box::new(menu(&[])?))
+            .build()
+            .map_err(|e| anyhow::anyhow!("Couldn't add the tray icon: {}", e))?;
+        Ok(Self {
+            icon,
+            recent: vec![],
+        })
+    }
+
+    // only rebuilds the menu when the entries changed
+    pub fn set_recent(&mut self, recent: Vec<(String, String)>) {
+        if recent == self.recent {
+            return;
+        }
+        if let Ok(menu) = menu(&recent) {
+            self.icon.set_menu(Some(Box::new(menu)));
+            self.recent = recent;
+        }
+    }
+
+    pub fn action(&self, id: &str) -> Option<TrayAction> {
+        match id {
+            SHOW_ID => Some(TrayAction::Show),
+            QUIT_ID => Some(TrayAction::Quit),
+            _ => {
+                let index: usize = id.strip_prefix(COPY_PREFIX)?.parse().ok()?;
+                let (vault, key) = self.recent.get(index)?;
+                Some(TrayAction::Copy(vault.clone(), key.clone()))
+            }
+        }
+    }
+}
+
+fn menu(recent: &[(String, String)]) -> anyhow::Result<Menu> {
+    let menu = Menu::new();
+    menu.append(&MenuItem::with_id(SHOW_ID, "Show Pants", true, None))?;
+    menu.append(&PredefinedMenuItem::separator())?;
+    if recent.is_empty() {
+        menu.append(&MenuItem::new("No recent entries", false, None))?;
+    }
+    for (i, (vault, key)) in recent.iter().enumerate() {
+        let label = format!("Copy {} ({})", key, vault);
+        menu.append(&MenuItem::with_id(
+            format!("{}{}", COPY_PREFIX, i),
+            label,
+            true,
+            None,
+        ))?;
+    }
+    menu.append(&PredefinedMenuItem::separator())?;
+    menu.append(&MenuItem::with_id(QUIT_ID, "Quit", true, None))?;
+    Ok(menu)
+}
+
+// a plain filled circle since there is no image to load
+fn icon() -> anyhow::Result<Icon> {
+    let center = ICON_SIZE as f32 / 2.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let (dx, dy) = (x as f32 + 0.5 - center, y as f32 + 0.5 - center);
+            let alpha = if dx.hypot(dy) <= center - 1.0 { 255 } else { 0 };
+            rgba.extend_from_slice(&[0x3b, 0x82, 0xf6, alpha]);
+        }
+    }
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+// gives the id of the menu item that was picked
+pub fn listen() -> Subscription<String> {
+    struct Listen;
+    subscription::channel(
+        std::any::TypeId::of::<Listen>(),
+        10,
+        |mut output| async move {
+            let receiver = MenuEvent::receiver();
+            loop {
+                while let Ok(event) = receiver.try_recv() {
+                    let _ = output.send(event.id.0).await;
+                }
+                async_std::task::sleep(POLL_TIME).await;
+            }
+        },
+    )
+}
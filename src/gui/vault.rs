@@ -1,12 +1,12 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use iced::{
     alignment, theme,
     widget::{button, column, container, row, text, tooltip},
-    Element, Length,
+    Alignment, Element, Length,
 };
 
-use crate::schema::Schema;
+use crate::{config::client_config::SortOrder, info::Info, schema::Schema, utils};
 
 use super::{
     entry::{Entry, EntryMessage},
@@ -20,12 +20,26 @@ pub struct Vault {
     pub expanded: bool,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
 #[derive(Debug, Clone)]
 pub enum VaultMessage {
     Entry(EntryMessage, String),
     Toggle,
     NewEntry,
+    ToggleFavorite(String),
+    Move(String, Direction),
+    ToggleSelect,
+    DeleteSelected,
+    RegenerateSelected,
+    Rename,
     Delete,
+    // forget the vault's password
+    Lock,
 }
 
 impl Vault {
@@ -51,21 +65,131 @@ impl Vault {
         self
     }
 
-    pub fn update(&mut self, schema: &Schema) {
+    // entries are stale once they haven't been modified for `rotation_days`, ones that were never
+    // modified since timestamps were kept can't be judged
+    pub fn update(&mut self, schema: &Schema, info: &Info, rotation_days: u64) {
+        let favorites = info.favorites(&self.name);
         self.entries = schema
             .data
             .iter()
             .map(|(key, value)| {
-                (
-                    key.to_string(),
-                    Entry::new(key.to_string(), value.to_string()),
-                )
+                let mut entry =
+                    Entry::new(key.to_string(), *value).favorite(favorites.contains(key));
+                entry.used = info.used(&self.name, key);
+                entry.modified = info.modified(&self.name, key);
+                entry.order = info.order(&self.name, key);
+                entry.tags = info.tags(&self.name, key);
+                entry.stale = rotation_days > 0
+                    && entry
+                        .modified
+                        .as_ref()
+                        .and_then(|date| utils::read_date(date).ok())
+                        .is_some_and(|date| {
+                            utils::now().signed_duration_since(date).num_days()
+                                >= rotation_days as i64
+                        });
+                (key.to_string(), entry)
             })
             .collect();
     }
 
-    pub fn view(&self) -> Element<VaultMessage> {
-        let name = text(self.name.to_string()).size(20).width(Length::Fill);
+    // the map already keeps them alphabetical, the most recent go first and the ones that were
+    // never used or modified go last, the sorts are stable so ties stay alphabetical
+    fn sorted(&self, sort_order: SortOrder) -> Vec<&Entry> {
+        let mut entries = self.entries.values().collect::<Vec<_>>();
+        match sort_order {
+            SortOrder::Alphabetical => {}
+            SortOrder::RecentlyUsed => entries.sort_by(|a, b| b.used.cmp(&a.used)),
+            SortOrder::RecentlyModified => entries.sort_by(|a, b| b.modified.cmp(&a.modified)),
+            SortOrder::Custom => entries.sort_by_key(|e| (e.order.is_none(), e.order)),
+        }
+        entries.sort_by_key(|e| !e.favorite);
+        entries
+    }
+
+    // the order of every entry after moving one, favorites can't be moved past the others since
+    // they are always shown first
+    pub fn moved(&self, key: &str, direction: Direction) -> Vec<String> {
+        let entries = self.sorted(SortOrder::Custom);
+        let mut keys = entries
+            .iter()
+            .map(|e| e.key.to_string())
+            .collect::<Vec<_>>();
+        if let Some(i) = keys.iter().position(|k| k == key) {
+            let j = match direction {
+                Direction::Up => i.checked_sub(1),
+                Direction::Down => Some(i + 1).filter(|j| *j < keys.len()),
+            };
+            if let Some(j) = j {
+                if entries[i].favorite == entries[j].favorite {
+                    keys.swap(i, j);
+                }
+            }
+        }
+        keys
+    }
+
+    // e.g. `12 entries, updated 3 days ago`, the timestamps sort the same as the dates so the
+    // newest one is just the largest
+    fn summary(&self) -> String {
+        let count = self.entries.len();
+        let plural = if count == 1 { "entry" } else { "entries" };
+        let newest = self
+            .entries
+            .values()
+            .filter_map(|e| e.modified.as_ref())
+            .max()
+            .and_then(|date| utils::read_date(date).ok());
+        match newest {
+            Some(date) => format!("{} {}, updated {}", count, plural, utils::time_since(date)),
+            None => format!("{} {}", count, plural),
+        }
+    }
+
+    fn name_matches(&self, search: &str) -> bool {
+        self.name.to_lowercase().contains(search)
+    }
+
+    // whether the vault should show up at all for the current search
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+        self.name_matches(&search)
+            || self
+                .entries
+                .keys()
+                .any(|key| key.to_lowercase().contains(&search))
+    }
+
+    // no tag shows everything, otherwise only the vaults and entries that have it
+    pub fn has_tag(&self, tag: Option<&str>) -> bool {
+        tag.map_or(true, |tag| {
+            self.entries.values().any(|e| e.tags.contains(tag))
+        })
+    }
+
+    // an empty search shows the vault normally, otherwise it is shown expanded with only the
+    // entries that match unless the vault's name itself matches
+    pub fn view(
+        &self,
+        search: &str,
+        tag: Option<&str>,
+        sort_order: SortOrder,
+        selection: Option<&BTreeSet<String>>,
+        unlocked: bool,
+        narrow: bool,
+    ) -> Element<VaultMessage> {
+        let search = search.to_lowercase();
+        let show_all = search.is_empty() || self.name_matches(&search);
+        let name = column![
+            text(self.name.to_string()).size(20),
+            text(self.summary()).size(12)
+        ]
+        .width(Length::Fill);
+        let rename_button = tooltip(
+            button("Rename").on_press(VaultMessage::Rename),
+            "Rename vault",
+            tooltip::Position::Bottom,
+        );
         let delete_button = tooltip(
             button("X")
                 .on_press(VaultMessage::Delete)
@@ -81,15 +205,77 @@ impl Vault {
         // .vertical_alignment(alignment::Vertical::Center)
         // .font(Font::MONOSPACE)
         // .width(Length::Shrink);
-        let header = row![name, delete_button];
+        // whether the next sensitive action asks for the password
+        let lock = if unlocked {
+            tooltip(
+                button(text("\u{1F513}")).on_press(VaultMessage::Lock),
+                "Unlocked, press to lock",
+                tooltip::Position::Bottom,
+            )
+        } else {
+            tooltip(
+                button(text("\u{1F512}")).style(theme::Button::Text),
+                "Locked",
+                tooltip::Position::Bottom,
+            )
+        };
+        let mut header = row![lock, name];
+        if let Some(selection) = selection {
+            header = header.push(
+                button(text(format!("Regenerate selected ({})", selection.len()))).on_press_maybe(
+                    (!selection.is_empty()).then_some(VaultMessage::RegenerateSelected),
+                ),
+            );
+            header = header.push(
+                button(text(format!("Delete selected ({})", selection.len())))
+                    .style(theme::Button::Destructive)
+                    .on_press_maybe(
+                        (!selection.is_empty()).then_some(VaultMessage::DeleteSelected),
+                    ),
+            );
+        }
+        let select_label = if selection.is_some() {
+            "Cancel"
+        } else {
+            "Select"
+        };
+        let select_button = button(select_label).on_press(VaultMessage::ToggleSelect);
+        let header = header
+            .push(select_button)
+            .push(rename_button)
+            .push(delete_button);
+        let movable = sort_order == SortOrder::Custom;
         let mut entries = self
-            .entries
-            .values()
+            .sorted(sort_order)
+            .into_iter()
+            .filter(|e| show_all || e.key.to_lowercase().contains(&search))
+            .filter(|e| tag.map_or(true, |tag| e.tags.contains(tag)))
             .map(|e| {
-                e.view()
-                    .map(move |message| VaultMessage::Entry(message, e.key.clone()))
+                let selected = selection.map(|selection| selection.contains(&e.key));
+                e.view(selected, movable, narrow)
+                    .map(move |message| match message {
+                        EntryMessage::ToggleFavorite => VaultMessage::ToggleFavorite(e.key.clone()),
+                        EntryMessage::MoveUp => VaultMessage::Move(e.key.clone(), Direction::Up),
+                        EntryMessage::MoveDown => {
+                            VaultMessage::Move(e.key.clone(), Direction::Down)
+                        }
+                        message => VaultMessage::Entry(message, e.key.clone()),
+                    })
             })
             .collect::<Vec<_>>();
+        // the entries come from the schema, so a fresh vault has none
+        if self.entries.is_empty() {
+            let placeholder = row![
+                text("No entries yet").width(Length::Fill),
+                button("Add one").on_press(VaultMessage::NewEntry)
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center);
+            let content = container(placeholder).padding(10);
+            return Expand::new(header, content, self.expanded || selection.is_some())
+                .on_press(VaultMessage::Toggle)
+                .into();
+        }
         entries.push(
             container(
                 button(
@@ -107,8 +293,12 @@ impl Vault {
         );
         let content = container(column(entries)).padding(10);
 
-        Expand::new(header, content, self.expanded)
-            .on_press(VaultMessage::Toggle)
-            .into()
+        Expand::new(
+            header,
+            content,
+            self.expanded || !search.is_empty() || tag.is_some() || selection.is_some(),
+        )
+        .on_press(VaultMessage::Toggle)
+        .into()
     }
 }
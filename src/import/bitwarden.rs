@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::{errors::ImportError, store::Store, totp::Totp, utils};
+
+// where items outside of any folder end up when grouping by folder
+pub const DEFAULT_VAULT: &str = "bitwarden";
+
+const LOGIN: u8 = 1;
+const SECURE_NOTE: u8 = 2;
+
+// only the parts of bitwarden's unencrypted json export that have somewhere to go
+#[derive(Debug, Deserialize)]
+struct Export {
+    #[serde(default)]
+    folders: Vec<Folder>,
+    #[serde(default)]
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Folder {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Item {
+    #[serde(rename = "folderId")]
+    folder_id: Option<String>,
+    #[serde(rename = "type")]
+    kind: u8,
+    name: Option<String>,
+    notes: Option<String>,
+    login: Option<Login>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Login {
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+    uris: Option<Vec<Uri>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Uri {
+    uri: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BitwardenImport {
+    pub vaults: BTreeMap<String, Vec<(String, Store)>>,
+    // cards and identities have no matching store
+    pub unsupported: usize,
+    // entries that couldn't be converted, e.g. an unreadable totp secret
+    pub failed: Vec<String>,
+}
+
+impl BitwardenImport {
+    pub fn entries(&self) -> usize {
+        self.vaults.values().map(|entries| entries.len()).sum()
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Found {} entries for {} vaults",
+            self.entries(),
+            self.vaults.len()
+        );
+        if self.unsupported > 0 {
+            summary.push_str(&format!(
+                ", {} unsupported items left out",
+                self.unsupported
+            ));
+        }
+        if !self.failed.is_empty() {
+            summary.push_str(&format!(", could not convert: {}", self.failed.join(", ")));
+        }
+        summary
+    }
+
+    // item names don't have to be unique in bitwarden, so repeats get a number added
+    fn insert(&mut self, vault: &str, key: String, value: Store) {
+        let entries = self.vaults.entry(vault.to_string()).or_default();
+        let taken = |key: &str| entries.iter().any(|(k, _)| k == key);
+        let mut unique = key.clone();
+        let mut count = 1;
+        while taken(&unique) {
+            count += 1;
+            unique = format!("{} ({})", key, count);
+        }
+        entries.push((unique, value));
+    }
+}
+
+// folders nest with a '/', which can't go in a vault name, so the levels are joined another way
+// and anything still unusable falls back to the default vault
+fn folder_vault(name: &str) -> String {
+    let name = name
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" - ")
+        .replace(char::is_control, "");
+    utils::vault_name(&name).unwrap_or_else(|_| DEFAULT_VAULT.to_string())
+}
+
+// groups the items by their folder unless a vault is given to put everything in
+pub fn parse(data: &str, vault: Option<&str>) -> Result<BitwardenImport, ImportError> {
+    let export: Export =
+        serde_json::from_str(data).map_err(|e| ImportError::Malformed(e.to_string()))?;
+    let folders = export
+        .folders
+        .into_iter()
+        .map(|folder| (folder.id, folder_vault(&folder.name)))
+        .collect::<BTreeMap<_, _>>();
+    let mut result = BitwardenImport::default();
+    for item in export.items {
+        let vault = match vault {
+            Some(vault) => vault.to_string(),
+            None => item
+                .folder_id
+                .as_ref()
+                .and_then(|id| folders.get(id))
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_VAULT.to_string()),
+        };
+        let name = item
+            .name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| "Untitled".to_string());
+        let notes = item.notes.filter(|notes| !notes.is_empty());
+        match (item.kind, item.login) {
+            (LOGIN, login) => {
+                let login = login.unwrap_or(Login {
+                    username: None,
+                    password: None,
+                    totp: None,
+                    uris: None,
+                });
+                let url = login
+                    .uris
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find_map(|uri| uri.uri)
+                    .unwrap_or_default();
+                result.insert(
+                    &vault,
+                    name.clone(),
+                    Store::Login(
+                        login.username.unwrap_or_default().into(),
+                        login.password.unwrap_or_default().into(),
+                        url.into(),
+                    ),
+                );
+                if let Some(totp) = login.totp.filter(|totp| !totp.is_empty()) {
+                    let totp = if totp.starts_with("otpauth://") {
+                        Totp::from_uri(&totp)
+                    } else {
+                        Totp::from_fields(&totp, "", "", "")
+                    };
+                    match totp {
+                        Ok(totp) => {
+                            result.insert(&vault, format!("{} TOTP", name), Store::Totp(totp))
+                        }
+                        Err(_) => result.failed.push(format!("{} TOTP", name)),
+                    }
+                }
+                // logins can carry notes too, they get their own entry so nothing is lost
+                if let Some(notes) = notes {
+                    result.insert(&vault, format!("{} notes", name), Store::Note(notes.into()));
+                }
+            }
+            (SECURE_NOTE, _) => {
+                result.insert(&vault, name, Store::Note(notes.unwrap_or_default().into()));
+            }
+            _ => result.unsupported += 1,
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,91 @@
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use secrecy::Secret;
+
+use crate::{
+    errors::ImportError,
+    export::FIELDS,
+    store::{Store, StoreChoice},
+};
+
+pub mod bitwarden;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+}
+
+impl ImportFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extensions() -> [&'static str; 2] {
+        ["csv", "json"]
+    }
+
+    // the same layout as exports, a key, an optional type, and then the fields of the entry,
+    // rows without a recognized type are taken as username/password entries
+    pub fn parse(&self, data: &str) -> Result<Vec<(String, Store)>, ImportError> {
+        let rows = match self {
+            Self::Csv => Self::parse_csv(data)?,
+            Self::Json => serde_json::from_str::<Vec<HashMap<String, String>>>(data)
+                .map_err(|e| ImportError::Malformed(e.to_string()))?,
+        };
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, row)| Self::convert(i + 1, row))
+            .collect()
+    }
+
+    fn parse_csv(data: &str) -> Result<Vec<HashMap<String, String>>, ImportError> {
+        let mut reader = csv::Reader::from_reader(data.as_bytes());
+        reader
+            .deserialize()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ImportError::Malformed(e.to_string()))
+    }
+
+    fn convert(
+        row_number: usize,
+        mut row: HashMap<String, String>,
+    ) -> Result<(String, Store), ImportError> {
+        let key = row
+            .remove("key")
+            .or_else(|| row.remove("name"))
+            .filter(|key| !key.is_empty())
+            .ok_or(ImportError::MissingKey(row_number))?;
+        let choice = row
+            .remove("type")
+            .and_then(|repr| StoreChoice::from_repr(&repr))
+            .unwrap_or_default();
+        // columns that don't apply to the type are ignored and missing ones are left empty
+        let mut fields = choice.convert_default().as_hash();
+        for field in FIELDS {
+            // optional fields like notes aren't in the defaults
+            if let Some(value) = row.remove(field) {
+                if choice.field_kind(field).is_some() {
+                    fields.insert(field.to_string(), Secret::new(value));
+                }
+            }
+        }
+        let store = choice
+            .convert(&fields)
+            .map_err(|e| ImportError::BadEntry(key.clone(), e))?;
+        Ok((key, store))
+    }
+}
+
+impl Display for ImportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::Json => write!(f, "JSON"),
+        }
+    }
+}
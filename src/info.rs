@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, fmt::Display};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +10,24 @@ use crate::schema::Schema;
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Info {
     pub data: BTreeMap<String, Schema>,
+    // password specs for the vaults that override the client's
+    #[serde(default)]
+    pub specs: BTreeMap<String, String>,
+    // favorited entries of the vaults
+    #[serde(default)]
+    pub favorites: BTreeMap<String, BTreeSet<String>>,
+    // when the entries of the vaults were last used and modified, only for the vaults unlocked
+    // since the manager started since they're kept encrypted
+    #[serde(default)]
+    pub used: BTreeMap<String, BTreeMap<String, String>>,
+    #[serde(default)]
+    pub modified: BTreeMap<String, BTreeMap<String, String>>,
+    // custom positions of the entries of the vaults
+    #[serde(default)]
+    pub order: BTreeMap<String, BTreeMap<String, usize>>,
+    // tags of the entries of the vaults
+    #[serde(default)]
+    pub tags: BTreeMap<String, BTreeMap<String, BTreeSet<String>>>,
 }
 
 impl Info {
@@ -16,11 +37,54 @@ impl Info {
     pub fn insert(&mut self, key: String, value: Schema) {
         self.data.insert(key, value);
     }
+    pub fn spec(&self, key: &str) -> Option<&String> {
+        self.specs.get(key)
+    }
+    pub fn favorites(&self, key: &str) -> BTreeSet<String> {
+        self.favorites.get(key).cloned().unwrap_or_default()
+    }
+    pub fn used(&self, vault: &str, key: &str) -> Option<String> {
+        self.used.get(vault).and_then(|used| used.get(key)).cloned()
+    }
+    pub fn modified(&self, vault: &str, key: &str) -> Option<String> {
+        self.modified
+            .get(vault)
+            .and_then(|modified| modified.get(key))
+            .cloned()
+    }
+    pub fn tags(&self, vault: &str, key: &str) -> BTreeSet<String> {
+        self.tags
+            .get(vault)
+            .and_then(|tags| tags.get(key))
+            .cloned()
+            .unwrap_or_default()
+    }
+    // every tag in use in any vault, for filtering by
+    pub fn all_tags(&self) -> BTreeSet<String> {
+        self.tags
+            .values()
+            .flat_map(|tags| tags.values().flatten().cloned())
+            .collect()
+    }
+    pub fn order(&self, vault: &str, key: &str) -> Option<usize> {
+        self.order
+            .get(vault)
+            .and_then(|order| order.get(key))
+            .copied()
+    }
 }
 
 impl From<BTreeMap<String, Schema>> for Info {
     fn from(value: BTreeMap<String, Schema>) -> Self {
-        Self { data: value }
+        Self {
+            data: value,
+            specs: BTreeMap::new(),
+            favorites: BTreeMap::new(),
+            used: BTreeMap::new(),
+            modified: BTreeMap::new(),
+            order: BTreeMap::new(),
+            tags: BTreeMap::new(),
+        }
     }
 }
 
@@ -117,21 +117,31 @@
 
 use secrecy::Secret;
 pub mod action;
+pub mod archive;
+pub mod audit;
+pub mod breach;
+pub mod card;
 pub mod cli;
 pub mod command;
 pub mod config;
 pub mod errors;
+pub mod export;
 pub mod file;
+pub mod generate;
 pub mod gui;
+pub mod import;
 pub mod info;
 pub mod manager_message;
 pub mod message;
 pub mod operation;
 pub mod output;
 pub mod reads;
+pub mod reuse;
 pub mod schema;
 pub mod secure;
 pub mod store;
+pub mod strength;
+pub mod totp;
 pub mod utils;
 pub mod vault;
 
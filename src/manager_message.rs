@@ -1,12 +1,124 @@
-use crate::{message::Message, Password};
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{archive::Collision, export::ExportFormat, message::Message, store::Store, Password};
+
+// bumped whenever messages or outputs change in a way an older client or manager would misread
+pub const PROTOCOL_VERSION: u32 = 2;
 
 #[derive(Debug)]
 pub enum ManagerMessage {
     Empty,
+    // the client's protocol version, sent first thing after connecting
+    Handshake(u32),
     NewVault(String),
     DeleteVault(String, Password),
     DeleteEmptyVault(String),
+    RenameVault {
+        old: String,
+        new: String,
+    },
+    // remakes the vault's key with the configured costs the next time it's unlocked
+    Reharden(String),
+    SetPasswordSpec {
+        vault: String,
+        spec: Option<String>,
+    },
+    SetFavorite {
+        vault: String,
+        key: String,
+        favorite: bool,
+    },
+    SetTags {
+        vault: String,
+        key: String,
+        tags: Vec<String>,
+    },
+    // the full order of the vault's entries, positions are taken from the index
+    SetOrder {
+        vault: String,
+        keys: Vec<String>,
+    },
+    Export {
+        vault: String,
+        password: Password,
+        format: ExportFormat,
+    },
+    // collisions with existing entries are skipped unless overwriting
+    Import {
+        vault: String,
+        password: Password,
+        entries: Vec<(String, Store)>,
+        overwrite: bool,
+    },
+    // imports into several vaults at once, creating the ones that don't exist yet
+    ImportVaults {
+        password: Password,
+        vaults: BTreeMap<String, Vec<(String, Store)>>,
+        overwrite: bool,
+    },
+    // writes the vaults to a single encrypted file, each vault is opened with its own password
+    // and the archive gets another
+    BackupExport {
+        vaults: BTreeMap<String, Password>,
+        archive_password: Password,
+        path: PathBuf,
+    },
+    // backs up the vault to an archive under its own password when the last automatic backup is
+    // older than the interval, the backup directory defaults to next to the vaults
+    ScheduledBackup {
+        vault: String,
+        password: Password,
+        dir: Option<PathBuf>,
+        interval_hours: u64,
+        keep: usize,
+    },
+    // the password opens the archive and is used for the vaults it restores into
+    BackupImport {
+        path: PathBuf,
+        // the restored vaults are written with this one, not the archive's
+        password: Password,
+        archive_password: Password,
+        collision: Collision,
+    },
+    // new passwords from the spec for each of the entries, everything else about them is kept
+    Regenerate {
+        vault: String,
+        password: Password,
+        keys: Vec<String>,
+        spec: String,
+    },
+    // hashes the entry's password so it can be checked against known breaches
+    CheckBreach {
+        vault: String,
+        key: String,
+        password: Password,
+    },
+    // groups the entries that share a password, none of the passwords are given back
+    CheckReuse {
+        vault: String,
+        password: Password,
+    },
+    // rates the entries' passwords, only the names and ratings of the weaker ones are given back
+    CheckStrength {
+        vault: String,
+        password: Password,
+    },
     List,
     Info,
+    // the manager's own version, it can differ from the client's
+    Version,
+    // the most recent events from the audit log
+    AuditLog(usize),
     VaultMessage(String, Message),
+    ChangePassword {
+        vault: String,
+        old: Password,
+        new: Password,
+    },
+    // sets a new password using the recovery key made when the vault was created
+    Recover {
+        vault: String,
+        recovery: Password,
+        new: Password,
+    },
 }
@@ -4,10 +4,17 @@ use crate::{file::BackupFile, store::Store, Password};
 #[derive(Debug, Clone)]
 pub enum Message {
     Get(Password, String),
+    GetAll(Password),
     Update(Password, String, Store),
+    UpdateMany(Password, Vec<(String, Store)>),
     Delete(Password, String),
+    DeleteMany(Password, Vec<String>),
+    Rename(Password, String, String),
     Backup(Password),
     Rotate(Password, Password),
+    // the recovery key and the new password
+    Recover(Password, Password),
+    CreateRecovery(Password),
     Restore(Password, Password, BackupFile),
     Schema,
     BackupList,
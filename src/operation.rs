@@ -7,6 +7,7 @@ use crate::{
 pub enum Operation {
     Get { key: String },
     Set { key: String, value: Option<Store> },
+    Rename { from: String, to: String },
 }
 
 #[derive(Debug, Clone)]
@@ -64,6 +65,7 @@ impl From<Commands> for Operations {
                     });
                 }
                 Command::Delete { key } => ops.push(Operation::Set { key, value: None }),
+                Command::Rename { from, to } => ops.push(Operation::Rename { from, to }),
             }
         }
         ops
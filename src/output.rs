@@ -1,4 +1,12 @@
-use crate::{file::BackupFile, info::Info, reads::Reads, schema::Schema, store::Store};
+use std::path::PathBuf;
+
+use secrecy::{ExposeSecret, Secret};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    audit::AuditEvent, file::BackupFile, info::Info, reads::Reads, schema::Schema, store::Store,
+    strength::Strength,
+};
 
 #[derive(Debug, Clone)]
 pub enum Output {
@@ -8,9 +16,107 @@ pub enum Output {
     Read(Reads<Store>),
     List(Vec<String>),
     Backup(BackupFile),
+    // where the archive was written and the vaults in it
+    Archive(PathBuf, Vec<String>),
+    // vault name and the exported data
+    Export(String, Secret<String>),
+    // the number of entries imported and the keys that were skipped
+    Import(usize, Vec<String>),
+    // the number of passwords regenerated and the keys that have no password
+    Regenerated(usize, Vec<String>),
+    // vault, key, and the hex sha1 of the entry's password
+    PasswordHash(String, String, String),
+    // vault and the names of the entries that share a password, grouped by password
+    Reused(String, Vec<Vec<String>>),
+    // vault and the entries whose passwords aren't rated strong
+    Weak(String, Vec<(String, Strength)>),
+    // the most recent events in the audit log, oldest first
+    Audit(Vec<AuditEvent>),
+    // a new recovery key, only ever shown this once
+    RecoveryKey(Secret<String>),
+    Version(String),
+    // the client's protocol version and the manager's, when they don't agree
+    ProtocolMismatch(u32, u32),
+    // the seconds until the vault takes passwords again
+    LockedOut(u64),
+    Error(String),
     Nothing,
 }
 
+impl Output {
+    // for the cli's machine readable output, entries are given as objects of their fields
+    pub fn to_json(&self) -> Value {
+        match self {
+            Self::Info(info) => Value::Object(
+                info.data
+                    .iter()
+                    .map(|(vault, schema)| (vault.to_string(), json!(schema.data)))
+                    .collect(),
+            ),
+            Self::Schema(schema) => json!(schema.data),
+            Self::BackupFiles(files) => {
+                json!(files
+                    .iter()
+                    .map(|file| file.to_string())
+                    .collect::<Vec<_>>())
+            }
+            Self::Read(reads) => Value::Object(
+                reads
+                    .data
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), Self::entry_json(value)))
+                    .collect(),
+            ),
+            Self::List(items) => json!(items),
+            Self::Backup(file) => json!(file.to_string()),
+            Self::Archive(path, vaults) => json!({"path": path, "vaults": vaults}),
+            Self::Export(vault, data) => json!({"vault": vault, "data": data.expose_secret()}),
+            Self::Import(imported, skipped) => json!({"imported": imported, "skipped": skipped}),
+            Self::Regenerated(regenerated, skipped) => {
+                json!({"regenerated": regenerated, "skipped": skipped})
+            }
+            Self::PasswordHash(vault, key, hash) => {
+                json!({"vault": vault, "key": key, "hash": hash})
+            }
+            Self::Reused(vault, groups) => json!({"vault": vault, "groups": groups}),
+            Self::Audit(events) => json!(events),
+            Self::Version(version) => json!({ "version": version }),
+            Self::ProtocolMismatch(client, manager) => json!({
+                "client_protocol": client,
+                "manager_protocol": manager
+            }),
+            Self::Weak(vault, entries) => json!({
+                "vault": vault,
+                "entries": entries
+                    .iter()
+                    .map(|(key, strength)| json!({"key": key, "strength": strength.to_string()}))
+                    .collect::<Vec<_>>()
+            }),
+            Self::RecoveryKey(key) => json!({ "recovery_key": key.expose_secret() }),
+            Self::LockedOut(seconds) => json!({ "locked_out": seconds }),
+            Self::Error(e) => json!({ "error": e }),
+            Self::Nothing => Value::Null,
+        }
+    }
+
+    pub fn entry_json(store: &Store) -> Value {
+        let mut fields = Map::new();
+        for (name, value) in store.custom_fields() {
+            fields.insert(name, json!(value.expose_secret()));
+        }
+        let store = &store.clone().plain();
+        if let Store::Totp(totp) = store {
+            fields.insert("code".to_string(), json!(totp.code()));
+        }
+        let (choice, values) = store.clone().split();
+        fields.insert("type".to_string(), json!(choice.to_string()));
+        for (field, value) in values {
+            fields.insert(field, json!(value.expose_secret()));
+        }
+        Value::Object(fields)
+    }
+}
+
 impl From<Vec<BackupFile>> for Output {
     fn from(value: Vec<BackupFile>) -> Self {
         Output::BackupFiles(value)
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 #[derive(Debug, Clone)]
 pub struct Reads<T> {
     pub data: HashMap<String, T>,
+    // when the read entries were last updated, if known
+    pub updated: HashMap<String, String>,
 }
 
 impl<T> Default for Reads<T> {
@@ -15,6 +17,7 @@ impl<T> Reads<T> {
     pub fn new() -> Self {
         Self {
             data: HashMap::default(),
+            updated: HashMap::default(),
         }
     }
 
@@ -25,6 +28,10 @@ impl<T> Reads<T> {
     pub fn remove(&mut self, key: &str) -> Option<T> {
         self.data.remove(key)
     }
+
+    pub fn updated(&self, key: &str) -> Option<&String> {
+        self.updated.get(key)
+    }
 }
 
 // impl<T: Display + Clone> Display for Reads<T> {
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+use sha2::{Digest, Sha256};
+
+use crate::{reads::Reads, store::Store};
+
+// the names of entries that share a password, each entry is dropped as soon as its password is
+// hashed and only the hashes are compared, the groups and the names in them are sorted
+pub fn reused(entries: Reads<Store>) -> Vec<Vec<String>> {
+    let mut groups: HashMap<Vec<u8>, Vec<String>> = HashMap::new();
+    for (key, store) in entries.data {
+        let Some(password) = store.as_hash().shift_remove("password") else {
+            continue;
+        };
+        if password.expose_secret().is_empty() {
+            continue;
+        }
+        let hash = Sha256::digest(password.expose_secret().as_bytes()).to_vec();
+        groups.entry(hash).or_default().push(key);
+    }
+    let mut groups = groups
+        .into_values()
+        .filter(|keys| keys.len() > 1)
+        .map(|mut keys| {
+            keys.sort();
+            keys
+        })
+        .collect::<Vec<_>>();
+    groups.sort();
+    groups
+}
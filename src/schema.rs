@@ -2,9 +2,38 @@ use std::{collections::BTreeMap, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
+// the type of each entry, stored under the same names the schema always used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StoreKind {
+    Password,
+    UsernamePassword,
+    Login,
+    Totp,
+    Note,
+    Card,
+    // written by a newer version, the entry can still be listed
+    #[serde(other)]
+    Unknown,
+}
+
+impl Display for StoreKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoreKind::Password => write!(f, "password"),
+            StoreKind::UsernamePassword => write!(f, "username-password"),
+            StoreKind::Login => write!(f, "login"),
+            StoreKind::Totp => write!(f, "totp"),
+            StoreKind::Note => write!(f, "note"),
+            StoreKind::Card => write!(f, "card"),
+            StoreKind::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Schema {
-    pub data: BTreeMap<String, String>,
+    pub data: BTreeMap<String, StoreKind>,
 }
 
 impl Schema {
@@ -14,11 +43,11 @@ impl Schema {
         }
     }
 
-    pub fn insert(&mut self, key: String, value: String) {
+    pub fn insert(&mut self, key: String, value: StoreKind) {
         self.data.insert(key, value);
     }
 
-    pub fn get(&self, key: &str) -> Option<&String> {
+    pub fn get(&self, key: &str) -> Option<&StoreKind> {
         self.data.get(key)
     }
 
@@ -38,15 +67,15 @@ impl Schema {
     }
 }
 
-impl From<BTreeMap<String, String>> for Schema {
-    fn from(value: BTreeMap<String, String>) -> Self {
+impl From<BTreeMap<String, StoreKind>> for Schema {
+    fn from(value: BTreeMap<String, StoreKind>) -> Self {
         Self { data: value }
     }
 }
 
 impl IntoIterator for Schema {
-    type Item = (String, String);
-    type IntoIter = std::collections::btree_map::IntoIter<String, String>;
+    type Item = (String, StoreKind);
+    type IntoIter = std::collections::btree_map::IntoIter<String, StoreKind>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.data.into_iter()
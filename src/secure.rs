@@ -4,7 +4,7 @@ use aes_gcm::{
     aead::{generic_array::GenericArray, Aead, OsRng},
     AeadCore, Aes256Gcm, Key, KeyInit,
 };
-use argon2::{password_hash::SaltString, Argon2};
+use argon2::{password_hash::SaltString, Algorithm, Argon2, Params, Version};
 use secrecy::ExposeSecret;
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,41 @@ use crate::{
     Password,
 };
 
+// argon2's costs for turning a password into a key, kept next to what they were used on so
+// changing the configured ones doesn't lock anything out
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
+pub struct KdfParams {
+    // in KiB
+    pub memory: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+// argon2's own defaults, which is what everything written before these were stored used
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    pub fn argon2(&self) -> Result<Argon2<'static>, argon2::Error> {
+        let params = Params::new(self.memory, self.iterations, self.parallelism, None)?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    // whether these cost at least as much as the other ones in every way
+    pub fn at_least(&self, other: &Self) -> bool {
+        self.memory >= other.memory
+            && self.iterations >= other.iterations
+            && self.parallelism >= other.parallelism
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct Encrypted<Data> {
     nonce: Vec<u8>,
@@ -30,6 +65,11 @@ impl<'de, Data: Deserialize<'de>> Decrypted<Data> {
     pub fn deserialize(&'de self) -> Data {
         bincode::deserialize(&self.data).unwrap()
     }
+
+    // for data that could have been written in an older layout
+    pub fn try_deserialize_as<T: Deserialize<'de>>(&'de self) -> Option<T> {
+        bincode::deserialize(&self.data).ok()
+    }
 }
 
 impl<'de, Data: Serialize + Deserialize<'de>> Encrypted<Data> {
@@ -65,6 +105,7 @@ impl<'de, Data: Serialize + Deserialize<'de>> Encrypted<Data> {
 pub trait SecureData {
     type Item;
     fn salt(&self) -> &str;
+    fn params(&self) -> &KdfParams;
     fn data(&self) -> &Encrypted<Self::Item>;
     // not much point in this function
     fn encrypt<'de>(data: &Self::Item, key: Key<Aes256Gcm>) -> anyhow::Result<Encrypted<Self::Item>>
@@ -89,15 +130,15 @@ pub trait SecureData {
     //     Decrypted::deserialize(decrypted)
     // }
     fn key(&self, password: Password) -> Key<Aes256Gcm> {
-        Self::get_key(self.salt(), password)
+        Self::get_key(self.salt(), self.params(), password)
     }
-    fn get_key(salt: &str, password: Password) -> Key<Aes256Gcm> {
+    fn get_key(salt: &str, params: &KdfParams, password: Password) -> Key<Aes256Gcm> {
         let salt_string = SaltString::from_b64(salt).unwrap();
         let mut salt_arr = [0u8; 64];
         let salt_bytes = salt_string.decode_b64(&mut salt_arr).unwrap();
 
         let mut output_key = [0u8; 32];
-        let argon2 = Argon2::default();
+        let argon2 = params.argon2().unwrap();
         argon2
             .hash_password_into(
                 password.expose_secret().as_bytes(),
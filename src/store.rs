@@ -1,15 +1,39 @@
-use std::{collections::HashMap, fmt::Display};
+use std::fmt::Display;
 
 use enum_iterator::{all, Sequence};
+use indexmap::IndexMap;
 use secrecy::{ExposeSecret, Secret};
 use serde::{ser::SerializeTupleVariant, Deserialize, Serialize};
 
-pub type StoreHash = HashMap<String, Secret<String>>;
+use crate::{
+    card::Card,
+    errors::SchemaError,
+    schema::StoreKind,
+    totp::{Totp, TotpAlgorithm, DEFAULT_DIGITS, DEFAULT_PERIOD},
+};
+
+// kept in the order the fields are listed for the type so forms are laid out the same every time
+pub type StoreHash = IndexMap<String, Secret<String>>;
+// extra named values on an entry, kept in the order they were added
+pub type CustomFields = Vec<(String, Secret<String>)>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Sequence)]
 pub enum StoreChoice {
     Password,
     UsernamePassword,
+    Login,
+    Totp,
+    Note,
+    Card,
+}
+
+// how a field should be shown, all of the values are stored the same way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Secret,
+    Url,
+    Text,
+    Multiline,
 }
 
 impl Display for StoreChoice {
@@ -17,6 +41,24 @@ impl Display for StoreChoice {
         match self {
             StoreChoice::Password => write!(f, "Password"),
             StoreChoice::UsernamePassword => write!(f, "Username/Password"),
+            StoreChoice::Login => write!(f, "Login"),
+            StoreChoice::Totp => write!(f, "TOTP"),
+            StoreChoice::Note => write!(f, "Note"),
+            StoreChoice::Card => write!(f, "Card"),
+        }
+    }
+}
+
+impl From<StoreKind> for StoreChoice {
+    fn from(value: StoreKind) -> Self {
+        match value {
+            StoreKind::Password => StoreChoice::Password,
+            StoreKind::UsernamePassword => StoreChoice::UsernamePassword,
+            StoreKind::Login => StoreChoice::Login,
+            StoreKind::Totp => StoreChoice::Totp,
+            StoreKind::Note => StoreChoice::Note,
+            StoreKind::Card => StoreChoice::Card,
+            StoreKind::Unknown => StoreChoice::default(),
         }
     }
 }
@@ -28,20 +70,121 @@ impl Default for StoreChoice {
 }
 
 impl StoreChoice {
-    pub fn convert(&self, data: &StoreHash) -> Option<Store> {
+    pub fn convert(&self, data: &StoreHash) -> Result<Store, SchemaError> {
+        let store = self.convert_fields(data)?;
+        match data.get("notes") {
+            Some(notes) if self.field_kind("notes").is_some() => {
+                Ok(store.with_notes(notes.clone()))
+            }
+            _ => Ok(store),
+        }
+    }
+
+    fn convert_fields(&self, data: &StoreHash) -> Result<Store, SchemaError> {
+        let get = |key: &str| data.get(key).ok_or(SchemaError::BadValues);
         match self {
             Self::Password => {
-                let p = data.get("password")?;
-                Some(Store::Password(p.clone()))
+                let p = get("password")?;
+                Ok(Store::Password(p.clone()))
             }
             Self::UsernamePassword => {
-                let p = data.get("password")?;
-                let u = data.get("username")?;
-                Some(Store::UsernamePassword(u.clone(), p.clone()))
+                let p = get("password")?;
+                let u = get("username")?;
+                Ok(Store::UsernamePassword(u.clone(), p.clone()))
+            }
+            Self::Login => {
+                let p = get("password")?;
+                let u = get("username")?;
+                let url = get("url")?;
+                Ok(Store::Login(u.clone(), p.clone(), url.clone()))
+            }
+            Self::Totp => {
+                let totp = Totp::from_fields(
+                    get("secret")?.expose_secret(),
+                    get("period")?.expose_secret(),
+                    get("digits")?.expose_secret(),
+                    get("algorithm")?.expose_secret(),
+                )?;
+                Ok(Store::Totp(totp))
+            }
+            Self::Note => {
+                let n = get("note")?;
+                Ok(Store::Note(n.clone()))
+            }
+            Self::Card => {
+                let card = Card::new(
+                    get("number")?.expose_secret(),
+                    get("expiry")?.expose_secret(),
+                    get("cvv")?.expose_secret(),
+                    get("cardholder")?.expose_secret(),
+                )?;
+                Ok(Store::Card(card))
             }
         }
     }
 
+    // the fields of the kind of entry in the order they are shown
+    pub fn fields(&self) -> Vec<(&'static str, FieldKind)> {
+        match self {
+            Self::Password => vec![
+                ("password", FieldKind::Secret),
+                ("notes", FieldKind::Multiline),
+            ],
+            Self::UsernamePassword => vec![
+                ("username", FieldKind::Text),
+                ("password", FieldKind::Secret),
+                ("notes", FieldKind::Multiline),
+            ],
+            Self::Login => vec![
+                ("username", FieldKind::Text),
+                ("url", FieldKind::Url),
+                ("password", FieldKind::Secret),
+                ("notes", FieldKind::Multiline),
+            ],
+            Self::Totp => vec![
+                ("secret", FieldKind::Secret),
+                ("period", FieldKind::Text),
+                ("digits", FieldKind::Text),
+                ("algorithm", FieldKind::Text),
+            ],
+            Self::Note => vec![("note", FieldKind::Multiline)],
+            Self::Card => vec![
+                ("cardholder", FieldKind::Text),
+                ("number", FieldKind::Secret),
+                ("expiry", FieldKind::Text),
+                ("cvv", FieldKind::Secret),
+                ("notes", FieldKind::Multiline),
+            ],
+        }
+    }
+
+    pub fn field_kind(&self, field: &str) -> Option<FieldKind> {
+        self.fields()
+            .into_iter()
+            .find(|(name, _)| *name == field)
+            .map(|(_, kind)| kind)
+    }
+
+    // there's at most one field edited as multiline text
+    pub fn multiline_field(&self) -> Option<&'static str> {
+        self.fields()
+            .into_iter()
+            .find(|(_, kind)| *kind == FieldKind::Multiline)
+            .map(|(name, _)| name)
+    }
+
+    // fields that are allowed to be left empty when creating or updating an entry
+    pub fn is_optional(&self, field: &str) -> bool {
+        match self {
+            Self::Password => field == "notes",
+            Self::UsernamePassword => field == "notes",
+            Self::Login => matches!(field, "url" | "notes"),
+            Self::Totp => matches!(field, "period" | "digits" | "algorithm"),
+            Self::Note => field == "note",
+            Self::Card => matches!(field, "cardholder" | "cvv" | "notes"),
+        }
+    }
+
     pub fn convert_default(&self) -> Store {
         match self {
             Self::Password => Store::Password(String::new().into()),
@@ -50,6 +193,37 @@ impl StoreChoice {
                 String::new().into(),
                 // StoreValue::Secret(String::new().into()),
             ),
+            Self::Login => Store::Login(
+                String::new().into(),
+                String::new().into(),
+                String::new().into(),
+            ),
+            Self::Totp => Store::Totp(Totp {
+                secret: String::new().into(),
+                period: DEFAULT_PERIOD,
+                digits: DEFAULT_DIGITS,
+                algorithm: TotpAlgorithm::default(),
+            }),
+            Self::Note => Store::Note(String::new().into()),
+            Self::Card => Store::Card(Card {
+                number: String::new().into(),
+                expiry: String::new(),
+                cvv: String::new().into(),
+                cardholder: String::new(),
+            }),
+        }
+    }
+
+    // inverse of the representation used in the schema
+    pub fn from_repr(repr: &str) -> Option<Self> {
+        match repr {
+            "password" => Some(Self::Password),
+            "username-password" => Some(Self::UsernamePassword),
+            "login" => Some(Self::Login),
+            "totp" => Some(Self::Totp),
+            "note" => Some(Self::Note),
+            "card" => Some(Self::Card),
+            _ => None,
         }
     }
 
@@ -62,6 +236,15 @@ impl StoreChoice {
 pub enum Store {
     Password(Secret<String>),
     UsernamePassword(Secret<String>, Secret<String>),
+    Totp(Totp),
+    Note(Secret<String>),
+    // username, password, and url
+    Login(Secret<String>, Secret<String>, Secret<String>),
+    // any of the others with custom fields added on
+    WithFields(Box<Store>, CustomFields),
+    // one of the password entries with freeform notes, not masked like the rest
+    WithNotes(Box<Store>, Secret<String>),
+    Card(Card),
 }
 
 impl Serialize for Store {
@@ -82,6 +265,36 @@ impl Serialize for Store {
                 state.serialize_field(p.expose_secret())?;
                 state.end()
             }
+            Self::Totp(t) => serializer.serialize_newtype_variant("Store", 2, "Totp", t),
+            Self::Note(n) => {
+                let mut state = serializer.serialize_tuple_variant("Store", 3, "Note", 1)?;
+                state.serialize_field(n.expose_secret())?;
+                state.end()
+            }
+            Self::Login(u, p, url) => {
+                let mut state = serializer.serialize_tuple_variant("Store", 4, "Login", 3)?;
+                state.serialize_field(u.expose_secret())?;
+                state.serialize_field(p.expose_secret())?;
+                state.serialize_field(url.expose_secret())?;
+                state.end()
+            }
+            Self::WithFields(store, fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(name, value)| (name, value.expose_secret()))
+                    .collect::<Vec<_>>();
+                let mut state = serializer.serialize_tuple_variant("Store", 5, "WithFields", 2)?;
+                state.serialize_field(store)?;
+                state.serialize_field(&fields)?;
+                state.end()
+            }
+            Self::WithNotes(store, notes) => {
+                let mut state = serializer.serialize_tuple_variant("Store", 6, "WithNotes", 2)?;
+                state.serialize_field(store)?;
+                state.serialize_field(notes.expose_secret())?;
+                state.end()
+            }
+            Self::Card(c) => serializer.serialize_newtype_variant("Store", 7, "Card", c),
         }
     }
 }
@@ -97,26 +310,121 @@ impl Serialize for Store {
 
 impl Store {
     // how to represent the type in the schema
+    pub fn kind(&self) -> StoreKind {
+        match self {
+            Self::Password(_) => StoreKind::Password,
+            Self::UsernamePassword(_, _) => StoreKind::UsernamePassword,
+            Self::Totp(_) => StoreKind::Totp,
+            Self::Note(_) => StoreKind::Note,
+            Self::Login(..) => StoreKind::Login,
+            Self::Card(_) => StoreKind::Card,
+            Self::WithFields(store, _) => store.kind(),
+            Self::WithNotes(store, _) => store.kind(),
+        }
+    }
+
     pub fn repr(&self) -> String {
+        self.kind().to_string()
+    }
+
+    // custom fields are only wrapped around when there are some
+    pub fn with_fields(self, fields: CustomFields) -> Self {
+        let store = self.base();
+        if fields.is_empty() {
+            store
+        } else {
+            Self::WithFields(Box::new(store), fields)
+        }
+    }
+
+    // empty notes aren't kept
+    pub fn with_notes(self, notes: Secret<String>) -> Self {
+        if notes.expose_secret().is_empty() {
+            self
+        } else {
+            Self::WithNotes(Box::new(self), notes)
+        }
+    }
+
+    pub fn notes(&self) -> Option<Secret<String>> {
+        match self {
+            Self::WithFields(store, _) => store.notes(),
+            Self::WithNotes(_, notes) => Some(notes.clone()),
+            _ => None,
+        }
+    }
+
+    // the entry without custom fields or notes
+    pub fn plain(self) -> Self {
+        match self {
+            Self::WithFields(store, _) | Self::WithNotes(store, _) => store.plain(),
+            store => store,
+        }
+    }
+
+    // the entry without any custom fields
+    pub fn base(self) -> Self {
+        match self {
+            Self::WithFields(store, _) => store.base(),
+            store => store,
+        }
+    }
+
+    pub fn custom_fields(&self) -> CustomFields {
         match self {
-            Self::Password(_) => "password".to_string(),
-            Self::UsernamePassword(_, _) => "username-password".to_string(),
+            Self::WithFields(_, fields) => fields.clone(),
+            _ => vec![],
         }
     }
 
     pub fn split(&self) -> (StoreChoice, StoreHash) {
         match self {
             Self::Password(p) => {
-                let mut map = HashMap::new();
+                let mut map = IndexMap::new();
                 map.insert("password".to_string(), p.clone());
                 (StoreChoice::Password, map)
             }
             Self::UsernamePassword(u, p) => {
-                let mut map = HashMap::new();
-                map.insert("password".to_string(), p.clone());
+                let mut map = IndexMap::new();
                 map.insert("username".to_string(), u.clone());
+                map.insert("password".to_string(), p.clone());
                 (StoreChoice::UsernamePassword, map)
             }
+            Self::Totp(t) => {
+                let mut map = IndexMap::new();
+                map.insert("secret".to_string(), t.secret.clone());
+                map.insert("period".to_string(), t.period.to_string().into());
+                map.insert("digits".to_string(), t.digits.to_string().into());
+                map.insert("algorithm".to_string(), t.algorithm.to_string().into());
+                (StoreChoice::Totp, map)
+            }
+            Self::Note(n) => {
+                let mut map = IndexMap::new();
+                map.insert("note".to_string(), n.clone());
+                (StoreChoice::Note, map)
+            }
+            Self::Login(u, p, url) => {
+                let mut map = IndexMap::new();
+                map.insert("username".to_string(), u.clone());
+                map.insert("url".to_string(), url.clone());
+                map.insert("password".to_string(), p.clone());
+                (StoreChoice::Login, map)
+            }
+            Self::Card(c) => {
+                let mut map = IndexMap::new();
+                map.insert("cardholder".to_string(), c.cardholder.clone().into());
+                map.insert("number".to_string(), c.number.clone());
+                map.insert("expiry".to_string(), c.expiry.clone().into());
+                map.insert("cvv".to_string(), c.cvv.clone());
+                (StoreChoice::Card, map)
+            }
+            // custom fields are kept apart so they can't be mistaken for the usual ones
+            Self::WithFields(store, _) => store.split(),
+            Self::WithNotes(store, notes) => {
+                let (choice, mut map) = store.split();
+                map.insert("notes".to_string(), notes.clone());
+                (choice, map)
+            }
         }
     }
 
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use secrecy::ExposeSecret;
+
+use crate::{reads::Reads, store::Store};
+
+// bits of entropy where the bar is considered full
+pub const MAX_BITS: f32 = 128.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl Strength {
+    pub fn from_bits(bits: f32) -> Self {
+        if bits < 50.0 {
+            Self::Weak
+        } else if bits < 80.0 {
+            Self::Fair
+        } else {
+            Self::Strong
+        }
+    }
+}
+
+impl std::fmt::Display for Strength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Weak => write!(f, "Weak"),
+            Self::Fair => write!(f, "Fair"),
+            Self::Strong => write!(f, "Strong"),
+        }
+    }
+}
+
+// rough estimate assuming every character is drawn uniformly from the classes that show up,
+// cheap enough to recompute on every render
+pub fn entropy(password: &str) -> f32 {
+    let (mut lower, mut upper, mut digit, mut symbol, mut other) =
+        (false, false, false, false, false);
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            c if c.is_ascii() => symbol = true,
+            _ => other = true,
+        }
+    }
+    let pool = [
+        (lower, 26),
+        (upper, 26),
+        (digit, 10),
+        (symbol, 33),
+        (other, 100),
+    ]
+    .iter()
+    .filter(|(present, _)| *present)
+    .map(|(_, size)| size)
+    .sum::<u32>();
+    if pool == 0 {
+        return 0.0;
+    }
+    password.chars().count() as f32 * (pool as f32).log2()
+}
+
+// the entries whose password isn't rated strong, passwords are rated one at a time and dropped
+// right after, sorted by name
+pub fn weak(entries: Reads<Store>) -> Vec<(String, Strength)> {
+    entries
+        .data
+        .into_iter()
+        .filter_map(|(key, store)| {
+            let password = store.as_hash().remove("password")?;
+            let strength = Strength::from_bits(entropy(password.expose_secret()));
+            (strength != Strength::Strong).then_some((key, strength))
+        })
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .collect()
+}
@@ -1,7 +1,10 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use chrono::{DateTime, Local, NaiveDateTime, ParseError};
-use iced::Theme;
+use iced::{Color, Theme};
+use url::Url;
+
+use crate::errors::{KeyError, VaultNameError};
 
 pub fn now() -> DateTime<Local> {
     Local::now()
@@ -17,6 +20,27 @@ pub fn read_date(date: &str) -> Result<DateTime<Local>, ParseError> {
     Ok(res)
 }
 
+// e.g. `3 days ago`, only the largest unit is given
+pub fn time_since(date: DateTime<Local>) -> String {
+    let elapsed = now().signed_duration_since(date);
+    let days = elapsed.num_days();
+    let (amount, unit) = if days >= 365 {
+        (days / 365, "year")
+    } else if days >= 30 {
+        (days / 30, "month")
+    } else if days > 0 {
+        (days, "day")
+    } else if elapsed.num_hours() > 0 {
+        (elapsed.num_hours(), "hour")
+    } else if elapsed.num_minutes() > 0 {
+        (elapsed.num_minutes(), "minute")
+    } else {
+        return "just now".to_string();
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{} ago", amount, unit, plural)
+}
+
 pub fn base_path() -> PathBuf {
     let base_dir =
         if let Some(project_dirs) = directories_next::ProjectDirs::from("com", "bski", "pants") {
@@ -27,6 +51,67 @@ pub fn base_path() -> PathBuf {
     base_dir
 }
 
+// entry names are trimmed so lookups aren't thrown off by stray spaces, and control characters
+// are rejected since they can't be typed back in
+pub fn entry_key(key: &str) -> Result<String, KeyError> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(KeyError::Empty);
+    }
+    match key.chars().find(|c| c.is_control()) {
+        Some(c) => Err(KeyError::BadCharacter(c)),
+        None => Ok(key.to_string()),
+    }
+}
+
+// vault names can end up as file names, so anything that could leave the vault directory or
+// can't be typed back in is rejected
+pub fn vault_name(name: &str) -> Result<String, VaultNameError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(VaultNameError::Empty);
+    }
+    if name == "." || name == ".." {
+        return Err(VaultNameError::Reserved(name.to_string()));
+    }
+    match name
+        .chars()
+        .find(|c| c.is_control() || matches!(c, '/' | '\\'))
+    {
+        Some(c) => Err(VaultNameError::BadCharacter(c)),
+        None => Ok(name.to_string()),
+    }
+}
+
+// only web addresses are handed off to the browser
+pub fn web_url(url: &str) -> Option<Url> {
+    let parsed = Url::parse(url.trim()).ok()?;
+    match parsed.scheme() {
+        "http" | "https" => Some(parsed),
+        _ => None,
+    }
+}
+
+// `#rrggbb`, the leading `#` is optional
+pub fn hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+// the os preference for light or dark, the usual default when it can't be told
+pub fn system_theme() -> Theme {
+    match dark_light::detect() {
+        dark_light::Mode::Dark => Theme::Dark,
+        dark_light::Mode::Light => Theme::Light,
+        dark_light::Mode::Default => Theme::default(),
+    }
+}
+
 pub fn theme_map() -> HashMap<String, Theme> {
     Theme::ALL
         .iter()
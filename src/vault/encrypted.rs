@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     action::Record,
-    secure::{Encrypted, SecureData},
+    secure::{Encrypted, KdfParams, SecureData},
     vault::Vault,
     Password,
 };
@@ -13,6 +13,9 @@ use crate::{
 pub struct PasswordEncrypted<Data> {
     pub data: Encrypted<Data>,
     pub salt: String,
+    // missing from anything written before they were configurable
+    #[serde(default)]
+    pub params: KdfParams,
 }
 
 impl<Data> SecureData for PasswordEncrypted<Data> {
@@ -20,6 +23,9 @@ impl<Data> SecureData for PasswordEncrypted<Data> {
     fn salt(&self) -> &str {
         &self.salt
     }
+    fn params(&self) -> &KdfParams {
+        &self.params
+    }
     fn data(&self) -> &Encrypted<Self::Item> {
         &self.data
     }
@@ -28,14 +34,27 @@ impl<Data> SecureData for PasswordEncrypted<Data> {
 pub type VaultEncrypted = PasswordEncrypted<Vault>;
 
 impl VaultEncrypted {
-    pub fn new(password: Password) -> anyhow::Result<Self> {
+    pub fn new(password: Password, params: KdfParams) -> anyhow::Result<Self> {
         let salt = SaltString::generate(&mut OsRng).to_string();
-        let key = Self::get_key(&salt, password);
-        Encrypted::encrypt(&Vault::new(), key).map(|vault| Self { data: vault, salt })
+        let key = Self::get_key(&salt, &params, password);
+        Encrypted::encrypt(&Vault::new(), key).map(|vault| Self {
+            data: vault,
+            salt,
+            params,
+        })
     }
 
-    pub fn from_vault(salt: String, key: Key<Aes256Gcm>, vault: &Vault) -> anyhow::Result<Self> {
-        Encrypted::encrypt(vault, key).map(|vault| Self { data: vault, salt })
+    pub fn from_vault(
+        salt: String,
+        params: KdfParams,
+        key: Key<Aes256Gcm>,
+        vault: &Vault,
+    ) -> anyhow::Result<Self> {
+        Encrypted::encrypt(vault, key).map(|vault| Self {
+            data: vault,
+            salt,
+            params,
+        })
     }
 
     pub fn update(&mut self, data: &Vault, key: Key<Aes256Gcm>) -> anyhow::Result<()> {
@@ -48,10 +67,23 @@ impl VaultEncrypted {
 pub type RecordEncrypted = PasswordEncrypted<Record>;
 
 impl RecordEncrypted {
-    pub fn new(password: Password) -> anyhow::Result<Self> {
+    pub fn new(password: Password, params: KdfParams) -> anyhow::Result<Self> {
         let salt = SaltString::generate(&mut OsRng).to_string();
-        let key = Self::get_key(&salt, password);
-        Encrypted::encrypt(&Record::new(), key).map(|vault| Self { data: vault, salt })
+        let key = Self::get_key(&salt, &params, password);
+        Encrypted::encrypt(&Record::new(), key).map(|vault| Self {
+            data: vault,
+            salt,
+            params,
+        })
+    }
+
+    // records are only ever encrypted with the vault's key
+    pub fn with_key(vault: &VaultEncrypted, key: Key<Aes256Gcm>) -> anyhow::Result<Self> {
+        Encrypted::encrypt(&Record::new(), key).map(|record| Self {
+            data: record,
+            salt: vault.salt.clone(),
+            params: vault.params,
+        })
     }
 
     pub fn update(&mut self, data: &Record, key: Key<Aes256Gcm>) -> anyhow::Result<()> {
@@ -1,5 +1,10 @@
 use core::panic;
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use aes_gcm::{Aes256Gcm, Key};
 use argon2::password_hash::SaltString;
@@ -7,25 +12,37 @@ use rand::rngs::OsRng;
 
 use crate::{
     command::{Command, Commands},
-    config::vault_config::VaultConfig,
-    errors::ManagerError,
+    config::{internal_config::InternalConfig, vault_config::VaultConfig},
+    errors::{CommunicationError, ManagerError},
     file::{BackupFile, ProjectFile, RecordFile, SaveDir, SchemaFile, VaultFile},
     message::Message,
     output::Output,
     reads::Reads,
     schema::Schema,
-    secure::{Encrypted, SecureData},
+    secure::{Encrypted, KdfParams, SecureData},
     store::Store,
     Password,
 };
 
 use super::{
     encrypted::{RecordEncrypted, VaultEncrypted},
-    Vault,
+    recovery::Recovery,
+    Stamps, Vault,
 };
 
 pub struct VaultInterface {
     config: VaultConfig,
+    // the costs for any key made for the vault
+    kdf: KdfParams,
+    // the entries' timestamps from the last message that unlocked the vault
+    stamps: Option<Stamps>,
+}
+
+// how keys get made for the vault and whether to remake the current one on unlocking
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeySettings {
+    pub params: KdfParams,
+    pub reharden: bool,
 }
 //
 // impl Default for VaultInterface {
@@ -36,13 +53,27 @@ pub struct VaultInterface {
 
 impl VaultInterface {
     pub fn new(save_dir: PathBuf) -> Self {
-        let config = VaultConfig::new(save_dir);
+        let config = VaultConfig::load_dir(save_dir);
 
-        Self { config }
+        Self {
+            config,
+            kdf: KdfParams::default(),
+            stamps: None,
+        }
+    }
+    pub fn with_kdf(mut self, kdf: KdfParams) -> Self {
+        self.kdf = kdf;
+        self
+    }
+    fn settings(&self) -> KeySettings {
+        KeySettings {
+            params: self.kdf,
+            reharden: self.config.reharden,
+        }
     }
     pub fn delete(&self, password: Password) -> anyhow::Result<()> {
         // ensure password is right
-        VaultHandler::get_interface(password, self.config.save_dir())?;
+        VaultHandler::get_interface(password, self.config.save_dir(), KeySettings::default())?;
         let dir = self.config.save_dir();
         let _ = dir.remove();
         Ok(())
@@ -56,11 +87,125 @@ impl VaultInterface {
             Err(ManagerError::NonEmptyVault.into())
         }
     }
-    pub fn receive(&self, message: Message) -> anyhow::Result<Output> {
+    // a vault that has never been written to has no directory yet, so there is nothing to move
+    pub fn rename(&self, to: &Path) -> anyhow::Result<()> {
+        if self.config.save_dir().exists() {
+            self.config.save_dir().rename(to)?;
+        }
+        Ok(())
+    }
+    // the decrypted vault, only for keeping in memory e.g. to put it in an archive
+    pub fn vault(&self, password: Password) -> anyhow::Result<Vault> {
+        let handler =
+            VaultHandler::load_interface(password, self.config.save_dir(), KeySettings::default())?;
+        Ok(handler.vault)
+    }
+    pub fn password_spec(&self) -> Option<String> {
+        self.config.password_spec.clone()
+    }
+    pub fn set_password_spec(&mut self, spec: Option<String>) -> anyhow::Result<()> {
+        self.config.password_spec = spec;
+        self.config.save()
+    }
+    // takes effect the next time the vault is unlocked, since that needs the password
+    pub fn set_reharden(&mut self) -> anyhow::Result<()> {
+        self.config.reharden = true;
+        self.config.save()
+    }
+    pub fn favorites(&self) -> BTreeSet<String> {
+        self.config.favorites.clone()
+    }
+    pub fn set_favorite(&mut self, key: String, favorite: bool) -> anyhow::Result<()> {
+        if favorite {
+            self.config.favorites.insert(key);
+        } else {
+            self.config.favorites.remove(&key);
+        }
+        self.config.save()
+    }
+    pub fn stamps(&self) -> Option<Stamps> {
+        self.stamps.clone()
+    }
+    pub fn order(&self) -> BTreeMap<String, usize> {
+        self.config.order.clone()
+    }
+    // always numbered from zero so the positions don't keep growing
+    pub fn set_order(&mut self, keys: Vec<String>) -> anyhow::Result<()> {
+        self.config.order = keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| (key, i))
+            .collect();
+        self.config.save()
+    }
+    pub fn tags(&self) -> BTreeMap<String, BTreeSet<String>> {
+        self.config.tags.clone()
+    }
+    // trimmed and without duplicates, no tags at all drops the entry from the map
+    pub fn set_tags(
+        &mut self,
+        key: String,
+        tags: impl IntoIterator<Item = String>,
+    ) -> anyhow::Result<()> {
+        let tags = tags
+            .into_iter()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect::<BTreeSet<_>>();
+        if tags.is_empty() {
+            self.config.tags.remove(&key);
+        } else {
+            self.config.tags.insert(key, tags);
+        }
+        self.config.save()
+    }
+    // keep the per entry metadata in line with a message that went through, the timestamps are
+    // kept in the vault itself so reads don't change anything here
+    pub fn track(&mut self, message: &Message) -> anyhow::Result<()> {
+        let config = &mut self.config;
+        match message {
+            Message::Delete(_, key) => {
+                config.favorites.remove(key);
+                config.order.remove(key);
+                config.tags.remove(key);
+            }
+            Message::DeleteMany(_, keys) => {
+                for key in keys {
+                    config.favorites.remove(key);
+                    config.order.remove(key);
+                    config.tags.remove(key);
+                }
+            }
+            Message::Rename(_, old, new) => {
+                if config.favorites.remove(old) {
+                    config.favorites.insert(new.to_string());
+                }
+                if let Some(order) = config.order.remove(old) {
+                    config.order.insert(new.to_string(), order);
+                }
+                if let Some(tags) = config.tags.remove(old) {
+                    config.tags.insert(new.to_string(), tags);
+                }
+            }
+            _ => return Ok(()),
+        }
+        self.config.save()
+    }
+    pub fn receive(&mut self, message: Message) -> anyhow::Result<Output> {
         match message {
             Message::Schema => Ok(self.get_schema().into()),
             Message::BackupList => Ok(self.config.save_dir().backup_file_all().into()),
-            _ => VaultHandler::receive(message, self.config.save_dir()),
+            _ => {
+                let settings = self.settings();
+                let (output, stamps) =
+                    VaultHandler::receive(message, self.config.save_dir(), settings)?;
+                self.stamps = Some(stamps);
+                if settings.reharden {
+                    self.config.reharden = false;
+                    self.config.save()?;
+                }
+                Ok(output)
+            }
         }
     }
 
@@ -80,6 +225,7 @@ impl VaultInterface {
 
 pub struct VaultHandler {
     vault: Vault,
+    settings: KeySettings,
     vault_encrypted: VaultEncrypted,
     key: Key<Aes256Gcm>,
     // schema: Schema,
@@ -91,89 +237,193 @@ pub struct VaultHandler {
 }
 
 impl VaultHandler {
-    pub fn receive(message: Message, save_dir: SaveDir) -> anyhow::Result<Output> {
+    // the entries' timestamps come back along with the output, since the vault is unlocked anyway
+    pub fn receive(
+        message: Message,
+        save_dir: SaveDir,
+        settings: KeySettings,
+    ) -> anyhow::Result<(Output, Stamps)> {
         match message {
             Message::Get(password, key) => {
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                if interface.vault.contains(&key) {
+                    interface.vault.touch(&key);
+                }
                 let command = Command::Read { key };
-                let mut interface = Self::load_interface(password, save_dir)?;
                 let reads = interface.transaction(command.into())?;
-                Ok(reads.into())
+                Ok((reads.into(), interface.vault.stamps()))
+            }
+            Message::GetAll(password) => {
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                let commands = interface
+                    .vault
+                    .schema()
+                    .keys()
+                    .into_iter()
+                    .map(|key| Command::Read { key })
+                    .collect::<Vec<_>>();
+                let reads = interface.transaction(commands.into())?;
+                Ok((reads.into(), interface.vault.stamps()))
             }
             Message::Update(password, key, value) => {
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                interface.vault.touch(&key);
                 let command = Command::Update { key, value };
-                let mut interface = Self::load_interface(password, save_dir)?;
                 let reads = interface.transaction(command.into())?;
-                Ok(reads.into())
+                Ok((reads.into(), interface.vault.stamps()))
+            }
+            Message::UpdateMany(password, entries) => {
+                let commands = entries
+                    .into_iter()
+                    .map(|(key, value)| Command::Update { key, value })
+                    .collect::<Vec<_>>();
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                let reads = interface.transaction(commands.into())?;
+                Ok((reads.into(), interface.vault.stamps()))
             }
             Message::Delete(password, key) => {
                 let command = Command::Delete { key };
-                let mut interface = Self::load_interface(password, save_dir)?;
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
                 let _reads = interface.transaction(command.into())?;
-                Ok(().into())
+                Ok((().into(), interface.vault.stamps()))
+            }
+            // one transaction so either all of them are deleted or none are
+            Message::DeleteMany(password, keys) => {
+                let commands = keys
+                    .into_iter()
+                    .map(|key| Command::Delete { key })
+                    .collect::<Vec<_>>();
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                let _reads = interface.transaction(commands.into())?;
+                Ok((().into(), interface.vault.stamps()))
+            }
+            Message::Rename(password, from, to) => {
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                if !interface.vault.contains(&from) {
+                    return Err(CommunicationError::NoEntry.into());
+                }
+                if interface.vault.contains(&to) {
+                    return Err(CommunicationError::ExistingEntry.into());
+                }
+                let command = Command::Rename { from, to };
+                let reads = interface.transaction(command.into())?;
+                Ok((reads.into(), interface.vault.stamps()))
             }
             Message::Backup(password) => {
-                let interface = Self::load_interface(password, save_dir)?;
+                let interface = Self::load_interface(password, save_dir, settings)?;
                 let backup = interface.backup()?;
-                Ok(Output::Backup(backup))
+                Ok((Output::Backup(backup), interface.vault.stamps()))
             }
             Message::Rotate(password, new_password) => {
-                let mut interface = Self::load_interface(password, save_dir)?;
-                let backup = interface.backup()?;
-                let new_vault = VaultEncrypted::new(new_password.clone())?;
-                let key = new_vault.key(new_password);
-                interface.vault_encrypted = new_vault;
-                interface.key = key;
-                interface.save()?;
-                Ok(Output::Backup(backup))
+                let interface = Self::load_interface(password, save_dir, settings)?;
+                let stamps = interface.vault.stamps();
+                Ok((interface.rotate(new_password)?, stamps))
+            }
+            // the recovery key stands in for the password, so it can only be used to set a new one
+            Message::Recover(recovery, new_password) => {
+                let recovery_file = save_dir.recovery_file();
+                if !recovery_file.exists() {
+                    return Err(ManagerError::NoRecoveryKey.into());
+                }
+                let key = recovery_file.read()?.deserialize().unlock(recovery)?;
+                let mut interface = Self::open_interface(key, save_dir, settings)?;
+                interface.check_unfinished()?;
+                let stamps = interface.vault.stamps();
+                Ok((interface.rotate(new_password)?, stamps))
+            }
+            // replaces any earlier recovery key, so an empty vault gets written out to fix its key
+            Message::CreateRecovery(password) => {
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
+                if !interface.vault_file.borrow().exists() {
+                    interface.save()?;
+                }
+                let (recovery, recovery_key) = Recovery::generate(interface.key)?;
+                interface.save_dir.recovery_file().write(&recovery)?;
+                Ok((Output::RecoveryKey(recovery_key), interface.vault.stamps()))
             }
             Message::Restore(password, backup_password, backup_file) => {
                 let backup_vault_enc = backup_file.read()?.deserialize();
                 let backup_key = backup_vault_enc.key(backup_password);
-                let _backup_vault = backup_vault_enc.decrypt(backup_key)?.deserialize();
+                let _backup_vault = Vault::from_decrypted(&backup_vault_enc.decrypt(backup_key)?);
 
-                let mut interface = Self::load_interface(password, save_dir)?;
+                let mut interface = Self::load_interface(password, save_dir, settings)?;
 
                 // have proved that the user knows the backup's and current vault's password and
                 // the decryption of both, so make a backup of the current vault and then copy in
                 // the old vault as the current vault
                 let new_backup = interface.backup()?;
 
+                interface.carry_recovery(backup_key)?;
                 interface.vault_encrypted = backup_vault_enc;
                 interface.key = backup_key;
                 interface.save()?;
-                Ok(Output::Backup(new_backup))
+                Ok((Output::Backup(new_backup), interface.vault.stamps()))
             }
             _ => panic!("Should have been caught by handler"),
         }
     }
 
-    fn load_interface(password: Password, save_dir: SaveDir) -> anyhow::Result<Self> {
-        let mut interface = Self::get_interface(password, save_dir)?;
+    fn load_interface(
+        password: Password,
+        save_dir: SaveDir,
+        settings: KeySettings,
+    ) -> anyhow::Result<Self> {
+        let mut interface = Self::get_interface(password.clone(), save_dir, settings)?;
         interface.check_unfinished()?;
+        if settings.reharden && !interface.vault_encrypted.params.at_least(&settings.params) {
+            interface.rekey(password)?;
+        }
         Ok(interface)
     }
 
-    fn get_interface(password: Password, save_dir: SaveDir) -> anyhow::Result<Self> {
+    fn get_interface(
+        password: Password,
+        save_dir: SaveDir,
+        settings: KeySettings,
+    ) -> anyhow::Result<Self> {
         let vault_file = save_dir.vault_file();
-        let record_file = save_dir.record_file();
-        let schema_file = save_dir.schema_file();
-        // let schema = Self::get_schema();
-        let record = RecordEncrypted::new(password.clone())?;
-        let (vault, key, vault_encrypted) = if vault_file.exists() {
-            let vault_encrypted = vault_file.read()?.deserialize();
-            let key = vault_encrypted.key(password);
-            let vault = vault_encrypted.decrypt(key)?.deserialize();
-            (vault, key, vault_encrypted)
+        if vault_file.exists() {
+            let key = vault_file.read()?.deserialize().key(password);
+            Self::open_interface(key, save_dir, settings)
         } else {
+            let record_file = save_dir.record_file();
+            let schema_file = save_dir.schema_file();
             let vault = Vault::new();
             let salt = SaltString::generate(&mut OsRng);
-            let key = VaultEncrypted::get_key(salt.as_str(), password);
-            let vault_encrypted = VaultEncrypted::from_vault(salt.to_string(), key, &vault)?;
-            (vault, key, vault_encrypted)
-        };
+            let key = VaultEncrypted::get_key(salt.as_str(), &settings.params, password);
+            let vault_encrypted =
+                VaultEncrypted::from_vault(salt.to_string(), settings.params, key, &vault)?;
+            let record = RecordEncrypted::with_key(&vault_encrypted, key)?;
+            Ok(Self {
+                vault,
+                settings,
+                vault_encrypted,
+                key,
+                record,
+                save_dir,
+                vault_file: Rc::new(RefCell::new(vault_file)),
+                record_file: Rc::new(RefCell::new(record_file)),
+                schema_file: Rc::new(RefCell::new(schema_file)),
+            })
+        }
+    }
+
+    // an existing vault from its key, however the key was found
+    fn open_interface(
+        key: Key<Aes256Gcm>,
+        save_dir: SaveDir,
+        settings: KeySettings,
+    ) -> anyhow::Result<Self> {
+        let vault_file = save_dir.vault_file();
+        let record_file = save_dir.record_file();
+        let schema_file = save_dir.schema_file();
+        let vault_encrypted: VaultEncrypted = vault_file.read()?.deserialize();
+        let vault = Vault::from_decrypted(&vault_encrypted.decrypt(key)?);
+        let record = RecordEncrypted::with_key(&vault_encrypted, key)?;
 
         Ok(Self {
             vault,
+            settings,
             vault_encrypted,
             key,
             // schema,
@@ -212,10 +462,37 @@ impl VaultHandler {
         Ok(())
     }
 
+    fn rotate(mut self, new_password: Password) -> anyhow::Result<Output> {
+        let backup = self.backup()?;
+        self.rekey(new_password)?;
+        Ok(Output::Backup(backup))
+    }
+
+    // a fresh salt and key from the password using the configured costs
+    fn rekey(&mut self, password: Password) -> anyhow::Result<()> {
+        let new_vault = VaultEncrypted::new(password.clone(), self.settings.params)?;
+        let key = new_vault.key(password);
+        self.carry_recovery(key)?;
+        self.vault_encrypted = new_vault;
+        self.key = key;
+        self.save()
+    }
+
+    // keeps an existing recovery key working for the vault's next key
+    fn carry_recovery(&self, key: Key<Aes256Gcm>) -> anyhow::Result<()> {
+        let mut recovery_file = self.save_dir.recovery_file();
+        if recovery_file.exists() {
+            let recovery = recovery_file.read()?.deserialize().rewrap(self.key, key)?;
+            recovery_file.write(&recovery)?;
+        }
+        Ok(())
+    }
+
     fn backup(&self) -> anyhow::Result<BackupFile> {
         let mut backup_file = self.save_dir.backup_file();
         let backup = VaultEncrypted {
             salt: self.vault_encrypted.salt.clone(),
+            params: self.vault_encrypted.params,
             data: Encrypted::encrypt(&self.vault, self.key)?,
         };
         backup_file.write(&backup)?;
@@ -223,13 +500,14 @@ impl VaultHandler {
     }
 
     fn transaction(&mut self, commands: Commands) -> anyhow::Result<Reads<Store>> {
-        let (reads, record) = self.vault.transaction(commands);
+        let (mut reads, record) = self.vault.transaction(commands);
         self.record.update(&record, self.key)?;
 
         self.record_file.borrow_mut().write(&self.record)?;
         self.vault.apply_record(record);
         self.save()?;
         self.record_file.borrow_mut().delete()?;
+        self.vault.stamp(&mut reads);
         Ok(reads)
     }
 }
@@ -1,45 +1,272 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
 use crate::{
+    archive::{self, Collision},
+    audit::{self, AuditEvent},
+    breach,
     config::{
         internal_config::{BaseConfig, InternalConfig},
         manager_config::ManagerConfig,
     },
-    errors::ManagerError,
+    errors::{CommunicationError, DecryptionError, ManagerError, VaultNameError},
+    generate::GenSpec,
     info::Info,
-    manager_message::ManagerMessage,
+    manager_message::{ManagerMessage, PROTOCOL_VERSION},
     message::Message,
     output::Output,
-    utils,
+    reuse,
+    secure::SecureData,
+    store::Store,
+    strength, utils, Password,
 };
 
-use super::interface::VaultInterface;
+use super::{interface::VaultInterface, Stamps};
+
+// the longest a vault is locked out for, however many wrong passwords there were
+const MAX_LOCKOUT: Duration = Duration::from_secs(60 * 60);
 
 pub struct VaultManager {
     config: ManagerConfig,
+    // only kept for as long as the manager is running
+    failures: BTreeMap<String, Failures>,
+    // the entries' timestamps of the vaults unlocked since the manager started, they are only
+    // written down encrypted in the vaults
+    stamps: BTreeMap<String, Stamps>,
+}
+
+// wrong passwords in a row for a vault and when it can be tried again
+#[derive(Debug, Default)]
+struct Failures {
+    count: u32,
+    until: Option<Instant>,
 }
 
 impl Default for VaultManager {
     fn default() -> Self {
         let config = <ManagerConfig as BaseConfig>::load_err();
 
-        Self { config }
+        Self {
+            config,
+            failures: BTreeMap::new(),
+            stamps: BTreeMap::new(),
+        }
     }
 }
 
 impl VaultManager {
     pub fn new() -> anyhow::Result<Self> {
-        <ManagerConfig as BaseConfig>::load().map(|config| Self { config })
+        <ManagerConfig as BaseConfig>::load().and_then(Self::from_config)
+    }
+
+    // bad key derivation costs would only show up once a key is made, so catch them up front
+    fn from_config(config: ManagerConfig) -> anyhow::Result<Self> {
+        config
+            .kdf
+            .argon2()
+            .map_err(|e| ManagerError::InvalidKdf(e.to_string()))?;
+        Ok(Self {
+            config,
+            failures: BTreeMap::new(),
+            stamps: BTreeMap::new(),
+        })
+    }
+
+    fn interface(&self, path: &Path) -> VaultInterface {
+        VaultInterface::new(path.to_path_buf()).with_kdf(self.config.kdf)
+    }
+
+    fn remember_stamps(&mut self, vault: &str, interface: &VaultInterface) {
+        if let Some(stamps) = interface.stamps() {
+            self.stamps.insert(vault.to_string(), stamps);
+        }
+    }
+
+    // keep the vaults somewhere other than the usual data directory, e.g. to run an isolated
+    // instance
+    pub fn with_dir(dir: Option<PathBuf>) -> anyhow::Result<Self> {
+        match dir {
+            None => Self::new(),
+            Some(dir) => {
+                if !dir.is_absolute() {
+                    return Err(ManagerError::InvalidDataDir(format!(
+                        "{} is not an absolute path",
+                        dir.display()
+                    ))
+                    .into());
+                }
+                if dir.exists() && !dir.is_dir() {
+                    return Err(ManagerError::InvalidDataDir(format!(
+                        "{} is not a directory",
+                        dir.display()
+                    ))
+                    .into());
+                }
+                ManagerConfig::load_dir(dir).and_then(Self::from_config)
+            }
+        }
+    }
+
+    fn scheduled_backup(
+        &mut self,
+        vault: String,
+        password: Password,
+        dir: PathBuf,
+        keep: usize,
+    ) -> anyhow::Result<Output> {
+        let path = self
+            .config
+            .map
+            .get(&vault)
+            .ok_or(ManagerError::VaultDoesNotExist)?;
+        let interface = self.interface(path);
+        let contents = BTreeMap::from([(
+            vault.clone(),
+            self.try_unlock(&vault, || interface.vault(password.clone()))?,
+        )]);
+        fs::create_dir_all(&dir)?;
+        let file = archive::timestamped_path(&dir, &vault);
+        archive::write(&file, &contents, password.clone(), self.config.kdf)?;
+        // a broken backup shouldn't push out the good ones, or be left around to restore from
+        if let Err(e) = archive::verify(&file, password, &[vault.clone()]) {
+            let _ = fs::remove_file(&file);
+            return Err(e.into());
+        }
+        archive::rotate(&dir, &vault, keep)?;
+        Ok(Output::Archive(file, vec![vault]))
+    }
+
+    // the first of `name (restored)`, `name (restored 2)`, ... that isn't a vault yet
+    fn unused_name(&self, name: &str) -> String {
+        let mut candidate = format!("{} (restored)", name);
+        let mut i = 2;
+        while self.config.map.contains_key(&candidate) {
+            candidate = format!("{} (restored {})", name, i);
+            i += 1;
+        }
+        candidate
     }
 
+    // collisions with existing entries are skipped unless overwriting, gives back how many were
+    // imported and the keys that were skipped
+    fn import(
+        &mut self,
+        vault: &str,
+        password: Password,
+        entries: Vec<(String, Store)>,
+        overwrite: bool,
+    ) -> anyhow::Result<(usize, Vec<String>)> {
+        let path = self
+            .config
+            .map
+            .get(vault)
+            .ok_or(ManagerError::VaultDoesNotExist)?;
+        let mut interface = self.interface(path);
+        let schema = match interface.receive(Message::Schema)? {
+            Output::Schema(schema) => schema,
+            _ => return Err(CommunicationError::UnexpectedOutput.into()),
+        };
+        let (entries, skipped): (Vec<_>, Vec<_>) = entries
+            .into_iter()
+            .partition(|(key, _)| overwrite || schema.get(key).is_none());
+        let skipped = skipped.into_iter().map(|(key, _)| key).collect();
+        let imported = entries.len();
+        let message = Message::UpdateMany(password, entries);
+        self.try_unlock(vault, || interface.receive(message.clone()))?;
+        self.remember_stamps(vault, &interface);
+        Ok((imported, skipped))
+    }
+
+    // failing to write the log doesn't stop the action
     pub fn receive(&mut self, message: ManagerMessage) -> anyhow::Result<Output> {
+        let audit = if self.config.audit_log {
+            audit::describe(&message)
+        } else {
+            None
+        };
+        let result = self.handle(message);
+        if let Some((action, vault, key)) = audit {
+            let error = match &result {
+                Err(e) => Some(e.to_string()),
+                Ok(Output::Error(e)) => Some(e.clone()),
+                Ok(_) => None,
+            };
+            let event = AuditEvent {
+                time: utils::format_date(utils::now()),
+                action,
+                vault,
+                key,
+                error,
+            };
+            let _ = audit::append(&self.config.audit_path(), &event);
+        }
+        result
+    }
+
+    // how much longer the vault can't be tried
+    fn locked_out(&self, vault: &str) -> Option<Duration> {
+        let until = self.failures.get(vault)?.until?;
+        until.checked_duration_since(Instant::now())
+    }
+
+    // everything that decrypts a vault goes through here, before a wrong password is turned into
+    // a message, so a wrong one counts towards a lockout and only getting in starts the count over
+    fn try_unlock<T>(
+        &mut self,
+        vault: &str,
+        unlock: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        if let Some(remaining) = self.locked_out(vault) {
+            return Err(ManagerError::LockedOut(remaining.as_secs() + 1).into());
+        }
+        let result = unlock();
+        match &result {
+            Ok(_) => {
+                self.failures.remove(vault);
+            }
+            Err(e) if e.is::<DecryptionError>() => self.count_failure(vault),
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn count_failure(&mut self, vault: &str) {
+        let attempts = self.config.unlock_attempts;
+        let failures = self.failures.entry(vault.to_string()).or_default();
+        failures.count += 1;
+        if attempts > 0 && failures.count >= attempts {
+            let doublings = (failures.count - attempts).min(16);
+            let lockout = Duration::from_secs(self.config.lockout_seconds)
+                .saturating_mul(1 << doublings)
+                .min(MAX_LOCKOUT);
+            failures.until = Some(Instant::now() + lockout);
+        }
+    }
+
+    fn handle(&mut self, message: ManagerMessage) -> anyhow::Result<Output> {
         match message {
             ManagerMessage::Empty => Ok(().into()),
+            ManagerMessage::Handshake(version) if version == PROTOCOL_VERSION => Ok(().into()),
+            ManagerMessage::Handshake(version) => {
+                Ok(Output::ProtocolMismatch(version, PROTOCOL_VERSION))
+            }
+            ManagerMessage::Version => Ok(Output::Version(env!("CARGO_PKG_VERSION").to_string())),
+            ManagerMessage::AuditLog(count) => Ok(Output::Audit(audit::recent(
+                &self.config.audit_path(),
+                count,
+            )?)),
             ManagerMessage::NewVault(name) => {
+                let name = utils::vault_name(&name)?;
+                let path = self.config.vault_path(&name)?;
                 if let std::collections::btree_map::Entry::Vacant(e) =
                     self.config.map.entry(name.clone())
                 {
-                    let mut path = utils::base_path();
-                    path.push(name.clone());
-                    e.insert(path.to_str().unwrap().into());
+                    e.insert(path);
                     self.config.save()?;
                     Ok(().into())
                 } else {
@@ -48,9 +275,10 @@ impl VaultManager {
             }
             ManagerMessage::DeleteVault(name, password) => {
                 if let Some(path) = self.config.map.get(&name) {
-                    let interface = VaultInterface::new(path.to_path_buf());
-                    interface.delete(password)?;
+                    let interface = self.interface(path);
+                    self.try_unlock(&name, || interface.delete(password))?;
                     self.config.map.remove(&name);
+                    self.stamps.remove(&name);
                     self.config.save()?;
                     Ok(().into())
                 } else {
@@ -59,9 +287,32 @@ impl VaultManager {
             }
             ManagerMessage::DeleteEmptyVault(name) => {
                 if let Some(path) = self.config.map.get(&name) {
-                    let interface = VaultInterface::new(path.to_path_buf());
+                    let interface = self.interface(path);
                     interface.delete_empty()?;
                     self.config.map.remove(&name);
+                    self.stamps.remove(&name);
+                    self.config.save()?;
+                    Ok(().into())
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::RenameVault { old, new } => {
+                let new = utils::vault_name(&new)?;
+                if self.config.map.contains_key(&new) {
+                    return Err(ManagerError::VaultExists.into());
+                }
+                let new_path = self.config.vault_path(&new)?;
+                if let Some(path) = self.config.map.remove(&old) {
+                    let interface = self.interface(&path);
+                    if let Err(e) = interface.rename(&new_path) {
+                        self.config.map.insert(old, path);
+                        return Err(e);
+                    }
+                    if let Some(stamps) = self.stamps.remove(&old) {
+                        self.stamps.insert(new.clone(), stamps);
+                    }
+                    self.config.map.insert(new, new_path);
                     self.config.save()?;
                     Ok(().into())
                 } else {
@@ -70,8 +321,357 @@ impl VaultManager {
             }
             ManagerMessage::VaultMessage(name, message) => {
                 if let Some(path) = self.config.map.get(&name) {
-                    let interface = VaultInterface::new(path.to_path_buf());
-                    interface.receive(message)
+                    let mut interface = self.interface(path);
+                    let result = if decrypts(&message) {
+                        self.try_unlock(&name, || interface.receive(message.clone()))
+                    } else {
+                        interface.receive(message.clone())
+                    };
+                    let output = match result {
+                        // a wrong password is expected, so it's an answer rather than a failure
+                        Err(e) if e.is::<DecryptionError>() => {
+                            return Ok(Output::Error(DecryptionError::InvalidPassword.to_string()))
+                        }
+                        res => res?,
+                    };
+                    interface.track(&message)?;
+                    self.remember_stamps(&name, &interface);
+                    Ok(output)
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::ChangePassword { vault, old, new } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    match self.try_unlock(&vault, || interface.receive(Message::Rotate(old, new))) {
+                        // nothing gets written when the old password can't decrypt the vault
+                        Err(e) if e.is::<DecryptionError>() => Ok(Output::Error(format!(
+                            "Incorrect password for {}, password was not changed",
+                            vault
+                        ))),
+                        res => res,
+                    }
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::Recover {
+                vault,
+                recovery,
+                new,
+            } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    match self.try_unlock(&vault, || {
+                        interface.receive(Message::Recover(recovery, new))
+                    }) {
+                        Err(e) if e.is::<DecryptionError>() => Ok(Output::Error(format!(
+                            "Incorrect recovery key for {}, password was not changed",
+                            vault
+                        ))),
+                        res => res,
+                    }
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::Reharden(vault) => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    interface.set_reharden()?;
+                    Ok(().into())
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::SetPasswordSpec { vault, spec } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    if let Some(spec) = &spec {
+                        GenSpec::from_str(spec)?;
+                    }
+                    let mut interface = self.interface(path);
+                    interface.set_password_spec(spec)?;
+                    Ok(().into())
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::SetFavorite {
+                vault,
+                key,
+                favorite,
+            } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    interface.set_favorite(key, favorite)?;
+                    Ok(().into())
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::SetTags { vault, key, tags } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    interface.set_tags(key, tags)?;
+                    Ok(().into())
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::SetOrder { vault, keys } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    interface.set_order(keys)?;
+                    Ok(().into())
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::Export {
+                vault,
+                password,
+                format,
+            } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    match self
+                        .try_unlock(&vault, || interface.receive(Message::GetAll(password)))?
+                    {
+                        Output::Read(reads) => Ok(Output::Export(vault, format.export(reads)?)),
+                        _ => Err(CommunicationError::UnexpectedOutput.into()),
+                    }
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::Import {
+                vault,
+                password,
+                entries,
+                overwrite,
+            } => {
+                let (imported, skipped) = self.import(&vault, password, entries, overwrite)?;
+                Ok(Output::Import(imported, skipped))
+            }
+            ManagerMessage::ImportVaults {
+                password,
+                vaults,
+                overwrite,
+            } => {
+                // checked up front so a bad name doesn't leave half the vaults created
+                let vaults = vaults
+                    .into_iter()
+                    .map(|(vault, entries)| Ok((utils::vault_name(&vault)?, entries)))
+                    .collect::<Result<Vec<_>, VaultNameError>>()?;
+                let mut imported = 0;
+                let mut skipped = vec![];
+                for (vault, entries) in vaults {
+                    if !self.config.map.contains_key(&vault) {
+                        self.receive(ManagerMessage::NewVault(vault.clone()))?;
+                    }
+                    let (count, keys) =
+                        self.import(&vault, password.clone(), entries, overwrite)?;
+                    imported += count;
+                    skipped.extend(keys.into_iter().map(|key| format!("{}: {}", vault, key)));
+                }
+                Ok(Output::Import(imported, skipped))
+            }
+            ManagerMessage::BackupExport {
+                vaults,
+                archive_password,
+                path,
+            } => {
+                let mut contents = BTreeMap::new();
+                for (name, password) in vaults {
+                    let dir = self
+                        .config
+                        .map
+                        .get(&name)
+                        .ok_or(ManagerError::VaultDoesNotExist)?;
+                    let interface = self.interface(dir);
+                    let vault = self.try_unlock(&name, || interface.vault(password))?;
+                    contents.insert(name, vault);
+                }
+                archive::write(&path, &contents, archive_password, self.config.kdf)?;
+                Ok(Output::Archive(path, contents.into_keys().collect()))
+            }
+            ManagerMessage::ScheduledBackup {
+                vault,
+                password,
+                dir,
+                interval_hours,
+                keep,
+            } => {
+                let dir = dir.unwrap_or_else(|| self.config.backup_dir());
+                if interval_hours == 0 || !archive::backup_due(&dir, &vault, interval_hours) {
+                    return Ok(Output::Nothing);
+                }
+                match self.scheduled_backup(vault, password, dir, keep) {
+                    Ok(output) => Ok(output),
+                    // the message it came along with reports the wrong password or the lockout
+                    Err(e)
+                        if e.is::<DecryptionError>()
+                            || matches!(
+                                e.downcast_ref::<ManagerError>(),
+                                Some(ManagerError::LockedOut(_))
+                            ) =>
+                    {
+                        Ok(Output::Nothing)
+                    }
+                    Err(e) => Ok(Output::Error(format!("Automatic backup failed: {}", e))),
+                }
+            }
+            ManagerMessage::BackupImport {
+                path,
+                password,
+                archive_password,
+                collision,
+            } => {
+                // a bad file or password is reported back rather than failing the manager
+                let archive = match archive::read(&path) {
+                    Ok(archive) => archive,
+                    Err(e) => return Ok(Output::Error(e.to_string())),
+                };
+                let Some(vaults) = archive
+                    .decrypt(archive.key(archive_password))
+                    .ok()
+                    .and_then(|decrypted| archive::contents(&decrypted))
+                else {
+                    return Ok(Output::Error(
+                        "Wrong password for the archive or it is corrupted".to_string(),
+                    ));
+                };
+                // every vault is checked before anything is written, so a bad name or a wrong
+                // password for one of them doesn't leave the restore half done
+                for name in vaults.keys() {
+                    utils::vault_name(name)?;
+                    if collision == Collision::Rename {
+                        continue;
+                    }
+                    if let Some(interface) =
+                        self.config.map.get(name).map(|path| self.interface(path))
+                    {
+                        self.try_unlock(name, || interface.vault(password.clone()))?;
+                    }
+                }
+                let mut imported = 0;
+                let mut skipped = vec![];
+                for (name, vault) in vaults {
+                    let exists = self.config.map.contains_key(&name);
+                    let name = if exists && collision == Collision::Rename {
+                        self.unused_name(&name)
+                    } else {
+                        name
+                    };
+                    if !self.config.map.contains_key(&name) {
+                        self.receive(ManagerMessage::NewVault(name.clone()))?;
+                    }
+                    let (count, keys) = self.import(
+                        &name,
+                        password.clone(),
+                        vault.entries(),
+                        collision == Collision::Overwrite,
+                    )?;
+                    imported += count;
+                    skipped.extend(keys.into_iter().map(|key| format!("{}: {}", name, key)));
+                }
+                Ok(Output::Import(imported, skipped))
+            }
+            ManagerMessage::Regenerate {
+                vault,
+                password,
+                keys,
+                spec,
+            } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let spec = GenSpec::from_str(&spec)?;
+                    let mut interface = self.interface(path);
+                    let mut reads = match self.try_unlock(&vault, || {
+                        interface.receive(Message::GetAll(password.clone()))
+                    })? {
+                        Output::Read(reads) => reads,
+                        _ => return Err(CommunicationError::UnexpectedOutput.into()),
+                    };
+                    let mut updates = vec![];
+                    let mut skipped = vec![];
+                    for key in keys {
+                        let Some(store) = reads.remove(&key) else {
+                            skipped.push(key);
+                            continue;
+                        };
+                        let custom = store.custom_fields();
+                        let (choice, mut fields) = store.split();
+                        if !fields.contains_key("password") {
+                            skipped.push(key);
+                            continue;
+                        }
+                        fields.insert("password".to_string(), spec.generate()?.into());
+                        updates.push((key, choice.convert(&fields)?.with_fields(custom)));
+                    }
+                    let regenerated = updates.len();
+                    // all in one transaction so a failure doesn't leave only some of them changed
+                    if !updates.is_empty() {
+                        let message = Message::UpdateMany(password, updates);
+                        interface.receive(message.clone())?;
+                        interface.track(&message)?;
+                    }
+                    // the update stamped the regenerated entries as modified
+                    self.remember_stamps(&vault, &interface);
+                    Ok(Output::Regenerated(regenerated, skipped))
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::CheckBreach {
+                vault,
+                key,
+                password,
+            } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    match self.try_unlock(&vault, || {
+                        interface.receive(Message::Get(password, key.clone()))
+                    })? {
+                        Output::Read(reads) => {
+                            let value = reads
+                                .data
+                                .get(&key)
+                                .ok_or(CommunicationError::NoEntry)?
+                                .as_hash();
+                            match value.get("password") {
+                                Some(p) => Ok(Output::PasswordHash(vault, key, breach::hash(p))),
+                                None => Ok(Output::Error(format!("{} has no password", key))),
+                            }
+                        }
+                        _ => Err(CommunicationError::UnexpectedOutput.into()),
+                    }
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::CheckReuse { vault, password } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    match self
+                        .try_unlock(&vault, || interface.receive(Message::GetAll(password)))?
+                    {
+                        Output::Read(reads) => Ok(Output::Reused(vault, reuse::reused(reads))),
+                        _ => Err(CommunicationError::UnexpectedOutput.into()),
+                    }
+                } else {
+                    Err(ManagerError::VaultDoesNotExist.into())
+                }
+            }
+            ManagerMessage::CheckStrength { vault, password } => {
+                if let Some(path) = self.config.map.get(&vault) {
+                    let mut interface = self.interface(path);
+                    match self
+                        .try_unlock(&vault, || interface.receive(Message::GetAll(password)))?
+                    {
+                        Output::Read(reads) => Ok(Output::Weak(vault, strength::weak(reads))),
+                        _ => Err(CommunicationError::UnexpectedOutput.into()),
+                    }
                 } else {
                     Err(ManagerError::VaultDoesNotExist.into())
                 }
@@ -86,13 +686,134 @@ impl VaultManager {
             ManagerMessage::Info => {
                 let mut info = Info::default();
                 for (name, path) in &self.config.map {
-                    let interface = VaultInterface::new(path.to_path_buf());
+                    let mut interface = self.interface(path);
                     if let Ok(Output::Schema(schema)) = interface.receive(Message::Schema) {
                         info.insert(name.to_string(), schema);
                     }
+                    if let Some(spec) = interface.password_spec() {
+                        info.specs.insert(name.to_string(), spec);
+                    }
+                    if let Some(stamps) = self.stamps.get(name) {
+                        info.used.insert(name.to_string(), stamps.used.clone());
+                        info.modified
+                            .insert(name.to_string(), stamps.modified.clone());
+                    }
+                    info.order.insert(name.to_string(), interface.order());
+                    let tags = interface.tags();
+                    if !tags.is_empty() {
+                        info.tags.insert(name.to_string(), tags);
+                    }
+                    let favorites = interface.favorites();
+                    if !favorites.is_empty() {
+                        info.favorites.insert(name.to_string(), favorites);
+                    }
                 }
                 Ok(info.into())
             }
         }
     }
 }
+
+// everything but the schema and the list of backups needs the vault's password
+fn decrypts(message: &Message) -> bool {
+    !matches!(message, Message::Schema | Message::BackupList)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(attempts: u32, seconds: u64) -> VaultManager {
+        let mut config = ManagerConfig::default();
+        config.unlock_attempts = attempts;
+        config.lockout_seconds = seconds;
+        VaultManager {
+            config,
+            failures: BTreeMap::new(),
+            stamps: BTreeMap::new(),
+        }
+    }
+
+    fn wrong(manager: &mut VaultManager) -> anyhow::Result<()> {
+        manager.try_unlock("vault", || Err(DecryptionError::InvalidPassword.into()))
+    }
+
+    // within a few seconds of the expected lockout, it only counts down from there
+    fn assert_locked_for(manager: &VaultManager, seconds: u64) {
+        let remaining = manager.locked_out("vault").unwrap();
+        assert!(remaining <= Duration::from_secs(seconds));
+        assert!(remaining > Duration::from_secs(seconds - 5));
+    }
+
+    #[test]
+    fn locked_out_at_threshold() {
+        let mut manager = manager(3, 30);
+        wrong(&mut manager).unwrap_err();
+        wrong(&mut manager).unwrap_err();
+        assert!(manager.locked_out("vault").is_none());
+        wrong(&mut manager).unwrap_err();
+        assert_locked_for(&manager, 30);
+
+        let mut called = false;
+        let e = manager
+            .try_unlock("vault", || {
+                called = true;
+                Ok(())
+            })
+            .unwrap_err();
+        assert!(!called);
+        assert!(matches!(
+            e.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut(_))
+        ));
+    }
+
+    #[test]
+    fn lockout_doubles() {
+        let mut manager = manager(3, 30);
+        for _ in 0..3 {
+            manager.count_failure("vault");
+        }
+        assert_locked_for(&manager, 30);
+        manager.count_failure("vault");
+        assert_locked_for(&manager, 60);
+        manager.count_failure("vault");
+        assert_locked_for(&manager, 120);
+        for _ in 0..20 {
+            manager.count_failure("vault");
+        }
+        assert_locked_for(&manager, MAX_LOCKOUT.as_secs());
+    }
+
+    #[test]
+    fn success_resets_count() {
+        let mut manager = manager(3, 30);
+        wrong(&mut manager).unwrap_err();
+        wrong(&mut manager).unwrap_err();
+        manager.try_unlock("vault", || Ok(())).unwrap();
+        assert!(manager.failures.is_empty());
+        wrong(&mut manager).unwrap_err();
+        wrong(&mut manager).unwrap_err();
+        assert!(manager.locked_out("vault").is_none());
+    }
+
+    #[test]
+    fn other_errors_not_counted() {
+        let mut manager = manager(1, 30);
+        manager
+            .try_unlock("vault", || -> anyhow::Result<()> {
+                Err(ManagerError::VaultDoesNotExist.into())
+            })
+            .unwrap_err();
+        assert!(manager.locked_out("vault").is_none());
+    }
+
+    #[test]
+    fn no_lockout_when_disabled() {
+        let mut manager = manager(0, 30);
+        for _ in 0..10 {
+            wrong(&mut manager).unwrap_err();
+        }
+        assert!(manager.locked_out("vault").is_none());
+    }
+}
@@ -1,6 +1,7 @@
 pub mod encrypted;
 pub mod interface;
 pub mod manager;
+pub mod recovery;
 
 use core::str;
 use std::collections::BTreeMap;
@@ -13,12 +14,58 @@ use crate::{
     operation::{Operation, Operations},
     reads::Reads,
     schema::Schema,
+    secure::Decrypted,
     store::Store,
+    utils,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vault {
     data: BTreeMap<String, Store>,
+    // when each entry was last updated and read, kept in here so they are encrypted along with
+    // the entries
+    updated: BTreeMap<String, String>,
+    used: BTreeMap<String, String>,
+}
+
+// vaults written before entries were timestamped
+#[derive(Deserialize)]
+struct LegacyVault {
+    data: BTreeMap<String, Store>,
+}
+
+impl From<LegacyVault> for Vault {
+    fn from(value: LegacyVault) -> Self {
+        Self {
+            data: value.data,
+            updated: BTreeMap::new(),
+            used: BTreeMap::new(),
+        }
+    }
+}
+
+// vaults written before reads were timestamped
+#[derive(Deserialize)]
+pub struct UpdatedVault {
+    data: BTreeMap<String, Store>,
+    updated: BTreeMap<String, String>,
+}
+
+impl From<UpdatedVault> for Vault {
+    fn from(value: UpdatedVault) -> Self {
+        Self {
+            data: value.data,
+            updated: value.updated,
+            used: BTreeMap::new(),
+        }
+    }
+}
+
+// when the entries of a vault were last read and changed, only known once it's been unlocked
+#[derive(Debug, Clone, Default)]
+pub struct Stamps {
+    pub used: BTreeMap<String, String>,
+    pub modified: BTreeMap<String, String>,
 }
 
 // impl Serialize for Vault {
@@ -44,9 +91,29 @@ impl Vault {
     pub fn new() -> Vault {
         Self {
             data: BTreeMap::new(),
+            updated: BTreeMap::new(),
+            used: BTreeMap::new(),
         }
     }
 
+    // the newest layout has to be tried first, reading an older vault as a newer layout fails
+    // while the reverse just ignores the extra bytes
+    pub fn from_decrypted(decrypted: &Decrypted<Vault>) -> Self {
+        decrypted
+            .try_deserialize_as::<Vault>()
+            .or_else(|| {
+                decrypted
+                    .try_deserialize_as::<UpdatedVault>()
+                    .map(Vault::from)
+            })
+            .or_else(|| {
+                decrypted
+                    .try_deserialize_as::<LegacyVault>()
+                    .map(Vault::from)
+            })
+            .unwrap()
+    }
+
     fn step(&self, reads: &mut Reads<Store>, operation: Operation) -> Action {
         match operation {
             Operation::Get { key } => {
@@ -66,6 +133,18 @@ impl Vault {
                     end: value,
                 }
             }
+            Operation::Rename { from, to } => {
+                if self.data.contains_key(&to) {
+                    return Action::Noop;
+                }
+                match self.data.get(&from) {
+                    Some(value) => {
+                        reads.insert(to.clone(), value.clone());
+                        Action::Rename { from, to }
+                    }
+                    None => Action::Noop,
+                }
+            }
         }
     }
 
@@ -85,15 +164,31 @@ impl Vault {
     }
 
     fn apply_action(&mut self, action: Action) {
-        if let Action::Replace { key, start: _, end } = action {
-            match end {
+        match action {
+            Action::Replace { key, start: _, end } => match end {
                 Some(value) => {
+                    self.updated
+                        .insert(key.clone(), utils::format_date(utils::now()));
                     self.data.insert(key, value);
                 }
                 None => {
+                    self.updated.remove(&key);
+                    self.used.remove(&key);
                     self.data.remove(&key);
                 }
+            },
+            Action::Rename { from, to } => {
+                if let Some(value) = self.data.remove(&from) {
+                    if let Some(updated) = self.updated.remove(&from) {
+                        self.updated.insert(to.clone(), updated);
+                    }
+                    if let Some(used) = self.used.remove(&from) {
+                        self.used.insert(to.clone(), used);
+                    }
+                    self.data.insert(to, value);
+                }
             }
+            Action::Noop => {}
         }
     }
 
@@ -103,6 +198,36 @@ impl Vault {
         }
     }
 
+    // fill in when the read entries were updated
+    pub fn stamp(&self, reads: &mut Reads<Store>) {
+        for key in reads.data.keys() {
+            if let Some(updated) = self.updated.get(key) {
+                reads.updated.insert(key.to_string(), updated.to_string());
+            }
+        }
+    }
+
+    // marks the entry as read, written out along with the rest of the vault
+    pub fn touch(&mut self, key: &str) {
+        self.used
+            .insert(key.to_string(), utils::format_date(utils::now()));
+    }
+
+    pub fn stamps(&self) -> Stamps {
+        Stamps {
+            used: self.used.clone(),
+            modified: self.updated.clone(),
+        }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.data.contains_key(key)
+    }
+
+    pub fn entries(self) -> Vec<(String, Store)> {
+        self.data.into_iter().collect()
+    }
+
     pub fn keys(self) -> Vec<String> {
         self.data.into_keys().collect()
     }
@@ -110,7 +235,7 @@ impl Vault {
     pub fn schema(&self) -> Schema {
         let mut schema = Schema::new();
         for (key, value) in &self.data {
-            schema.insert(key.to_string(), value.repr());
+            schema.insert(key.to_string(), value.kind());
         }
         schema
     }
@@ -0,0 +1,95 @@
+use aes_gcm::{Aes256Gcm, Key};
+use argon2::password_hash::SaltString;
+use rand::{rngs::OsRng, RngCore};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::DecryptionError,
+    secure::{Encrypted, KdfParams, SecureData},
+    Password,
+};
+
+use super::encrypted::PasswordEncrypted;
+
+// the vault's key wrapped under the recovery key, along with the recovery key's own key wrapped
+// under the vault's so the recovery key keeps working after the password changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recovery {
+    pub vault_key: PasswordEncrypted<Vec<u8>>,
+    pub recovery_key: Encrypted<Vec<u8>>,
+}
+
+impl Recovery {
+    // the recovery key is only ever handed back from here, nothing stores it in the clear
+    pub fn generate(vault_key: Key<Aes256Gcm>) -> anyhow::Result<(Self, Password)> {
+        let mut bytes = [0u8; 20];
+        OsRng.fill_bytes(&mut bytes);
+        let recovery = format_key(&bytes);
+        let salt = SaltString::generate(&mut OsRng).to_string();
+        // the recovery key is random enough that argon2's defaults are plenty
+        let params = KdfParams::default();
+        let recovery_key =
+            PasswordEncrypted::<Vec<u8>>::get_key(&salt, &params, normalize(&recovery));
+        Ok((Self::wrap(salt, params, recovery_key, vault_key)?, recovery))
+    }
+
+    fn wrap(
+        salt: String,
+        params: KdfParams,
+        recovery_key: Key<Aes256Gcm>,
+        vault_key: Key<Aes256Gcm>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            vault_key: PasswordEncrypted {
+                data: Encrypted::encrypt(&vault_key.to_vec(), recovery_key)?,
+                salt,
+                params,
+            },
+            recovery_key: Encrypted::encrypt(&recovery_key.to_vec(), vault_key)?,
+        })
+    }
+
+    pub fn unlock(&self, recovery: Password) -> Result<Key<Aes256Gcm>, DecryptionError> {
+        let key = self.vault_key.key(normalize(&recovery));
+        let bytes = self.vault_key.data.decrypt(key)?.deserialize();
+        Ok(Key::<Aes256Gcm>::clone_from_slice(&bytes))
+    }
+
+    // the same recovery key for the vault's new key
+    pub fn rewrap(&self, old: Key<Aes256Gcm>, new: Key<Aes256Gcm>) -> anyhow::Result<Self> {
+        let bytes = self.recovery_key.decrypt(old)?.deserialize();
+        let recovery_key = Key::<Aes256Gcm>::clone_from_slice(&bytes);
+        Self::wrap(
+            self.vault_key.salt.clone(),
+            self.vault_key.params,
+            recovery_key,
+            new,
+        )
+    }
+}
+
+// hex in dashed groups of four so it can be written down
+fn format_key(bytes: &[u8]) -> Password {
+    bytes
+        .chunks(2)
+        .map(|pair| {
+            pair.iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+        .into()
+}
+
+// however it was written down, only the hex digits matter
+fn normalize(recovery: &Password) -> Password {
+    recovery
+        .expose_secret()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<String>()
+        .into()
+}